@@ -0,0 +1,104 @@
+package java.io;
+
+public class PrintStream {
+
+  /*
+   * The stream the VM writes to: 1 for standard output and 2 for
+   * standard error, either of which an embedder may redirect.
+   */
+  private final int fd;
+
+  public PrintStream(int fd) {
+    this.fd = fd;
+  }
+
+  private native void write(String s);
+
+  public void print(boolean b) {
+    write(String.valueOf(b));
+  }
+
+  public void print(char c) {
+    write(String.valueOf(c));
+  }
+
+  public void print(int i) {
+    write(String.valueOf(i));
+  }
+
+  public void print(long l) {
+    write(String.valueOf(l));
+  }
+
+  public void print(float f) {
+    write(String.valueOf(f));
+  }
+
+  public void print(double d) {
+    write(String.valueOf(d));
+  }
+
+  public void print(char[] s) {
+    write(new String(s));
+  }
+
+  public void print(String s) {
+    write(String.valueOf(s));
+  }
+
+  public void print(Object obj) {
+    write(String.valueOf(obj));
+  }
+
+  public void println() {
+    write("\n");
+  }
+
+  public void println(boolean x) {
+    print(x);
+    println();
+  }
+
+  public void println(char x) {
+    print(x);
+    println();
+  }
+
+  public void println(int x) {
+    print(x);
+    println();
+  }
+
+  public void println(long x) {
+    print(x);
+    println();
+  }
+
+  public void println(float x) {
+    print(x);
+    println();
+  }
+
+  public void println(double x) {
+    print(x);
+    println();
+  }
+
+  public void println(char[] x) {
+    print(x);
+    println();
+  }
+
+  public void println(String x) {
+    print(x);
+    println();
+  }
+
+  public void println(Object x) {
+    print(x);
+    println();
+  }
+
+  public void flush() {
+  }
+}
@@ -0,0 +1,25 @@
+package java.lang;
+
+public final class Class<T> implements java.io.Serializable {
+
+  // Mirrors are created by the VM, which fills in these fields.
+  private String name;
+  private Class<?> componentType;
+
+  private Class() {
+  }
+
+  public String getName() {
+    return name;
+  }
+
+  public Class<?> getComponentType() {
+    return componentType;
+  }
+
+  public boolean isArray() {
+    return componentType != null;
+  }
+
+  public native boolean desiredAssertionStatus();
+}
@@ -0,0 +1,11 @@
+package java.lang;
+
+public class IndexOutOfBoundsException extends RuntimeException {
+
+  public IndexOutOfBoundsException() {
+  }
+
+  public IndexOutOfBoundsException(String message) {
+    super(message);
+  }
+}
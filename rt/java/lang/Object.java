@@ -0,0 +1,65 @@
+package java.lang;
+
+public class Object {
+
+  public Object() {
+  }
+
+  public final native Class<?> getClass();
+
+  public native int hashCode();
+
+  /*
+   * Waiting and notifying require the calling thread to own the
+   * object's monitor, or throw IllegalMonitorStateException.
+   */
+  public final native void notify();
+
+  public final native void notifyAll();
+
+  public final native void wait(long timeout) throws InterruptedException;
+
+  public final void wait() throws InterruptedException {
+    wait(0);
+  }
+
+  public final void wait(long timeout, int nanos) throws InterruptedException {
+    if (timeout < 0) {
+      throw new IllegalArgumentException("timeout value is negative");
+    }
+    if (nanos < 0 || nanos > 999999) {
+      throw new IllegalArgumentException(
+          "nanosecond timeout value out of range");
+    }
+    if (nanos > 0 && timeout < 0x7fffffffffffffffL) {
+      timeout++;
+    }
+    wait(timeout);
+  }
+
+  public boolean equals(Object obj) {
+    return this == obj;
+  }
+
+  public String toString() {
+    int hash = hashCode();
+    char[] digits = new char[8];
+    int start = digits.length;
+    do {
+      digits[--start] = "0123456789abcdef".charAt(hash & 0xf);
+      hash >>>= 4;
+    } while (hash != 0);
+    char[] hex = new char[digits.length - start];
+    for (int i = 0; i < hex.length; i++) {
+      hex[i] = digits[start + i];
+    }
+    return getClass().getName().concat("@").concat(new String(hex));
+  }
+
+  /*
+   * Called once the collector finds the object unreachable, if its
+   * class overrides this method; exceptions it throws are ignored.
+   */
+  protected void finalize() throws Throwable {
+  }
+}
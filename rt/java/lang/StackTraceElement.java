@@ -0,0 +1,37 @@
+package java.lang;
+
+public final class StackTraceElement implements java.io.Serializable {
+
+  private final String declaringClass;
+  private final String methodName;
+  private final String fileName;
+  private final int lineNumber;
+
+  public StackTraceElement(String declaringClass, String methodName,
+                           String fileName, int lineNumber) {
+    this.declaringClass = declaringClass;
+    this.methodName = methodName;
+    this.fileName = fileName;
+    this.lineNumber = lineNumber;
+  }
+
+  public String getClassName() {
+    return declaringClass;
+  }
+
+  public String getMethodName() {
+    return methodName;
+  }
+
+  public String getFileName() {
+    return fileName;
+  }
+
+  public int getLineNumber() {
+    return lineNumber;
+  }
+
+  public boolean isNativeMethod() {
+    return lineNumber == -2;
+  }
+}
@@ -0,0 +1,135 @@
+package java.lang;
+
+public final class String implements java.io.Serializable {
+
+  /*
+   * Chars are stored one per byte when they all fit in Latin-1,
+   * and otherwise as UTF-16, two bytes per char, high byte first.
+   */
+  static final byte LATIN1 = 0;
+  static final byte UTF16 = 1;
+
+  private final byte[] value;
+  private final byte coder;
+  private int hash;
+
+  public String() {
+    this.value = new byte[0];
+    this.coder = LATIN1;
+  }
+
+  public String(char[] value) {
+    byte coder = LATIN1;
+    for (int i = 0; i < value.length; i++) {
+      if (value[i] > 0xff) {
+        coder = UTF16;
+      }
+    }
+    this.coder = coder;
+    this.value = new byte[value.length << coder];
+    for (int i = 0; i < value.length; i++) {
+      if (coder == LATIN1) {
+        this.value[i] = (byte) value[i];
+      } else {
+        this.value[i << 1] = (byte) (value[i] >> 8);
+        this.value[(i << 1) + 1] = (byte) value[i];
+      }
+    }
+  }
+
+  public int length() {
+    return value.length >> coder;
+  }
+
+  public boolean isEmpty() {
+    return value.length == 0;
+  }
+
+  public char charAt(int index) {
+    if (coder == LATIN1) {
+      return (char) (value[index] & 0xff);
+    }
+    return (char) (((value[index << 1] & 0xff) << 8)
+        | (value[(index << 1) + 1] & 0xff));
+  }
+
+  public boolean equals(Object other) {
+    if (this == other) {
+      return true;
+    }
+    if (!(other instanceof String)) {
+      return false;
+    }
+    String string = (String) other;
+    if (coder != string.coder || value.length != string.value.length) {
+      return false;
+    }
+    for (int i = 0; i < value.length; i++) {
+      if (value[i] != string.value[i]) {
+        return false;
+      }
+    }
+    return true;
+  }
+
+  public int hashCode() {
+    int h = hash;
+    if (h == 0) {
+      for (int i = 0; i < length(); i++) {
+        h = 31 * h + charAt(i);
+      }
+      hash = h;
+    }
+    return h;
+  }
+
+  public String concat(String str) {
+    if (str.isEmpty()) {
+      return this;
+    }
+    char[] chars = new char[length() + str.length()];
+    for (int i = 0; i < length(); i++) {
+      chars[i] = charAt(i);
+    }
+    for (int i = 0; i < str.length(); i++) {
+      chars[length() + i] = str.charAt(i);
+    }
+    return new String(chars);
+  }
+
+  public native String intern();
+
+  public static String valueOf(Object obj) {
+    if (obj == null) {
+      return "null";
+    }
+    return obj.toString();
+  }
+
+  public static String valueOf(char[] data) {
+    return new String(data);
+  }
+
+  public static String valueOf(boolean b) {
+    if (b) {
+      return "true";
+    }
+    return "false";
+  }
+
+  public static String valueOf(char c) {
+    return new String(new char[] {c});
+  }
+
+  public static native String valueOf(int i);
+
+  public static native String valueOf(long l);
+
+  public static native String valueOf(float f);
+
+  public static native String valueOf(double d);
+
+  public String toString() {
+    return this;
+  }
+}
@@ -0,0 +1,85 @@
+package java.lang;
+
+import java.io.PrintStream;
+import java.util.Properties;
+
+public final class System {
+
+  public static final PrintStream out = new PrintStream(1);
+  public static final PrintStream err = new PrintStream(2);
+
+  private static final Properties props = new SystemProperties();
+
+  private System() {
+  }
+
+  public static native void exit(int status);
+
+  // Frees the objects that are no longer reachable.
+  public static native void gc();
+
+  /*
+   * The system properties are held by the VM, which seeds them from
+   * the standard properties and those the launcher or embedder sets.
+   * A key that's null or empty is rejected.
+   */
+  public static native String getProperty(String key);
+
+  public static String getProperty(String key, String def) {
+    String value = getProperty(key);
+    if (value == null) {
+      return def;
+    }
+    return value;
+  }
+
+  public static native String setProperty(String key, String value);
+
+  public static native String clearProperty(String key);
+
+  // Returns a view of the system properties, through which they can be changed.
+  public static Properties getProperties() {
+    return props;
+  }
+
+  // The keys of the system properties, in sorted order.
+  private static native String[] propertyNames();
+
+  public static native String getenv(String name);
+
+  private static final class SystemProperties extends Properties {
+
+    SystemProperties() {
+    }
+
+    public String getProperty(String key) {
+      if (key.isEmpty()) {
+        return null;
+      }
+      return System.getProperty(key);
+    }
+
+    public Object setProperty(String key, String value) {
+      return System.setProperty(key, value);
+    }
+
+    public Object remove(Object key) {
+      if (!(key instanceof String) || ((String) key).isEmpty()) {
+        return null;
+      }
+      return System.clearProperty((String) key);
+    }
+
+    public boolean containsKey(Object key) {
+      return key instanceof String && getProperty((String) key) != null;
+    }
+
+    public int size() {
+      return propertyNames().length;
+    }
+
+    public boolean isEmpty() {
+      return size() == 0;
+    }
+  }
+}
@@ -0,0 +1,191 @@
+package java.lang;
+
+/*
+ * A thread of execution, which the VM runs on a thread of its own
+ * once it's started. The VM keeps the state of running threads,
+ * which it finds by their ids; the main thread's id is 1.
+ */
+public class Thread implements Runnable {
+
+  public static final int MIN_PRIORITY = 1;
+  public static final int NORM_PRIORITY = 5;
+  public static final int MAX_PRIORITY = 10;
+
+  private static int threadInitNumber;
+  private static long threadSeqNumber = 1;
+
+  private final long tid;
+  private volatile String name;
+  private int priority;
+  private boolean daemon;
+  private boolean started;
+  private final Runnable target;
+
+  public Thread() {
+    this(null, nextThreadName());
+  }
+
+  public Thread(Runnable target) {
+    this(target, nextThreadName());
+  }
+
+  public Thread(String name) {
+    this(null, name);
+  }
+
+  public Thread(Runnable target, String name) {
+    if (name == null) {
+      throw new NullPointerException("name cannot be null");
+    }
+    Thread parent = currentThread();
+    this.tid = nextThreadId();
+    this.name = name;
+    this.priority = parent.getPriority();
+    this.daemon = parent.isDaemon();
+    this.target = target;
+  }
+
+  private static synchronized String nextThreadName() {
+    return "Thread-".concat(String.valueOf(threadInitNumber++));
+  }
+
+  private static synchronized long nextThreadId() {
+    return ++threadSeqNumber;
+  }
+
+  public static native Thread currentThread();
+
+  public static native void yield();
+
+  public static native void sleep(long millis) throws InterruptedException;
+
+  public static void sleep(long millis, int nanos)
+      throws InterruptedException {
+    if (millis < 0) {
+      throw new IllegalArgumentException("timeout value is negative");
+    }
+    if (nanos < 0 || nanos > 999999) {
+      throw new IllegalArgumentException(
+          "nanosecond timeout value out of range");
+    }
+    if (nanos >= 500000 || (nanos != 0 && millis == 0)) {
+      millis++;
+    }
+    sleep(millis);
+  }
+
+  public synchronized void start() {
+    if (started) {
+      throw new IllegalThreadStateException();
+    }
+    started = true;
+    start0();
+  }
+
+  private native void start0();
+
+  public void run() {
+    if (target != null) {
+      target.run();
+    }
+  }
+
+  /*
+   * Interrupting a thread that's sleeping, joining or waiting makes it
+   * throw InterruptedException, and one that's parked return; otherwise
+   * the thread's interrupt status is set.
+   */
+  public void interrupt() {
+    interrupt0();
+  }
+
+  private native void interrupt0();
+
+  // Tests whether the current thread has been interrupted, clearing the status.
+  public static boolean interrupted() {
+    return currentThread().isInterrupted(true);
+  }
+
+  public boolean isInterrupted() {
+    return isInterrupted(false);
+  }
+
+  private native boolean isInterrupted(boolean clearInterrupted);
+
+  public final native boolean isAlive();
+
+  /*
+   * Waits for the thread to die, for at most the given number of
+   * milliseconds, or if it's 0, indefinitely.
+   */
+  public final native void join(long millis) throws InterruptedException;
+
+  public final void join(long millis, int nanos)
+      throws InterruptedException {
+    if (millis < 0) {
+      throw new IllegalArgumentException("timeout value is negative");
+    }
+    if (nanos < 0 || nanos > 999999) {
+      throw new IllegalArgumentException(
+          "nanosecond timeout value out of range");
+    }
+    if (nanos >= 500000 || (nanos != 0 && millis == 0)) {
+      millis++;
+    }
+    join(millis);
+  }
+
+  public final void join() throws InterruptedException {
+    join(0);
+  }
+
+  // Whether the current thread owns the monitor of the given object.
+  public static native boolean holdsLock(Object obj);
+
+  public long getId() {
+    return tid;
+  }
+
+  public final String getName() {
+    return name;
+  }
+
+  public final synchronized void setName(String name) {
+    if (name == null) {
+      throw new NullPointerException("name cannot be null");
+    }
+    this.name = name;
+  }
+
+  public final int getPriority() {
+    return priority;
+  }
+
+  // Priorities are recorded, though the VM doesn't act on them.
+  public final void setPriority(int newPriority) {
+    if (newPriority > MAX_PRIORITY || newPriority < MIN_PRIORITY) {
+      throw new IllegalArgumentException();
+    }
+    priority = newPriority;
+  }
+
+  public final boolean isDaemon() {
+    return daemon;
+  }
+
+  /*
+   * The VM exits once every thread that isn't a daemon thread has
+   * died, stopping any daemon threads that are still running.
+   */
+  public final void setDaemon(boolean on) {
+    if (isAlive()) {
+      throw new IllegalThreadStateException();
+    }
+    daemon = on;
+  }
+
+  public String toString() {
+    return "Thread[".concat(name).concat(",")
+        .concat(String.valueOf(priority)).concat(",main]");
+  }
+}
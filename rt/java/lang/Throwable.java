@@ -0,0 +1,59 @@
+package java.lang;
+
+public class Throwable implements java.io.Serializable {
+
+  private String detailMessage;
+  private Throwable cause;
+  // Filled in by the VM when the throwable is created.
+  private StackTraceElement[] stackTrace;
+
+  public Throwable() {
+  }
+
+  public Throwable(String message) {
+    this.detailMessage = message;
+  }
+
+  public Throwable(String message, Throwable cause) {
+    this.detailMessage = message;
+    this.cause = cause;
+  }
+
+  public Throwable(Throwable cause) {
+    this.cause = cause;
+  }
+
+  public String getMessage() {
+    return detailMessage;
+  }
+
+  public String getLocalizedMessage() {
+    return getMessage();
+  }
+
+  public Throwable getCause() {
+    return cause;
+  }
+
+  public Throwable initCause(Throwable cause) {
+    this.cause = cause;
+    return this;
+  }
+
+  public String toString() {
+    String s = getClass().getName();
+    String message = getLocalizedMessage();
+    if (message == null) {
+      return s;
+    }
+    return s.concat(": ").concat(message);
+  }
+
+  public StackTraceElement[] getStackTrace() {
+    return stackTrace;
+  }
+
+  public void setStackTrace(StackTraceElement[] stackTrace) {
+    this.stackTrace = stackTrace;
+  }
+}
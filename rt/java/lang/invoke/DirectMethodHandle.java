@@ -0,0 +1,20 @@
+package java.lang.invoke;
+
+/*
+ * A handle to a field or method, as resolved by the VM
+ * from a MethodHandle constant.
+ */
+class DirectMethodHandle extends MethodHandle {
+
+  private final int referenceKind;
+  private final Class<?> declaringClass;
+  private final String name;
+
+  private DirectMethodHandle(MethodType type, int referenceKind,
+                             Class<?> declaringClass, String name) {
+    super(type);
+    this.referenceKind = referenceKind;
+    this.declaringClass = declaringClass;
+    this.name = name;
+  }
+}
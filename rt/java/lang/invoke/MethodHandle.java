@@ -0,0 +1,14 @@
+package java.lang.invoke;
+
+public abstract class MethodHandle {
+
+  private final MethodType type;
+
+  MethodHandle(MethodType type) {
+    this.type = type;
+  }
+
+  public MethodType type() {
+    return type;
+  }
+}
@@ -0,0 +1,20 @@
+package java.lang.invoke;
+
+public class MethodHandles {
+
+  private MethodHandles() {
+  }
+
+  public static final class Lookup {
+
+    private final Class<?> lookupClass;
+
+    private Lookup(Class<?> lookupClass) {
+      this.lookupClass = lookupClass;
+    }
+
+    public Class<?> lookupClass() {
+      return lookupClass;
+    }
+  }
+}
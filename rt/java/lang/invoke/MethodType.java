@@ -0,0 +1,24 @@
+package java.lang.invoke;
+
+public final class MethodType implements java.io.Serializable {
+
+  private final Class<?> rtype;
+  private final Class<?>[] ptypes;
+
+  private MethodType(Class<?> rtype, Class<?>[] ptypes) {
+    this.rtype = rtype;
+    this.ptypes = ptypes;
+  }
+
+  public Class<?> returnType() {
+    return rtype;
+  }
+
+  public int parameterCount() {
+    return ptypes.length;
+  }
+
+  public Class<?> parameterType(int num) {
+    return ptypes[num];
+  }
+}
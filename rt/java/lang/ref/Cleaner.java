@@ -0,0 +1,79 @@
+package java.lang.ref;
+
+/*
+ * Runs cleaning actions once the objects they're registered for
+ * become phantom reachable. Rather than enqueueing the references
+ * it registers for a thread of its own to poll, the VM cleans them
+ * as soon as the collector clears them.
+ */
+public final class Cleaner {
+
+  public interface Cleanable {
+
+    // Runs the cleaning action, unless it already has been.
+    void clean();
+  }
+
+  /*
+   * The cleanables not yet cleaned, which are kept reachable
+   * until they are, whether or not their cleaner still is.
+   */
+  private static PhantomCleanable registered;
+
+  private Cleaner() {
+  }
+
+  public static Cleaner create() {
+    return new Cleaner();
+  }
+
+  public Cleanable register(Object obj, Runnable action) {
+    if (obj == null) {
+      throw new NullPointerException("obj");
+    }
+    if (action == null) {
+      throw new NullPointerException("action");
+    }
+    return new PhantomCleanable(obj, action);
+  }
+
+  private static final class PhantomCleanable
+      extends PhantomReference<Object> implements Cleanable {
+
+    private Runnable action;
+    private PhantomCleanable previous;
+    private PhantomCleanable next;
+
+    PhantomCleanable(Object referent, Runnable action) {
+      super(referent, null);
+      this.action = action;
+      next = registered;
+      if (next != null) {
+        next.previous = this;
+      }
+      registered = this;
+    }
+
+    public void clean() {
+      Runnable action = this.action;
+      if (action == null) {
+        return;
+      }
+      this.action = null;
+      if (previous == null) {
+        registered = next;
+      } else {
+        previous.next = next;
+      }
+      if (next != null) {
+        next.previous = previous;
+      }
+      clear();
+      action.run();
+    }
+
+    void enqueuePending() {
+      clean();
+    }
+  }
+}
@@ -0,0 +1,17 @@
+package java.lang.ref;
+
+/*
+ * A reference whose referent can't be retrieved, which the collector
+ * clears only once its referent has been finalized, if need be, and
+ * is no longer reachable at all.
+ */
+public class PhantomReference<T> extends Reference<T> {
+
+  public PhantomReference(T referent, ReferenceQueue<? super T> queue) {
+    super(referent, queue);
+  }
+
+  public T get() {
+    return null;
+  }
+}
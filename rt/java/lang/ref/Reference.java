@@ -0,0 +1,62 @@
+package java.lang.ref;
+
+/*
+ * The base class of reference objects, whose referents the collector
+ * doesn't keep alive. Once a referent is no longer strongly reachable,
+ * the collector clears the reference, and the VM then enqueues it on
+ * the queue it was registered with, if any.
+ */
+public abstract class Reference<T> {
+
+  // Cleared by the collector, which finds this field by name.
+  private T referent;
+
+  /*
+   * The queue the reference was registered with, until it's enqueued
+   * on it: ReferenceQueue.NULL if it wasn't registered with one, and
+   * ReferenceQueue.ENQUEUED while it's on the queue.
+   */
+  ReferenceQueue<? super T> queue;
+
+  // The next reference on the queue, or this one if it's the last.
+  Reference<?> next;
+
+  Reference(T referent) {
+    this(referent, null);
+  }
+
+  Reference(T referent, ReferenceQueue<? super T> queue) {
+    this.referent = referent;
+    if (queue == null) {
+      this.queue = (ReferenceQueue<? super T>) ReferenceQueue.NULL;
+    } else {
+      this.queue = queue;
+    }
+  }
+
+  public T get() {
+    return referent;
+  }
+
+  public void clear() {
+    referent = null;
+  }
+
+  public boolean isEnqueued() {
+    return queue == ReferenceQueue.ENQUEUED;
+  }
+
+  // Enqueues the reference as if the collector had cleared it.
+  public boolean enqueue() {
+    referent = null;
+    return queue.enqueue(this);
+  }
+
+  /*
+   * Called by the VM after the collector clears the reference, at
+   * the first point it can run Java code.
+   */
+  void enqueuePending() {
+    queue.enqueue(this);
+  }
+}
@@ -0,0 +1,84 @@
+package java.lang.ref;
+
+/*
+ * The queue the VM enqueues cleared references on, for the program
+ * to poll or wait for.
+ */
+public class ReferenceQueue<T> {
+
+  static final ReferenceQueue<Object> NULL = new Null();
+  static final ReferenceQueue<Object> ENQUEUED = new Null();
+
+  // Rejects references that aren't registered with a queue, or already on one.
+  private static final class Null extends ReferenceQueue<Object> {
+
+    Null() {
+    }
+
+    boolean enqueue(Reference<?> reference) {
+      return false;
+    }
+  }
+
+  private Reference<? extends T> head;
+
+  public ReferenceQueue() {
+  }
+
+  boolean enqueue(Reference<? extends T> reference) {
+    if (reference.queue != this) {
+      return false;
+    }
+    reference.queue = ENQUEUED;
+    if (head == null) {
+      reference.next = reference;
+    } else {
+      reference.next = head;
+    }
+    head = reference;
+    return true;
+  }
+
+  public Reference<? extends T> poll() {
+    Reference<? extends T> reference = head;
+    if (reference == null) {
+      return null;
+    }
+    if (reference.next == reference) {
+      head = null;
+    } else {
+      head = (Reference<? extends T>) reference.next;
+    }
+    reference.queue = NULL;
+    reference.next = null;
+    return reference;
+  }
+
+  /*
+   * Waits for a reference to be enqueued, for at most the given
+   * number of milliseconds, or if it's 0, indefinitely. Rather than
+   * wait for the collector to run, the queue collects garbage itself,
+   * once if there's a timeout, and otherwise until a reference is
+   * enqueued, letting other threads run in between.
+   */
+  public Reference<? extends T> remove(long timeout)
+      throws InterruptedException {
+    if (timeout < 0) {
+      throw new IllegalArgumentException("Negative timeout value");
+    }
+    Reference<? extends T> reference = poll();
+    while (reference == null) {
+      System.gc();
+      reference = poll();
+      if (timeout != 0) {
+        break;
+      }
+      Thread.yield();
+    }
+    return reference;
+  }
+
+  public Reference<? extends T> remove() throws InterruptedException {
+    return remove(0);
+  }
+}
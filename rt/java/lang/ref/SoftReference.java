@@ -0,0 +1,16 @@
+package java.lang.ref;
+
+/*
+ * A reference that the collector clears only when it runs short of
+ * memory, as it does before throwing OutOfMemoryError.
+ */
+public class SoftReference<T> extends Reference<T> {
+
+  public SoftReference(T referent) {
+    super(referent);
+  }
+
+  public SoftReference(T referent, ReferenceQueue<? super T> queue) {
+    super(referent, queue);
+  }
+}
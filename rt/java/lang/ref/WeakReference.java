@@ -0,0 +1,16 @@
+package java.lang.ref;
+
+/*
+ * A reference that the collector clears as soon as its referent is
+ * no longer strongly (or softly) reachable.
+ */
+public class WeakReference<T> extends Reference<T> {
+
+  public WeakReference(T referent) {
+    super(referent);
+  }
+
+  public WeakReference(T referent, ReferenceQueue<? super T> queue) {
+    super(referent, queue);
+  }
+}
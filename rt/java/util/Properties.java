@@ -0,0 +1,103 @@
+package java.util;
+
+/*
+ * A table of string properties. Unlike the JDK's, it isn't a
+ * Hashtable: entries are kept in insertion order in parallel arrays.
+ */
+public class Properties {
+
+  private String[] keys = new String[16];
+  private String[] values = new String[16];
+  private int size;
+
+  protected Properties defaults;
+
+  public Properties() {
+  }
+
+  public Properties(Properties defaults) {
+    this.defaults = defaults;
+  }
+
+  private int indexOf(Object key) {
+    for (int i = 0; i < size; i++) {
+      if (keys[i].equals(key)) {
+        return i;
+      }
+    }
+    return -1;
+  }
+
+  public String getProperty(String key) {
+    int index = indexOf(key);
+    if (index >= 0) {
+      return values[index];
+    }
+    if (defaults == null) {
+      return null;
+    }
+    return defaults.getProperty(key);
+  }
+
+  public String getProperty(String key, String defaultValue) {
+    String value = getProperty(key);
+    if (value == null) {
+      return defaultValue;
+    }
+    return value;
+  }
+
+  public Object setProperty(String key, String value) {
+    if (key == null || value == null) {
+      throw new NullPointerException();
+    }
+    int index = indexOf(key);
+    if (index >= 0) {
+      String previous = values[index];
+      values[index] = value;
+      return previous;
+    }
+    if (size == keys.length) {
+      String[] newKeys = new String[size * 2];
+      String[] newValues = new String[size * 2];
+      for (int i = 0; i < size; i++) {
+        newKeys[i] = keys[i];
+        newValues[i] = values[i];
+      }
+      keys = newKeys;
+      values = newValues;
+    }
+    keys[size] = key;
+    values[size] = value;
+    size++;
+    return null;
+  }
+
+  public Object remove(Object key) {
+    int index = indexOf(key);
+    if (index < 0) {
+      return null;
+    }
+    String previous = values[index];
+    size--;
+    for (int i = index; i < size; i++) {
+      keys[i] = keys[i + 1];
+      values[i] = values[i + 1];
+    }
+    keys[size] = null;
+    values[size] = null;
+    return previous;
+  }
+
+  public boolean containsKey(Object key) {
+    return indexOf(key) >= 0;
+  }
+
+  public int size() {
+    return size;
+  }
+
+  public boolean isEmpty() {
+    return size == 0;
+  }
+}
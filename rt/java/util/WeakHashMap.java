@@ -0,0 +1,168 @@
+package java.util;
+
+import java.lang.ref.ReferenceQueue;
+import java.lang.ref.WeakReference;
+
+/*
+ * A hash table whose keys are weakly referenced, so that an entry
+ * goes away once its key is no longer otherwise reachable. Unlike
+ * the JDK's, it isn't a Map: it offers only the basic operations.
+ */
+public class WeakHashMap<K, V> {
+
+  // Stands in for the null key, which can't be weakly referenced.
+  private static final Object NULL_KEY = new Object();
+
+  private Entry<K, V>[] table = newTable(16);
+  private int size;
+
+  // Where the collector enqueues the entries whose keys it has cleared.
+  private final ReferenceQueue<Object> queue = new ReferenceQueue<Object>();
+
+  private static final class Entry<K, V> extends WeakReference<Object> {
+
+    final int hash;
+    V value;
+    Entry<K, V> next;
+
+    Entry(Object key, int hash, V value, Entry<K, V> next,
+          ReferenceQueue<Object> queue) {
+      super(key, queue);
+      this.hash = hash;
+      this.value = value;
+      this.next = next;
+    }
+  }
+
+  public WeakHashMap() {
+  }
+
+  private static <K, V> Entry<K, V>[] newTable(int length) {
+    return (Entry<K, V>[]) new Entry<?, ?>[length];
+  }
+
+  private static Object maskNull(Object key) {
+    if (key == null) {
+      return NULL_KEY;
+    }
+    return key;
+  }
+
+  private static int hash(Object key) {
+    int hash = key.hashCode();
+    return hash ^ (hash >>> 16);
+  }
+
+  private int indexFor(int hash) {
+    return hash & (table.length - 1);
+  }
+
+  private Entry<K, V> getEntry(Object key) {
+    expungeStaleEntries();
+    Object k = maskNull(key);
+    int hash = hash(k);
+    for (Entry<K, V> e = table[indexFor(hash)]; e != null; e = e.next) {
+      if (e.hash == hash && k.equals(e.get())) {
+        return e;
+      }
+    }
+    return null;
+  }
+
+  public V get(Object key) {
+    Entry<K, V> e = getEntry(key);
+    if (e == null) {
+      return null;
+    }
+    return e.value;
+  }
+
+  public boolean containsKey(Object key) {
+    return getEntry(key) != null;
+  }
+
+  public V put(K key, V value) {
+    Entry<K, V> e = getEntry(key);
+    if (e != null) {
+      V previous = e.value;
+      e.value = value;
+      return previous;
+    }
+    if (size >= table.length * 3 / 4) {
+      resize(table.length * 2);
+    }
+    Object k = maskNull(key);
+    int hash = hash(k);
+    int index = indexFor(hash);
+    table[index] = new Entry<K, V>(k, hash, value, table[index], queue);
+    size++;
+    return null;
+  }
+
+  public V remove(Object key) {
+    Entry<K, V> e = getEntry(key);
+    if (e == null) {
+      return null;
+    }
+    unlink(e);
+    return e.value;
+  }
+
+  public int size() {
+    expungeStaleEntries();
+    return size;
+  }
+
+  public boolean isEmpty() {
+    return size() == 0;
+  }
+
+  public void clear() {
+    while (queue.poll() != null) {
+    }
+    table = newTable(table.length);
+    size = 0;
+  }
+
+  private void resize(int length) {
+    Entry<K, V>[] oldTable = table;
+    table = newTable(length);
+    for (int i = 0; i < oldTable.length; i++) {
+      Entry<K, V> e = oldTable[i];
+      while (e != null) {
+        Entry<K, V> next = e.next;
+        int index = indexFor(e.hash);
+        e.next = table[index];
+        table[index] = e;
+        e = next;
+      }
+    }
+  }
+
+  private void unlink(Entry<K, V> entry) {
+    int index = indexFor(entry.hash);
+    Entry<K, V> previous = null;
+    for (Entry<K, V> e = table[index]; e != null; e = e.next) {
+      if (e == entry) {
+        if (previous == null) {
+          table[index] = e.next;
+        } else {
+          previous.next = e.next;
+        }
+        size--;
+        return;
+      }
+      previous = e;
+    }
+  }
+
+  // Removes the entries the collector has cleared the keys of.
+  private void expungeStaleEntries() {
+    Object cleared;
+    while ((cleared = queue.poll()) != null) {
+      Entry<K, V> e = (Entry<K, V>) cleared;
+      unlink(e);
+      e.value = null;
+    }
+  }
+}
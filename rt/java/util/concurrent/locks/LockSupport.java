@@ -0,0 +1,39 @@
+package java.util.concurrent.locks;
+
+/*
+ * Blocks and unblocks threads, for building locks and other
+ * synchronizers. Each thread has a permit, which unpark makes
+ * available and park consumes, blocking until it's available.
+ */
+public class LockSupport {
+
+  private LockSupport() {
+  }
+
+  public static native void unpark(Thread thread);
+
+  /*
+   * Parks the current thread until its permit is available, it's
+   * interrupted or the given number of nanoseconds (if not 0) pass,
+   * or spuriously; callers should recheck the condition they wait for.
+   */
+  private static native void park0(long nanos);
+
+  public static void park() {
+    park0(0);
+  }
+
+  public static void park(Object blocker) {
+    park0(0);
+  }
+
+  public static void parkNanos(long nanos) {
+    if (nanos > 0) {
+      park0(nanos);
+    }
+  }
+
+  public static void parkNanos(Object blocker, long nanos) {
+    parkNanos(nanos);
+  }
+}
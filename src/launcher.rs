@@ -0,0 +1,567 @@
+use std::collections::{HashMap, VecDeque};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rustedjvm::heap::Collector;
+use rustedjvm::interpreter::{self, Assertions, JvmOptions, Scheduler, Verbose};
+use rustedjvm::jar::JarFile;
+
+pub const USAGE: &str = "\
+Usage: rusted_jvm [options] <mainclass> [args...]
+           (to execute a class)
+   or  rusted_jvm [options] -jar <jarfile> [args...]
+           (to execute a jar file)
+
+ Arguments following the main class or -jar <jarfile> are passed as
+ the arguments to the main method.
+
+ where options include:
+
+    -cp <class search path of directories and zip/jar files>
+    -classpath <class search path of directories and zip/jar files>
+    --class-path <class search path of directories and zip/jar files>
+                  A : separated list of directories and jar files to
+                  search for class files; dir/* stands for every jar
+                  file in dir.
+    -D<name>=<value>
+                  set a system property
+    -verbose:[class|gc]
+                  enable verbose output
+    -version      print product version to the error stream and exit
+    --version     print product version to the output stream and exit
+    -showversion  print product version to the error stream and continue
+    --show-version
+                  print product version to the output stream and continue
+    -? -h -help
+                  print this help message to the error stream
+    --help        print this help message to the output stream
+    -ea[:<packagename>...|:<classname>]
+    -enableassertions[:<packagename>...|:<classname>]
+                  enable assertions with specified granularity
+    -da[:<packagename>...|:<classname>]
+    -disableassertions[:<packagename>...|:<classname>]
+                  disable assertions with specified granularity
+    -esa | -enablesystemassertions
+    -dsa | -disablesystemassertions
+                  accepted for compatibility; the class library
+                  has no assertions
+    -Xms<size>    set initial Java heap size
+    -Xmx<size>    set maximum Java heap size
+    -Xss<size>    set Java thread stack size
+    -Xtrace       dump each class file parsed and trace each method
+                  invoked, for debugging the VM
+    -XX:+UseGenerationalGC
+                  collect garbage with a generational copying collector
+                  instead of marking and sweeping the whole heap
+    -XX:ThreadSchedulerSeed=<seed>
+                  interleave threads deterministically, in the order
+                  chosen by the given seed, so that runs with the same
+                  seed behave the same way
+    -XX:ExploreSchedules=<count>
+                  run the program under the given number of schedules,
+                  with seeds counting up from ThreadSchedulerSeed (or 0),
+                  and report the seeds under which it fails
+    @argument files
+                  one or more argument files containing options
+    --disable-@files
+                  prevent further argument file expansion
+
+Options can also be given in the JDK_JAVA_OPTIONS environment variable.
+";
+
+// The stream to which the launcher prints.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Output {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    Run,
+    Help(Output),
+    // Prints the version and exits.
+    Version(Output),
+}
+
+/*
+ * A launcher command line, as parsed from the options given
+ * in JDK_JAVA_OPTIONS followed by the command line arguments.
+ */
+#[derive(Debug, PartialEq)]
+pub struct Launch {
+    pub action: Action,
+    // Where to print the version before running, if anywhere.
+    pub show_version: Option<Output>,
+    pub class_path: Option<String>,
+    // The main class as given, or with -jar, the jar file.
+    pub main_class: Option<String>,
+    pub jar: bool,
+    pub args: Vec<String>,
+    // In the order given; later definitions override earlier ones.
+    pub system_properties: Vec<(String, String)>,
+    pub stack_size: Option<usize>,
+    pub initial_heap_size: Option<usize>,
+    pub max_heap_size: Option<usize>,
+    pub collector: Collector,
+    pub scheduler: Scheduler,
+    // The number of schedules to explore, if the program is to be.
+    pub explore_schedules: Option<u64>,
+    pub assertions: Assertions,
+    pub verbose: Verbose,
+}
+
+/*
+ * The ways in which a command line can be rejected, along with
+ * what the launcher reports to standard error before exiting.
+ */
+#[derive(Debug, PartialEq)]
+pub enum LaunchError {
+    // Reported, if there's a reason, followed by the usage.
+    Usage(Option<String>),
+    // Reported as is.
+    Fatal(String),
+}
+
+fn usage_error(reason: &str) -> LaunchError {
+    LaunchError::Usage(Some(reason.to_string()))
+}
+
+fn create_vm_error(reason: &str) -> LaunchError {
+    LaunchError::Fatal(format!("{}\nError: Could not create the Java \
+                                Virtual Machine.\nError: A fatal exception \
+                                has occurred. Program will exit.", reason))
+}
+
+/*
+ * A command line argument, which may name an argument file to be
+ * expanded in its place; those read from argument files may not.
+ */
+struct Arg {
+    value: String,
+    may_expand: bool,
+}
+
+impl Launch {
+    /*
+     * Parses a command line (excluding the program name), preceded
+     * by the options in JDK_JAVA_OPTIONS, if it's set.
+     */
+    pub fn parse(args: Vec<String>, jdk_java_options: Option<&str>)
+            -> Result<Launch, LaunchError> {
+        let mut launch = Launch {
+            action: Action::Run,
+            show_version: None,
+            class_path: None,
+            main_class: None,
+            jar: false,
+            args: Vec::new(),
+            system_properties: Vec::new(),
+            stack_size: None,
+            initial_heap_size: None,
+            max_heap_size: None,
+            collector: Collector::default(),
+            scheduler: Scheduler::default(),
+            explore_schedules: None,
+            assertions: Assertions::default(),
+            verbose: Verbose::default(),
+        };
+        let mut expand_arg_files = true;
+
+        if let Some(options) = jdk_java_options {
+            let mut options = tokenize(options).into_iter()
+                .map(|value| Arg { value, may_expand: true }).collect();
+            launch.parse_options(&mut options, &mut expand_arg_files)?;
+            if launch.jar {
+                return Err(usage_error("Option -jar is not allowed in \
+                                        environment variable \
+                                        JDK_JAVA_OPTIONS"));
+            }
+            if launch.main_class.is_some() {
+                return Err(usage_error("Cannot specify main class in \
+                                        environment variable \
+                                        JDK_JAVA_OPTIONS"));
+            }
+        }
+
+        let mut args = args.into_iter()
+            .map(|value| Arg { value, may_expand: true }).collect();
+        launch.parse_options(&mut args, &mut expand_arg_files)?;
+        if launch.action != Action::Run {
+            return Ok(launch);
+        }
+        launch.args = args.into_iter().map(|arg| arg.value).collect();
+
+        if launch.main_class.is_none() {
+            return Err(LaunchError::Usage(None));
+        }
+        if let (Some(initial), Some(max)) = (launch.initial_heap_size,
+                                             launch.max_heap_size) {
+            if initial > max {
+                return Err(LaunchError::Fatal(
+                    "Error occurred during initialization of VM\nInitial \
+                     heap size set to a larger value than the maximum heap \
+                     size".to_string()));
+            }
+        }
+        Ok(launch)
+    }
+
+    /*
+     * Parses options until the main class (or -jar and its jar file)
+     * is found, leaving the arguments that follow it; argument files
+     * are expanded until then, too.
+     */
+    fn parse_options(&mut self, args: &mut VecDeque<Arg>,
+                     expand_arg_files: &mut bool)
+            -> Result<(), LaunchError> {
+        while let Some(arg) = args.pop_front() {
+            if arg.may_expand && *expand_arg_files
+                    && arg.value.starts_with('@') {
+                if arg.value.starts_with("@@") {
+                    args.push_front(Arg { value: arg.value[1 ..].to_string(),
+                                          may_expand: false });
+                    continue;
+                }
+                let path = &arg.value[1 ..];
+                let contents = match fs::read_to_string(path) {
+                    Ok(contents) => contents,
+                    Err(_) => return Err(LaunchError::Fatal(format!(
+                        "Error: could not open `{}'", path))),
+                };
+                for value in tokenize(&contents).into_iter().rev() {
+                    args.push_front(Arg { value, may_expand: false });
+                }
+                continue;
+            }
+
+            let arg = arg.value;
+            let option = arg.as_str();
+            match option {
+                "-cp" | "-classpath" | "--class-path" =>
+                    match args.pop_front() {
+                        Some(class_path) =>
+                            self.class_path = Some(class_path.value),
+                        None => return Err(usage_error(&format!(
+                            "{} requires class path specification",
+                            option))),
+                    },
+                _ if option.starts_with("--class-path=") =>
+                    self.class_path =
+                        Some(option["--class-path=".len() ..].to_string()),
+                "-jar" => match args.pop_front() {
+                    Some(jar) => {
+                        self.main_class = Some(jar.value);
+                        self.jar = true;
+                        return Ok(());
+                    },
+                    None => return Err(usage_error(
+                        "-jar requires jar file specification")),
+                },
+                _ if option.starts_with("-D") => {
+                    let mut definition = option[2 ..].splitn(2, '=');
+                    if let Some(key) = definition.next()
+                            .filter(|key| !key.is_empty()) {
+                        let value = definition.next().unwrap_or("");
+                        self.system_properties.push((key.to_string(),
+                                                     value.to_string()));
+                    }
+                },
+                "-verbose" | "-verbose:class" => self.verbose.class = true,
+                "-verbose:gc" => self.verbose.gc = true,
+                // As in the JDK, other kinds of verbose output are ignored.
+                _ if option.starts_with("-verbose:") => {},
+                "-version" => {
+                    self.action = Action::Version(Output::Stderr);
+                    return Ok(());
+                },
+                "--version" => {
+                    self.action = Action::Version(Output::Stdout);
+                    return Ok(());
+                },
+                "-showversion" => self.show_version = Some(Output::Stderr),
+                "--show-version" => self.show_version = Some(Output::Stdout),
+                "-?" | "-h" | "-help" => {
+                    self.action = Action::Help(Output::Stderr);
+                    return Ok(());
+                },
+                "--help" => {
+                    self.action = Action::Help(Output::Stdout);
+                    return Ok(());
+                },
+                "-esa" | "-enablesystemassertions" | "-dsa"
+                    | "-disablesystemassertions" => {},
+                _ if is_assertion_switch(option, "-ea", "-enableassertions") =>
+                    self.set_assertions(option, true),
+                _ if is_assertion_switch(option, "-da", "-disableassertions") =>
+                    self.set_assertions(option, false),
+                "-Xtrace" => self.verbose.trace = true,
+                _ if option.starts_with("-Xss") => self.stack_size =
+                    Some(size_option(option, "thread stack size")?),
+                _ if option.starts_with("-Xmx") => self.max_heap_size =
+                    Some(size_option(option, "maximum heap size")?),
+                _ if option.starts_with("-Xms") => self.initial_heap_size =
+                    Some(size_option(option, "initial heap size")?),
+                _ if option.starts_with("-XX:") =>
+                    self.set_vm_option(&option[4 ..])?,
+                "--disable-@files" => *expand_arg_files = false,
+                _ if option.starts_with('-') => return Err(create_vm_error(
+                    &format!("Unrecognized option: {}", option))),
+                _ => {
+                    self.main_class = Some(arg);
+                    return Ok(());
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /*
+     * Applies -ea or -da, given alone to set the default, or with
+     * a class name, a package name followed by "...", or "..." alone
+     * for the unnamed package.
+     */
+    fn set_assertions(&mut self, option: &str, enabled: bool) {
+        let assertions = &mut self.assertions;
+        match option.find(':').map(|idx| &option[idx + 1 ..]) {
+            None | Some("") => assertions.enabled_by_default = enabled,
+            Some(name) if name.ends_with("...") => {
+                let package = name.trim_end_matches("...");
+                assertions.packages.insert(package.to_string(), enabled);
+            },
+            Some(name) => {
+                assertions.classes.insert(name.to_string(), enabled);
+            },
+        }
+    }
+
+    /*
+     * Applies a -XX option: -XX:+<name> or -XX:-<name> for a boolean
+     * option, or -XX:<name>=<value> for one that takes a number.
+     */
+    fn set_vm_option(&mut self, option: &str) -> Result<(), LaunchError> {
+        let (enabled, name) = match option.chars().next() {
+            Some('+') => (Some(true), &option[1 ..]),
+            Some('-') => (Some(false), &option[1 ..]),
+            _ => (None, option),
+        };
+        let flag = name.split('=').next().unwrap_or(name);
+        match (flag, enabled) {
+            ("UseGenerationalGC", Some(enabled)) if flag == name =>
+                self.collector = if enabled {
+                    Collector::Generational
+                } else {
+                    Collector::MarkSweep
+                },
+            ("ThreadSchedulerSeed", None) =>
+                self.scheduler = Scheduler::Deterministic(
+                    number_option(name)?),
+            ("ExploreSchedules", None) =>
+                self.explore_schedules = Some(number_option(name)?),
+            ("UseGenerationalGC", _) | ("ThreadSchedulerSeed", _)
+                | ("ExploreSchedules", _) => return Err(create_vm_error(
+                &format!("Improperly specified VM option '{}'", name))),
+            _ => return Err(create_vm_error(
+                &format!("Unrecognized VM option '{}'", name))),
+        }
+        Ok(())
+    }
+
+    /*
+     * Creates the options for the VM to run the program with, and
+     * returns them along with the binary name of the main class.
+     * Without -cp, the CLASSPATH environment variable, if set,
+     * gives the class path; with -jar, the jar file does.
+     */
+    pub fn jvm_options(&self, classpath_env: Option<&str>)
+            -> Result<(JvmOptions, String), LaunchError> {
+        let main_class = match self.main_class {
+            Some(ref main_class) => main_class,
+            None => return Err(LaunchError::Usage(None)),
+        };
+        let (class_path, main_class) = if self.jar {
+            jar_class_path(Path::new(main_class))?
+        } else {
+            let class_path = self.class_path.as_deref()
+                .or(classpath_env)
+                .unwrap_or(".");
+            (expand_class_path(class_path), main_class.clone())
+        };
+
+        let system_properties: HashMap<String, String> =
+            self.system_properties.iter().cloned().collect();
+        let options = JvmOptions {
+            class_path,
+            initial_heap_size: self.initial_heap_size,
+            max_heap_size: self.max_heap_size,
+            collector: self.collector,
+            scheduler: self.scheduler,
+            stack_size: self.stack_size
+                .unwrap_or(interpreter::DEFAULT_STACK_SIZE),
+            system_properties,
+            assertions: self.assertions.clone(),
+            verbose: self.verbose,
+            ..JvmOptions::default()
+        };
+        Ok((options, main_class.replace('.', "/")))
+    }
+
+    /*
+     * The seed of the first schedule to explore: the one given with
+     * -XX:ThreadSchedulerSeed, if any.
+     */
+    pub fn first_seed(&self) -> u64 {
+        match self.scheduler {
+            Scheduler::Deterministic(seed) => seed,
+            Scheduler::Os => 0,
+        }
+    }
+}
+
+// Parses the value of an option such as -XX:ExploreSchedules=100.
+fn number_option(option: &str) -> Result<u64, LaunchError> {
+    let value = option.split('=').nth(1).unwrap_or("");
+    match value.parse::<u64>() {
+        Ok(number) if value.chars().all(|c| c.is_ascii_digit()) => Ok(number),
+        _ => Err(create_vm_error(&format!(
+            "Improperly specified VM option '{}'", option))),
+    }
+}
+
+fn is_assertion_switch(option: &str, short: &str, long: &str) -> bool {
+    [short, long].iter().any(|&switch| {
+        option == switch || option.starts_with(&format!("{}:", switch))
+    })
+}
+
+// Parses the size given by an option such as -Xmx512m.
+fn size_option(option: &str, name: &str) -> Result<usize, LaunchError> {
+    parse_size(&option[4 ..]).ok_or_else(|| create_vm_error(&format!(
+        "Invalid {}: {}", name, option)))
+}
+
+/*
+ * Parses a size such as 512k, 64m or 1g (or a number of bytes).
+ */
+fn parse_size(size: &str) -> Option<usize> {
+    let (digits, multiplier) = match size.chars().last() {
+        Some('k') | Some('K') => (&size[.. size.len() - 1], 1 << 10),
+        Some('m') | Some('M') => (&size[.. size.len() - 1], 1 << 20),
+        Some('g') | Some('G') => (&size[.. size.len() - 1], 1 << 30),
+        Some('t') | Some('T') => (&size[.. size.len() - 1], 1 << 40),
+        _ => (size, 1),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse::<usize>().ok()
+        .and_then(|count| count.checked_mul(multiplier))
+}
+
+/*
+ * Splits an argument file (or JDK_JAVA_OPTIONS) into arguments,
+ * which are separated by whitespace unless quoted with ' or ".
+ * Within quotes, a backslash escapes the next character, and at
+ * the end of a line, continues the argument on the next line,
+ * without its leading whitespace. A # starting an argument begins
+ * a comment that runs to the end of the line.
+ */
+pub fn tokenize(contents: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut chars = contents.chars().peekable();
+    let mut current: Option<String> = None;
+    let mut quote: Option<char> = None;
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), '\\') => match chars.next() {
+                Some('n') => push(&mut current, '\n'),
+                Some('t') => push(&mut current, '\t'),
+                Some('r') => push(&mut current, '\r'),
+                Some('f') => push(&mut current, '\x0c'),
+                Some('\r') | Some('\n') => {
+                    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                        chars.next();
+                    }
+                },
+                Some(escaped) => push(&mut current, escaped),
+                None => {},
+            },
+            (Some(_), _) => push(&mut current, c),
+            (None, '\'') | (None, '"') => {
+                quote = Some(c);
+                current.get_or_insert_with(String::new);
+            },
+            (None, '#') if current.is_none() => {
+                while chars.peek().is_some_and(|&c| c != '\n') {
+                    chars.next();
+                }
+            },
+            (None, _) if c.is_whitespace() => args.extend(current.take()),
+            (None, _) => push(&mut current, c),
+        }
+    }
+    args.extend(current);
+    args
+}
+
+fn push(current: &mut Option<String>, c: char) {
+    current.get_or_insert_with(String::new).push(c);
+}
+
+/*
+ * Splits a class path into its entries, replacing any ending in
+ * "*" with the jar files in that directory.
+ */
+fn expand_class_path(class_path: &str) -> Vec<PathBuf> {
+    let mut entries = Vec::new();
+    for entry in env::split_paths(class_path) {
+        if entry.file_name().is_none_or(|name| name != "*") {
+            entries.push(entry);
+            continue;
+        }
+        let directory = entry.parent().unwrap_or_else(|| Path::new(""));
+        let mut jars: Vec<PathBuf> = match fs::read_dir(directory) {
+            Ok(listing) => listing.filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|extension| {
+                    extension.eq_ignore_ascii_case("jar")
+                }))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        jars.sort();
+        entries.extend(jars);
+    }
+    entries
+}
+
+/*
+ * The class path for running a jar file: the jar itself, followed
+ * by those listed in its manifest's Class-Path, which are relative
+ * to its directory. Its manifest's Main-Class names the main class.
+ */
+fn jar_class_path(path: &Path) -> Result<(Vec<PathBuf>, String), LaunchError> {
+    let jar = match JarFile::open(path) {
+        Ok(jar) => jar,
+        Err(_) => return Err(LaunchError::Fatal(format!(
+            "Error: Unable to access jarfile {}", path.display()))),
+    };
+    let invalid = |_| LaunchError::Fatal(format!(
+        "Error: Invalid or corrupt jarfile {}", path.display()));
+    let main_class = match jar.manifest_attribute("Main-Class")
+            .map_err(invalid)? {
+        Some(main_class) => main_class,
+        None => return Err(LaunchError::Fatal(format!(
+            "no main manifest attribute, in {}", path.display()))),
+    };
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+    let mut class_path = vec![path.to_path_buf()];
+    if let Some(entries) = jar.manifest_attribute("Class-Path")
+            .map_err(invalid)? {
+        class_path.extend(entries.split_whitespace()
+                          .map(|entry| directory.join(entry)));
+    }
+    Ok((class_path, main_class))
+}
@@ -1,39 +1,1062 @@
 extern crate rustedjvm;
 
+mod launcher;
+
 use std::env;
+use std::io;
+use std::process;
 
-use rustedjvm::classes::*;
-use rustedjvm::interpreter;
+use launcher::{Action, Launch, LaunchError, Output};
+use rustedjvm::interpreter::{self, JvmOptions, RunError};
 
 fn main() {
 
-    let args: Vec<String> = env::args().collect();
-    if args.len() != 2 {
-        println!("Usage: $ rusted_jvm <ClassNameToRun>");
-        return;
+    let args: Vec<String> = env::args().skip(1).collect();
+    let jdk_java_options = env::var("JDK_JAVA_OPTIONS").ok()
+        .filter(|options| !options.trim().is_empty());
+    if let Some(ref options) = jdk_java_options {
+        eprintln!("NOTE: Picked up JDK_JAVA_OPTIONS: {}", options);
+    }
+
+    let launch = match Launch::parse(args, jdk_java_options.as_deref()) {
+        Ok(launch) => launch,
+        Err(error) => exit_with(error),
     };
+    match launch.action {
+        Action::Run => {},
+        Action::Help(Output::Stdout) => {
+            print!("{}", launcher::USAGE);
+            return;
+        },
+        Action::Help(Output::Stderr) => {
+            eprint!("{}", launcher::USAGE);
+            return;
+        },
+        Action::Version(output) => {
+            print_version(output);
+            return;
+        },
+    }
+    if let Some(output) = launch.show_version {
+        print_version(output);
+    }
 
-    run(&args[1]);
+    let classpath_env = env::var("CLASSPATH").ok();
+    let (options, main_class) =
+        match launch.jvm_options(classpath_env.as_deref()) {
+            Ok(options) => options,
+            Err(error) => exit_with(error),
+        };
+    let args: Vec<&str> = launch.args.iter().map(|arg| arg.as_str()).collect();
+    if let Some(count) = launch.explore_schedules {
+        explore_schedules(&launch, classpath_env.as_deref(), &main_class,
+                          &args, count);
+    }
+    match interpreter::run_with_options(options, &main_class, &args) {
+        Ok(()) => {},
+        Err(RunError::InvalidMainClass(reason)) => {
+            eprintln!("Error: {}", reason);
+            process::exit(1);
+        },
+        Err(RunError::Exit(status)) => process::exit(status),
+        // The exception has already been reported along with its trace.
+        Err(RunError::UncaughtException(_)) => process::exit(1),
+        Err(RunError::Internal(error)) => {
+            eprintln!("{}", error);
+            process::exit(1);
+        },
+    }
 }
 
-fn run(class_name: &String) {
-    let class_file = ClassFile::new(class_name);
-    let class = class_file.parse();
+/*
+ * Runs the program under each of the schedules to explore, with its
+ * output discarded, and reports the seeds under which it failed.
+ */
+fn explore_schedules(launch: &Launch, classpath_env: Option<&str>,
+                     main_class: &str, args: &[&str], count: u64) -> ! {
+    let first_seed = launch.first_seed();
+    let seeds = first_seed .. first_seed.saturating_add(count);
+    let options = |_| match launch.jvm_options(classpath_env) {
+        Ok((options, _)) => JvmOptions {
+            stdout: Box::new(io::sink()),
+            stderr: Box::new(io::sink()),
+            ..options
+        },
+        Err(error) => exit_with(error),
+    };
+    let failures = match interpreter::explore_schedules(options, main_class,
+                                                        args, seeds) {
+        Ok(failures) => failures,
+        Err(RunError::InvalidMainClass(reason)) => {
+            eprintln!("Error: {}", reason);
+            process::exit(1);
+        },
+        Err(error) => panic!("[ERROR] Unexpected failure: {:?}", error),
+    };
+    for &(seed, ref error) in failures.iter() {
+        let failure = match *error {
+            RunError::Exit(status) => format!("exited with status {}", status),
+            RunError::UncaughtException(ref description) =>
+                format!("threw {}", description),
+            RunError::Internal(ref error) => error.to_string(),
+            RunError::InvalidMainClass(ref reason) => reason.clone(),
+        };
+        println!("Seed {}: {}", seed, failure);
+    }
+    println!("{} of {} schedules failed", failures.len(), count);
+    process::exit(if failures.is_empty() { 0 } else { 1 });
+}
 
-    interpreter::run(class);
+fn exit_with(error: LaunchError) -> ! {
+    match error {
+        LaunchError::Usage(reason) => {
+            if let Some(reason) = reason {
+                eprintln!("Error: {}", reason);
+            }
+            eprint!("{}", launcher::USAGE);
+        },
+        LaunchError::Fatal(message) => eprintln!("{}", message),
+    }
+    process::exit(1);
+}
+
+/*
+ * As with java, -version prints to standard error, in a slightly
+ * different format from --version, which prints to standard output.
+ */
+fn print_version(output: Output) {
+    let version = env!("CARGO_PKG_VERSION");
+    match output {
+        Output::Stdout => println!("rusted_jvm {}", version),
+        Output::Stderr => eprintln!("rusted_jvm version \"{}\"", version),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::run;
+    use std::io::{self, Write};
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, Mutex};
+
+    use launcher::{Action, Launch, LaunchError, Output};
+    use rustedjvm::classes::ClassFile;
+    use rustedjvm::heap::Collector;
+    use rustedjvm::interpreter;
+    use rustedjvm::interpreter::{Jvm, JvmOptions, RunError, Scheduler,
+                                 VmError};
+    use rustedjvm::natives::NativeRegistry;
+    use rustedjvm::value::Value;
+
+    /*
+     * Splits the path of a test program, given without the .class
+     * suffix, into the directory containing it, which serves as the
+     * class path, and the name of its class.
+     */
+    fn locate(class_name: &str) -> (PathBuf, String) {
+        let path = Path::new(class_name);
+        let class_path = path.parent().unwrap_or_else(|| Path::new(""));
+        match path.file_name() {
+            Some(name) => (class_path.to_path_buf(),
+                           name.to_string_lossy().into_owned()),
+            None => panic!("Invalid class name: {}", class_name),
+        }
+    }
+
+    /*
+     * Runs a test program with the given options, such as its heap
+     * size, collector, scheduler and where it writes its output.
+     */
+    fn run_with_output(class_name: &str, args: &[&str],
+                       options: JvmOptions) -> Result<(), RunError> {
+        let (class_path, main_class) = locate(class_name);
+        interpreter::run_with_options(JvmOptions {
+            class_path: vec![class_path],
+            ..options
+        }, &main_class, args)
+    }
+
+    fn run(class_name: &str) -> Result<(), RunError> {
+        run_with_output(class_name, &[], JvmOptions::default())
+    }
+
+    fn uncaught(description: &str) -> Result<(), RunError> {
+        Err(RunError::UncaughtException(description.to_string()))
+    }
+
+    // A sink that keeps what's written to it for inspection.
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Captured {
+        fn contents(&self) -> String {
+            String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+        }
+    }
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /*
+     * Runs a program, returning its result along with
+     * everything it wrote to standard output and error.
+     */
+    fn run_captured(class_name: &str, args: &[&str])
+            -> (Result<(), RunError>, String, String) {
+        run_captured_with(class_name, args, JvmOptions::default())
+    }
+
+    fn run_captured_with(class_name: &str, args: &[&str],
+                         options: JvmOptions)
+            -> (Result<(), RunError>, String, String) {
+        let stdout = Captured::default();
+        let stderr = Captured::default();
+        let result = run_with_output(class_name, args, JvmOptions {
+            stdout: Box::new(stdout.clone()),
+            stderr: Box::new(stderr.clone()),
+            ..options
+        });
+        (result, stdout.contents(), stderr.contents())
+    }
+
+    /*
+     * Runs a program on a VM the test can go on to inspect, returning
+     * it along with what the program wrote to standard output.
+     */
+    fn run_in_jvm(class_name: &str, options: JvmOptions) -> (Jvm, String) {
+        let (class_path, main_class) = locate(class_name);
+        let stdout = Captured::default();
+        let mut jvm = Jvm::new(JvmOptions {
+            class_path: vec![class_path],
+            stdout: Box::new(stdout.clone()),
+            ..options
+        });
+        assert!(jvm.run_main(&main_class, &[]).is_ok());
+        (jvm, stdout.contents())
+    }
 
     #[test]
     fn test_simple_addition() {
-        run(&String::from("test/SimpleAddition"));
+        assert_eq!(run("test/SimpleAddition"), Ok(()));
+    }
+
+    #[test]
+    fn test_method_invocation() {
+        assert_eq!(run("test/MethodInvocation"), Ok(()));
+    }
+
+    #[test]
+    fn test_int_arithmetic() {
+        let expected = "2147483641\n-2147483648\n0\n31\n-2\n1073741822\n\
+                        20\n1073741822\n12\n4364\n4364\n8740\n57712\n";
+        assert_eq!(run_captured("test/IntArithmetic", &[]),
+                   (Ok(()), expected.to_string(), String::new()));
+    }
+
+    #[test]
+    fn test_wide_arithmetic() {
+        let expected = "-2305841651189014804\n-5380297186107701204\n-4\n\
+                        36.375\n19.875\n-1.9499998\n1286620964\n29.5\n\
+                        Infinity\n-Infinity\nNaN\n-0.0\n-0.0\ntrue\n\
+                        329\n401\n228\n260\n260\n260\n\
+                        2147483647\n9223372036854775807\n\
+                        2147483647\n9223372036854775807\n\
+                        -2147483648\n-9223372036854775808\n\
+                        0\n0\n0\n0\n0\n0\n\
+                        2147483647\n9223372036854775807\n\
+                        2147483647\n9223372036854775807\n\
+                        -2147483648\n-9223372036854775808\n\
+                        -2\n-2\n-2\n-2\n2\n2\n34.5\n8\n2.5\n17\n";
+        assert_eq!(run_captured("test/WideArithmetic", &[]),
+                   (Ok(()), expected.to_string(), String::new()));
+    }
+
+    #[test]
+    fn test_branching() {
+        let expected = "111\n681\n358\n666\n135\n\
+                        -1\n4\n10\n4\n20\n2\n30\n4\n40\n4\n-1\n4\n\
+                        1\n3\n4\n5\n9\n10\n20100\n20\n";
+        assert_eq!(run_captured("test/Branching", &[]),
+                   (Ok(()), expected.to_string(), String::new()));
+    }
+
+    #[test]
+    fn test_object_fields() {
+        let expected = "55\n31\n98245\n-2999999991\n\
+                        false\n0\n0\n0\n0\n0\n0.0\n0.0\nnull\n\
+                        7000000000\n7\n-8\n9\n24\n2\n0\n";
+        assert_eq!(run_captured("test/ObjectFields", &[]),
+                   (Ok(()), expected.to_string(), String::new()));
     }
 
-    /*#[test]
+    #[test]
+    fn test_arrays() {
+        let expected = "2\n285\n68630377364883\n3.25\n65406\n108\n15342\n\
+                        6\n2\n3\n4\n0\n0\n\u{0}\u{0}z\n";
+        assert_eq!(run_captured("test/Arrays", &["x", "y"]),
+                   (Ok(()), expected.to_string(), String::new()));
+    }
+
+    #[test]
+    fn test_static_fields() {
+        let (mut jvm, output) = run_in_jvm("test/StaticFields",
+                                           JvmOptions::default());
+        // Parent, then Table for its default method, then Child.
+        assert_eq!(output,
+                   "2\n1\n3\n213\n42\n40\n25\n0.5\n5\n5\n15\ntrue\n-6\n");
+
+        let child = jvm.load_class("StaticFields$Child").unwrap();
+        let name = match jvm.get_static(child, "NAME", "Ljava/lang/String;") {
+            Ok(Value::Ref(name)) => jvm.string_value(name),
+            other => panic!("Unexpected value: {:?}", other),
+        };
+        assert_eq!(name, "child");
+        assert_eq!(jvm.get_static(child, "BIG", "J"),
+                   Ok(Value::Long(1 << 40)));
+        assert_eq!(jvm.get_static(child, "HALF", "D"), Ok(Value::Double(0.5)));
+        let table = jvm.load_class("StaticFields$Table").unwrap();
+        assert_eq!(jvm.get_static(table, "LIMIT", "I"), Ok(Value::Int(5)));
+    }
+
+    #[test]
+    fn test_exceptions() {
+        assert_eq!(run("test/Exceptions"), Ok(()));
+    }
+
+    #[test]
+    fn test_superclass_initializer_failure() {
+        let expected = "parent failed\n\
+                        Could not initialize class InitializerFailure$Child\n";
+        assert_eq!(run_captured("test/InitializerFailure", &[]),
+                   (Ok(()), expected.to_string(), String::new()));
+    }
+
+    #[test]
+    fn test_linkage_errors() {
+        let expected = "java.lang.NoSuchFieldError: count\n\
+                        java.lang.NoSuchFieldError: name\n\
+                        java.lang.NoSuchMethodError: \
+                        LinkageTarget.missing()V\n\
+                        java.lang.IncompatibleClassChangeError: Expected \
+                        static method LinkageTarget.helper()V\n\
+                        java.lang.NoClassDefFoundError: LinkageGone\n\
+                        java.lang.AbstractMethodError: \
+                        LinkageSquare.area()I\n\
+                        caught LinkageGone\n";
+        assert_eq!(run_captured("test/Linkage", &[]),
+                   (Ok(()), expected.to_string(), String::new()));
+    }
+
+    #[test]
+    fn test_instantiation_error() {
+        assert_eq!(run_captured("test/Instantiation", &[]),
+                   (Ok(()), "Shape\n".to_string(), String::new()));
+    }
+
+    // Every entry of an exception table is read, and what follows it.
+    #[test]
+    fn test_exception_table() {
+        let class_file = ClassFile::new("test/Exceptions");
+        let class = class_file.parse(false);
+        let code = match class.method("catchByType", "(I)I")
+                .and_then(|method| method.code()) {
+            Some(code) => code,
+            None => panic!("catchByType lacks code"),
+        };
+        let handlers: Vec<(u16, u16, u16, &str)> = code.exception_table
+            .iter()
+            .map(|entry| (entry.start_pc, entry.end_pc, entry.handle_pc,
+                          class.class_name(entry.catch_type)))
+            .collect();
+        assert_eq!(handlers, vec![(0, 5, 6, "Exceptions$SpecificFailure"),
+                                  (0, 5, 17, "Exceptions$Failure")]);
+        assert_eq!(code.line_number(0), Some(47));
+        assert_eq!(code.line_number(17), Some(51));
+    }
+
+    #[test]
+    fn test_uncaught_exception() {
+        assert_eq!(run("test/UncaughtException"),
+                   uncaught("UncaughtException$Failure"));
+    }
+
+    #[test]
+    fn test_divide_by_zero() {
+        assert_eq!(run("test/DivideByZero"),
+                   uncaught("java.lang.ArithmeticException: / by zero"));
+    }
+
+    #[test]
+    fn test_array_index_out_of_bounds() {
+        assert_eq!(run("test/ArrayIndexOutOfBounds"),
+                   uncaught("java.lang.ArrayIndexOutOfBoundsException: \
+                            Index 3 out of bounds for length 3"));
+    }
+
+    #[test]
+    fn test_negative_array_size() {
+        assert_eq!(run("test/NegativeArraySize"),
+                   uncaught("java.lang.NegativeArraySizeException: -2"));
+    }
+
+    #[test]
+    fn test_array_store() {
+        assert_eq!(run("test/ArrayStore"),
+                   uncaught("java.lang.ArrayStoreException: \
+                            ArrayStore$Orange"));
+    }
+
+    #[test]
+    fn test_null_pointers() {
+        assert_eq!(run("test/NullPointers"), Ok(()));
+    }
+
+    #[test]
+    fn test_null_receiver() {
+        assert_eq!(run("test/NullReceiver"),
+                   uncaught("java.lang.NullPointerException: Cannot invoke \
+                            \"NullReceiver$Greeter.greet(int, long[])\" \
+                            because \"<local1>\" is null"));
+    }
+
+    #[test]
+    fn test_null_field_chain() {
+        assert_eq!(run("test/NullFieldChain"),
+                   uncaught("java.lang.NullPointerException: Cannot read \
+                            field \"value\" because \
+                            \"<parameter1>.next.next\" is null"));
+    }
+
+    #[test]
+    fn test_null_array_return() {
+        assert_eq!(run("test/NullArrayReturn"),
+                   uncaught("java.lang.NullPointerException: Cannot load \
+                            from int array because the return value of \
+                            \"NullArrayReturn.row(int)\" is null"));
+    }
+
+    #[test]
+    fn test_stack_traces() {
+        assert_eq!(run("test/StackTraces"), Ok(()));
+    }
+
+    #[test]
+    fn test_chained_trace() {
+        assert_eq!(run("test/ChainedTrace"), uncaught("ChainedTrace$Failure"));
+    }
+
+    #[test]
+    fn test_casts() {
+        // Bit n of the first line holds the result of the nth instanceof.
+        assert_eq!(run_captured("test/Casts", &[]),
+                   (Ok(()), "6289863\n23\n242\nnull\n".to_string(),
+                    String::new()));
+    }
+
+    #[test]
+    fn test_class_cast() {
+        assert_eq!(run("test/ClassCast"),
+                   uncaught("java.lang.ClassCastException: class \
+                            ClassCast$Cat cannot be cast to class \
+                            ClassCast$Dog"));
+    }
+
+    #[test]
+    fn test_constants() {
+        let expected = "1237570\n-1000000\n0.75\n9876543210\n2.718281828\n\
+                        true\ntrue\n318033107\n\
+                        Constants\n[[I\nint\njava.lang.String\n\
+                        true\nfalse\ntrue\ntrue\n\
+                        long\n2\nint\n[Ljava.lang.String;\ntrue\n\
+                        int\n1\njava.lang.String\ntrue\n\
+                        LoadableConstants value java.lang.Object argument\n\
+                        true\n";
+        assert_eq!(run_captured("test/Constants", &[]),
+                   (Ok(()), expected.to_string(), String::new()));
+    }
+
+    #[test]
+    fn test_strings() {
+        let (mut jvm, output) = run_in_jvm("test/Strings",
+                                           JvmOptions::default());
+        assert_eq!(output, "12418072\n99\n31\n");
+
+        // Strings whose chars all fit in a byte are stored as Latin-1.
+        let class = jvm.load_class("Strings").unwrap();
+        for &(method, coder) in [("latin", 0), ("wide", 1)].iter() {
+            let string = match jvm.invoke_static(class, method,
+                                                 "()Ljava/lang/String;", &[]) {
+                Ok(Value::Ref(string)) => string,
+                other => panic!("Unexpected result: {:?}", other),
+            };
+            assert_eq!(jvm.get_field(string, "coder", "B"),
+                       Ok(Value::Byte(coder)));
+        }
+        let wide = jvm.new_string("日本").unwrap();
+        assert_eq!(jvm.get_field(wide, "coder", "B"), Ok(Value::Byte(1)));
+        let latin = jvm.new_string("über").unwrap();
+        assert_eq!(jvm.get_field(latin, "coder", "B"), Ok(Value::Byte(0)));
+    }
+
+    #[test]
     fn test_hello_world() {
-        run(&String::from("test/HelloWorld"));
-    }*/
+        assert_eq!(run_captured("test/HelloWorld", &[]),
+                   (Ok(()), "Hello, world!\n".to_string(), String::new()));
+    }
+
+    #[test]
+    fn test_printing() {
+        let expected = "true\nλ\n-42\n-9223372036854775808\n1.5\n0.3\n\
+                        0.30000000000000004\n100.0\n1.0E7\n1.23456789E7\n\
+                        0.001\n1.0E-4\n-0.0\nInfinity\nNaN\n\
+                        1.7976931348623157E308\n1.4E-45\nok\n日本語\n\
+                        null\nnull\nPoint3,-4\nPrinting$Failure: bad input\n\
+                        no newline, 7\n";
+        assert_eq!(run_captured("test/Printing", &[]),
+                   (Ok(()), expected.to_string(), "to stderr\n".to_string()));
+    }
+
+    #[test]
+    fn test_uncaught_exception_output() {
+        let (result, _, stderr) = run_captured("test/ChainedTrace", &[]);
+        assert_eq!(result, uncaught("ChainedTrace$Failure"));
+        assert!(stderr.starts_with("Exception in thread \"main\" \
+                                    ChainedTrace$Failure\n\tat "));
+        assert!(stderr.ends_with("\t... 1 more\n"));
+    }
+
+    #[test]
+    fn test_exit() {
+        assert_eq!(run_captured("test/Exit", &["a", "b c"]),
+                   (Err(RunError::Exit(42)), "2\na\nb c\n".to_string(),
+                    String::new()));
+        assert_eq!(run_captured("test/Exit", &[]),
+                   (Ok(()), "0\n".to_string(), String::new()));
+    }
+
+    #[test]
+    fn test_invalid_main_class() {
+        let invalid = |reason: &str| {
+            Err(RunError::InvalidMainClass(reason.to_string()))
+        };
+        assert_eq!(run("test/InstanceMain"),
+                   invalid("Main method is not static in class \
+                            InstanceMain, please define the main method \
+                            as:\n   public static void main(String[] args)"));
+        assert_eq!(run("test/Embedding"),
+                   invalid("Main method not found in class Embedding, \
+                            please define the main method as:\n   \
+                            public static void main(String[] args)"));
+        assert_eq!(run("test/Missing"),
+                   invalid("Could not find or load main class Missing"));
+    }
+
+    #[test]
+    fn test_natives() {
+        let mut natives = NativeRegistry::new();
+        natives.register("Natives", "add", "(IJ)J", |_, args| {
+            match (args[0], args[1]) {
+                (Value::Int(a), Value::Long(b)) => Ok(Value::Long(a as i64 + b)),
+                _ => panic!("Unexpected arguments: {:?}", args),
+            }
+        });
+        natives.register("Natives", "greet",
+                         "(Ljava/lang/String;)Ljava/lang/String;",
+                         |env, args| match args[0] {
+            Value::Ref(name) => {
+                let greeting = format!("Hello, {}!", env.string_value(name));
+                Ok(Value::Ref(env.new_string(&greeting)?))
+            },
+            _ => panic!("Unexpected arguments: {:?}", args),
+        });
+        natives.register("Natives", "initial", "(Ljava/lang/String;Z)C",
+                         |env, args| match (args[0], args[1]) {
+            (Value::Ref(name), Value::Boolean(upper)) => {
+                let initial = env.string_value(name).chars().next().unwrap();
+                let initial = if upper {
+                    initial.to_ascii_uppercase()
+                } else {
+                    initial
+                };
+                Ok(Value::Char(initial as u16))
+            },
+            _ => panic!("Unexpected arguments: {:?}", args),
+        });
+        natives.register("Natives", "describe", "(D)Ljava/lang/String;",
+                         |env, args| match (args[0], args[1]) {
+            (Value::Ref(this), Value::Double(weight)) => {
+                let name = match env.get_field(this, "name",
+                                               "Ljava/lang/String;") {
+                    Value::Ref(name) => env.string_value(name),
+                    other => panic!("Unexpected name: {:?}", other),
+                };
+                let description = format!("{} weighs {}", name, weight);
+                Ok(Value::Ref(env.new_string(&description)?))
+            },
+            _ => panic!("Unexpected arguments: {:?}", args),
+        });
+        natives.register("Natives", "fail", "(I)V", |env, args| {
+            let message = format!("failed with {:?}", args[0]);
+            Err(env.throw("java/lang/ArithmeticException", &message))
+        });
+
+        let options = JvmOptions { natives, ..JvmOptions::default() };
+        let (result, output, _) = run_captured_with("test/Natives", &[],
+                                                    options);
+        let unsatisfied = "java.lang.UnsatisfiedLinkError: \
+                           'void Natives.missing(int[], java.lang.String)'";
+        assert_eq!(result, uncaught(unsatisfied));
+        assert_eq!(output,
+                   format!("2000000000040\nHello, native!\nR\n\
+                            crate weighs 2.5\nfailed with Int(7)\n{}\n",
+                           unsatisfied));
+    }
+
+    #[test]
+    fn test_embedding() {
+        let mut jvm = Jvm::new(JvmOptions {
+            class_path: vec![PathBuf::from("test")],
+            max_heap_size: Some(1 << 20),
+            ..JvmOptions::default()
+        });
+        let class = jvm.load_class("Embedding").unwrap();
+        assert_eq!(jvm.invoke_static(class, "add", "(II)I",
+                                     &[Value::Int(40), Value::Int(2)]),
+                   Ok(Value::Int(42)));
+        let world = jvm.new_string("world").unwrap();
+        let greeting = match jvm.invoke_static(
+                class, "greet", "(Ljava/lang/String;)Ljava/lang/String;",
+                &[Value::Ref(world)]) {
+            Ok(Value::Ref(greeting)) => jvm.string_value(greeting),
+            other => panic!("Unexpected result: {:?}", other),
+        };
+        assert_eq!(greeting, "Hello, world!");
+
+        let name = jvm.new_string("crate").unwrap();
+        let object = jvm.new_object(class, "(Ljava/lang/String;)V",
+                                    &[Value::Ref(name)]).unwrap();
+        assert_eq!(jvm.invoke_virtual(object, "add", "(J)J",
+                                      &[Value::Long(5)]),
+                   Ok(Value::Long(5)));
+        jvm.set_field(object, "total", "J", Value::Long(1 << 40)).unwrap();
+        assert_eq!(jvm.get_field(object, "total", "J"),
+                   Ok(Value::Long(1 << 40)));
+        let description = match jvm.invoke_virtual(
+                object, "describe", "()Ljava/lang/String;", &[]) {
+            Ok(Value::Ref(description)) => jvm.string_value(description),
+            other => panic!("Unexpected result: {:?}", other),
+        };
+        assert_eq!(description, "crate has 1099511627776");
+        assert_eq!(jvm.get_static(class, "instances", "I"),
+                   Ok(Value::Int(1)));
+        jvm.set_static(class, "instances", "I", Value::Int(7)).unwrap();
+        assert_eq!(jvm.get_static(class, "instances", "I"),
+                   Ok(Value::Int(7)));
+
+        let describe = |jvm: &mut Jvm, result: Result<Value, _>| {
+            match result {
+                Err(exception) => jvm.describe_exception(exception),
+                Ok(value) => panic!("Unexpected result: {:?}", value),
+            }
+        };
+        let result = jvm.invoke_static(class, "check", "(Z)V",
+                                       &[Value::Boolean(false)]);
+        assert_eq!(describe(&mut jvm, result),
+                   "java.lang.IllegalArgumentException: invalid");
+        let result = jvm.invoke_static(class, "add", "(II)I",
+                                       &[Value::Int(1), Value::Long(2)]);
+        assert_eq!(describe(&mut jvm, result),
+                   "java.lang.IllegalArgumentException: argument type \
+                    mismatch: Long(2) is not a int");
+        let result = jvm.invoke_static(class, "add", "(II)I", &[]);
+        assert_eq!(describe(&mut jvm, result),
+                   "java.lang.IllegalArgumentException: wrong number of \
+                    arguments for 'int Embedding.add(int, int)': 2 \
+                    expected, 0 given");
+        let result = jvm.invoke_static(class, "subtract", "(II)I", &[]);
+        assert_eq!(describe(&mut jvm, result),
+                   "java.lang.NoSuchMethodError: \
+                    'int Embedding.subtract(int, int)'");
+        let result = jvm.invoke_static(class, "describe",
+                                       "()Ljava/lang/String;", &[]);
+        assert_eq!(describe(&mut jvm, result),
+                   "java.lang.IncompatibleClassChangeError: Expected \
+                    static method 'java.lang.String Embedding.describe()'");
+        let result = jvm.invoke_static(class, "allocate", "(I)[I",
+                                       &[Value::Int(1 << 20)]);
+        assert_eq!(describe(&mut jvm, result),
+                   "java.lang.OutOfMemoryError: Java heap space");
+        let result = jvm.get_field(object, "count", "I");
+        assert_eq!(describe(&mut jvm, result),
+                   "java.lang.NoSuchFieldError: count");
+
+        let shape = jvm.load_class("Embedding$Shape").unwrap();
+        let exception = jvm.new_object(shape, "()V", &[]).unwrap_err();
+        assert_eq!(jvm.describe_exception(exception),
+                   "java.lang.InstantiationError: Embedding$Shape");
+        let description = match jvm.load_class("Missing") {
+            Err(exception) => jvm.describe_exception(exception),
+            Ok(class) => panic!("Unexpectedly loaded {}", class.name),
+        };
+        assert_eq!(description, "java.lang.NoClassDefFoundError: Missing");
+    }
+
+    /*
+     * Runs a program as the launcher does, given its command line,
+     * returning its result along with what it wrote to standard output.
+     */
+    fn launch(args: &[&str]) -> (Result<(), RunError>, String) {
+        let args = args.iter().map(|arg| arg.to_string()).collect();
+        let launch = Launch::parse(args, None).unwrap();
+        let (options, main_class) = launch.jvm_options(None).unwrap();
+        let stdout = Captured::default();
+        let options = JvmOptions {
+            stdout: Box::new(stdout.clone()),
+            stderr: Box::new(io::sink()),
+            ..options
+        };
+        let args: Vec<&str> = launch.args.iter().map(|arg| arg.as_str())
+            .collect();
+        let result = interpreter::run_with_options(options, &main_class,
+                                                   &args);
+        (result, stdout.contents())
+    }
+
+    fn parse(args: &[&str], jdk_java_options: Option<&str>)
+            -> Result<Launch, LaunchError> {
+        let args = args.iter().map(|arg| arg.to_string()).collect();
+        Launch::parse(args, jdk_java_options)
+    }
+
+    #[test]
+    fn test_launcher_options() {
+        let launch = parse(&["-showversion", "@test/launcher.args", "arg"],
+                           Some("-ea -Dgreeting=overridden")).unwrap();
+        assert_eq!(launch.show_version, Some(Output::Stderr));
+        assert_eq!(launch.main_class, Some("com.acme.Launcher".to_string()));
+        assert_eq!(launch.args, vec!["@not-expanded", "arg"]);
+        assert_eq!(launch.stack_size, Some(512 << 10));
+        assert_eq!(launch.max_heap_size, Some(64 << 20));
+        assert!(launch.assertions.enabled_by_default);
+        let (options, main_class) = launch.jvm_options(None).unwrap();
+        assert_eq!(main_class, "com/acme/Launcher");
+        assert_eq!(options.class_path, vec![PathBuf::from("test")]);
+        let property = |key: &str| options.system_properties.get(key)
+            .map(|value| value.as_str());
+        assert_eq!(property("greeting"), Some("hello, world"));
+        assert_eq!(property("empty"), Some(""));
+        assert_eq!(property("path"), Some("C:\\temp"));
+        assert_eq!(property("continued"), Some("one two"));
+
+        assert_eq!(parse(&["--version", "Main"], None).unwrap().action,
+                   Action::Version(Output::Stdout));
+        assert_eq!(parse(&["-?"], None).unwrap().action,
+                   Action::Help(Output::Stderr));
+        assert_eq!(parse(&["--disable-@files", "@Main"], None).unwrap()
+                       .main_class,
+                   Some("@Main".to_string()));
+        assert_eq!(parse(&["@@Main"], None).unwrap().main_class,
+                   Some("@Main".to_string()));
+
+        let usage = |reason: &str| {
+            Err(LaunchError::Usage(Some(reason.to_string())))
+        };
+        assert_eq!(parse(&[], None), Err(LaunchError::Usage(None)));
+        assert_eq!(parse(&["-cp"], None),
+                   usage("-cp requires class path specification"));
+        assert_eq!(parse(&["-jar"], None),
+                   usage("-jar requires jar file specification"));
+        assert_eq!(parse(&[], Some("-cp test Main")),
+                   usage("Cannot specify main class in environment \
+                          variable JDK_JAVA_OPTIONS"));
+        let fatal = |message: &str| Err(LaunchError::Fatal(message.to_string()));
+        let create_vm = |reason: &str| fatal(&format!(
+            "{}\nError: Could not create the Java Virtual Machine.\nError: A \
+             fatal exception has occurred. Program will exit.", reason));
+        assert_eq!(parse(&["-Xss1q", "Main"], None),
+                   create_vm("Invalid thread stack size: -Xss1q"));
+        assert_eq!(parse(&["-Xmx", "Main"], None),
+                   create_vm("Invalid maximum heap size: -Xmx"));
+        assert_eq!(parse(&["-XX:+UseGenerationalGC", "Main"], None).unwrap()
+                       .collector,
+                   Collector::Generational);
+        let launch = parse(&["-XX:ThreadSchedulerSeed=42",
+                             "-XX:ExploreSchedules=10", "Main"], None).unwrap();
+        assert_eq!(launch.scheduler, Scheduler::Deterministic(42));
+        assert_eq!(launch.explore_schedules, Some(10));
+        assert_eq!(launch.first_seed(), 42);
+        assert_eq!(parse(&["-XX:ExploreSchedules=-1", "Main"], None),
+                   create_vm("Improperly specified VM option \
+                              'ExploreSchedules=-1'"));
+        assert_eq!(parse(&["-XX:+UseGenerationalGC", "Main"], None).unwrap()
+                       .scheduler,
+                   Scheduler::Os);
+        assert_eq!(parse(&["-XX:+Unknown", "Main"], None),
+                   create_vm("Unrecognized VM option 'Unknown'"));
+        assert_eq!(parse(&["-XX:UseGenerationalGC=1", "Main"], None),
+                   create_vm("Improperly specified VM option \
+                              'UseGenerationalGC=1'"));
+        assert_eq!(parse(&["-Xms2g", "-Xmx1g", "Main"], None),
+                   fatal("Error occurred during initialization of VM\n\
+                          Initial heap size set to a larger value than the \
+                          maximum heap size"));
+        assert_eq!(parse(&["@test/missing.args"], None),
+                   fatal("Error: could not open `test/missing.args'"));
+    }
+
+    #[test]
+    fn test_launcher_assertions() {
+        assert_eq!(launch(&["-cp", "test", "com.acme.Launcher"]),
+                   (Ok(()), "false\nfalse\n".to_string()));
+        assert_eq!(launch(&["-cp", "test", "-ea", "com.acme.Launcher", "x"]),
+                   (Ok(()), "x\ntrue\ntrue\n".to_string()));
+        assert_eq!(launch(&["-cp", "test", "-ea:com.acme...",
+                            "-da:com.acme.util.Helper", "com.acme.Launcher"]),
+                   (Ok(()), "true\nfalse\n".to_string()));
+        assert_eq!(launch(&["-cp", "test", "-enableassertions:com.acme.util...",
+                            "com/acme/Launcher"]),
+                   (Ok(()), "false\ntrue\n".to_string()));
+        assert_eq!(launch(&["-cp", "test", "-ea", "com.acme.Launcher",
+                            "a", "b", "c"]),
+                   (uncaught("java.lang.AssertionError: too many arguments"),
+                    String::new()));
+    }
+
+    #[test]
+    fn test_launcher_jar() {
+        assert_eq!(launch(&["-ea", "-jar", "test/launcher.jar", "a", "-cp"]),
+                   (Ok(()), "a\n-cp\ntrue\ntrue\n".to_string()));
+    }
+
+    #[test]
+    fn test_stack_overflow() {
+        assert_eq!(launch(&["-cp", "test", "-Xss64k", "StackOverflow"]),
+                   (uncaught("java.lang.StackOverflowError"),
+                    "true\n50\n".to_string()));
+    }
+
+    #[test]
+    fn test_system_properties() {
+        let stdout = Captured::default();
+        let property = |key: &str, value: &str| {
+            (key.to_string(), value.to_string())
+        };
+        let mut jvm = Jvm::new(JvmOptions {
+            class_path: vec![PathBuf::from("test")],
+            system_properties: vec![property("greeting", "hello"),
+                                    property("java.version", "1.8.0_292")]
+                .into_iter().collect(),
+            environment: vec![property("GREETING", "hi")].into_iter().collect(),
+            stdout: Box::new(stdout.clone()),
+            ..JvmOptions::default()
+        });
+        assert_eq!(jvm.system_property("java.class.path"), Some("test"));
+        assert_eq!(jvm.system_property("line.separator"),
+                   Some(if cfg!(windows) { "\r\n" } else { "\n" }));
+        assert_eq!(jvm.set_system_property("set.by.host", "x"), None);
+        assert!(jvm.run_main("SystemProperties", &[]).is_ok());
+        assert_eq!(stdout.contents(),
+                   "hello\nnull\ndefault\nhello\ngoodbye\nx\ncleared\n\
+                    1.8.0_292\ntest\ntrue\ngoodbye\nchanged\nchanged\ntrue\n\
+                    hi\nnull\nkey can't be null\nkey can't be empty\n");
+        assert_eq!(jvm.system_property("greeting"), Some("changed"));
+        assert_eq!(jvm.system_property("set.by.host"), None);
+        assert_eq!(jvm.clear_system_property("set.by.guest"),
+                   Some("yes".to_string()));
+    }
+
+    #[test]
+    fn test_garbage_collection() {
+        let (mut jvm, output) = run_in_jvm("test/Garbage", JvmOptions {
+            max_heap_size: Some(4 << 20),
+            ..JvmOptions::default()
+        });
+        assert_eq!(output,
+                   "2499950000\n4950\n0123456789\nJava heap space\ntrue\n");
+        let stats = *jvm.heap().stats();
+        assert!(stats.collections >= 3);
+        assert!(stats.objects_freed >= 100_000);
+
+        // Objects the host holds survive only while it refers to them.
+        jvm.delete_local_refs();
+        jvm.collect_garbage();
+        let kept = jvm.new_string("kept").unwrap();
+        jvm.new_global_ref(kept);
+        jvm.new_string("dropped").unwrap();
+        jvm.delete_local_refs();
+        let collection = jvm.collect_garbage();
+        assert_eq!(collection.objects_freed, 2);
+        assert_eq!(jvm.string_value(kept), "kept");
+        jvm.delete_global_ref(kept);
+        assert_eq!(jvm.collect_garbage().objects_freed, 2);
+    }
+
+    #[test]
+    fn test_generational_garbage_collection() {
+        let run = |class_name: &str| {
+            let (jvm, output) = run_in_jvm(class_name, JvmOptions {
+                max_heap_size: Some(4 << 20),
+                collector: Collector::Generational,
+                ..JvmOptions::default()
+            });
+            (output, *jvm.heap().stats())
+        };
+        let (output, stats) = run("test/Garbage");
+        assert_eq!(output,
+                   "2499950000\n4950\n0123456789\nJava heap space\ntrue\n");
+        assert!(stats.young_collections >= 3);
+        assert!(stats.collections > stats.young_collections);
+
+        // Old objects keep the young ones stored to them alive.
+        let (output, stats) = run("test/Generations");
+        assert_eq!(output, "380\n19\n");
+        assert!(stats.young_collections >= 40);
+        assert!(stats.objects_freed >= 90_000);
+    }
+
+    #[test]
+    fn test_vm_strings_collected() {
+        let (jvm, output) = run_in_jvm("test/NumberStrings", JvmOptions {
+            max_heap_size: Some(1 << 20),
+            ..JvmOptions::default()
+        });
+        assert_eq!(output, "99999\n");
+        // The strings alone fill the heap many times over.
+        assert!(jvm.heap().stats().collections >= 10);
+    }
+
+    #[test]
+    fn test_dynamic_constant_survives_collection() {
+        for &collector in [Collector::MarkSweep, Collector::Generational]
+                .iter() {
+            let (jvm, output) = run_in_jvm("test/Condy", JvmOptions {
+                max_heap_size: Some(1 << 20),
+                collector,
+                ..JvmOptions::default()
+            });
+            assert_eq!(output,
+                       "LoadableConstants value java.lang.Object argument\n\
+                        true\n");
+            assert!(jvm.heap().stats().collections >= 1);
+        }
+    }
+
+    #[test]
+    fn test_references() {
+        for &collector in [Collector::MarkSweep, Collector::Generational]
+                .iter() {
+            let (_, output) = run_in_jvm("test/References", JvmOptions {
+                max_heap_size: Some(4 << 20),
+                collector,
+                ..JvmOptions::default()
+            });
+            assert_eq!(output,
+                       "true\ntrue\ntrue\ntrue\ntrue\ntrue\n\
+                        Java heap space\ntrue\ntrue\ntrue\n\
+                        cleaned\ncleaned\nfinalized once\nonce\ntrue\n\
+                        102\n2\nkept\nnull\n");
+        }
+    }
+
+    #[test]
+    fn test_monitors() {
+        let expected = "current thread is not owner\nowned\n\
+                        current thread is not owner\nfailed\n\
+                        current thread is not owner\n\
+                        current thread is not owner\nowned\n\
+                        current thread is not owner\n\
+                        timeout value is negative\n\
+                        current thread is not owner\nNullPointerException\n\
+                        1\n";
+        assert_eq!(run_captured("test/Monitors", &[]),
+                   (Ok(()), expected.to_string(), String::new()));
+    }
+
+    const THREADS_OUTPUT: &str = "main\n1\ntrue\nThread-0\nfalse\n40000\n\
+                                  false\nstarted twice\nitem 0\nitem 1\n\
+                                  item 2\nitem 3\nitem 4\n\
+                                  sleep interrupted\nfalse\ntrue\nfalse\n\
+                                  unparked\n4950\n4950\n4950\n\
+                                  initialized\ninitialized\nfalse\nfalse\n\
+                                  true\ntrue\ndone\n";
+
+    const THREADS_ERRORS: &str = "Exception in thread \"failing\" \
+                                  java.lang.RuntimeException: thrown in \
+                                  thread\n\
+                                  \tat Threads$Failing.run(Threads.java:126)\n";
+
+    #[test]
+    fn test_threads() {
+        // The allocating threads collect garbage while the others wait.
+        for &collector in [Collector::MarkSweep, Collector::Generational]
+                .iter() {
+            assert_eq!(run_captured_with("test/Threads", &[], JvmOptions {
+                           max_heap_size: Some(2 << 20),
+                           collector,
+                           ..JvmOptions::default()
+                       }),
+                       (Ok(()), THREADS_OUTPUT.to_string(),
+                        THREADS_ERRORS.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_scheduled_threads() {
+        let run = |seed: u64, class_name: &str, args: &[&str]| {
+            run_captured_with(class_name, args, JvmOptions {
+                max_heap_size: Some(2 << 20),
+                scheduler: Scheduler::Deterministic(seed),
+                ..JvmOptions::default()
+            })
+        };
+        // Sleeping threads skip ahead in virtual time.
+        for seed in 0 .. 3 {
+            assert_eq!(run(seed, "test/Threads", &[]),
+                       (Ok(()), THREADS_OUTPUT.to_string(),
+                        THREADS_ERRORS.to_string()));
+        }
+
+        // Some schedules lose updates, and each runs the same way again.
+        let options = |_| JvmOptions {
+            class_path: vec![PathBuf::from("test")],
+            stdout: Box::new(io::sink()),
+            stderr: Box::new(io::sink()),
+            ..JvmOptions::default()
+        };
+        let failures = interpreter::explore_schedules(options, "Races",
+                                                      &["count"], 0 .. 20)
+            .unwrap();
+        assert!(!failures.is_empty() && failures.len() < 20);
+        let lost_updates = "java.lang.RuntimeException: lost updates";
+        for &(seed, ref error) in failures.iter() {
+            assert_eq!(*error,
+                       RunError::UncaughtException(lost_updates.to_string()));
+            let (result, output, _) = run(seed, "test/Races", &["count"]);
+            assert_eq!(result, uncaught(lost_updates));
+            assert_eq!(run(seed, "test/Races", &["count"]).1, output);
+        }
+
+        // Threads taking locks in opposite orders can deadlock.
+        let failures = interpreter::explore_schedules(options, "Races",
+                                                      &["deadlock"], 0 .. 20)
+            .unwrap();
+        assert!(!failures.is_empty());
+        for (_, error) in failures.iter() {
+            assert_eq!(*error, RunError::Internal(VmError(
+                "Deadlock: every thread is blocked.".to_string())));
+        }
+
+        // An exception escaping any thread fails a schedule.
+        let failures = interpreter::explore_schedules(options, "Threads",
+                                                      &[], 0 .. 1).unwrap();
+        assert_eq!(failures, vec![(0, RunError::UncaughtException(
+            "java.lang.RuntimeException: thrown in thread".to_string()))]);
+    }
 }
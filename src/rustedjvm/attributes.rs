@@ -1,18 +1,23 @@
 use std::collections::HashMap;
+use std::fmt;
 
 use constants::*;
 use exceptions::*;
+use reader::*;
 
 pub enum Attribute<'a> {
+    BootstrapMethods(BootstrapMethodsAttribute<'a>),
     Code(CodeAttribute<'a>),
+    ConstantValue(ConstantValueAttribute<'a>),
     LineNumberTable(LineNumberTableAttribute<'a>),
     SourceFile(SourceFileAttribute<'a>),
+    Unknown(UnknownAttribute<'a>),
 }
 
 pub struct CodeAttribute<'a> {
     pub attr_name_idx: u16,
     pub attr_name: &'a str,
-    pub attr_length: u16,
+    pub attr_length: u32,
     pub max_stack: u16,
     pub max_locals: u16,
     pub code_length: usize,
@@ -23,55 +28,100 @@ pub struct CodeAttribute<'a> {
     pub attributes: Vec<Attribute<'a>>,
 }
 
+/*
+ * Gives the initial value of a static field, as an index
+ * of a numeric or string constant in the constant pool.
+ */
+pub struct ConstantValueAttribute<'a> {
+    pub attr_name_idx: u16,
+    pub attr_name: &'a str,
+    pub attr_length: u32,
+    pub constant_value_idx: u16,
+}
+
 pub struct LineNumberTableAttribute<'a> {
     pub attr_name_idx: u16,
     pub attr_name: &'a str,
-    pub attr_length: u16,
+    pub attr_length: u32,
     pub line_number_table_length: u16,
     pub line_nbr_table_entries: Vec<LineNumberTableEntry>,
 }
 
+/*
+ * Lists the bootstrap methods that compute the values of dynamic
+ * constants and link invokedynamic call sites, each as the index
+ * of a MethodHandle constant followed by those of its arguments.
+ */
+pub struct BootstrapMethodsAttribute<'a> {
+    pub attr_name_idx: u16,
+    pub attr_name: &'a str,
+    pub attr_length: u32,
+    pub num_bootstrap_methods: u16,
+    pub bootstrap_methods: Vec<BootstrapMethod>,
+}
+
+pub struct BootstrapMethod {
+    pub bootstrap_method_ref: u16,
+    pub bootstrap_arguments: Vec<u16>,
+}
+
 pub struct SourceFileAttribute<'a> {
     pub attr_name_idx: u16,
     pub attr_name: &'a str,
-    pub attr_length: u16,
+    pub attr_length: u32,
     pub src_file_idx: u16,
 }
 
+/*
+ * Attributes that the interpreter has no use for (e.g., StackMapTable,
+ * which is only consulted by the verifier) are skipped over.
+ */
+pub struct UnknownAttribute<'a> {
+    pub attr_name_idx: u16,
+    pub attr_name: &'a str,
+    pub attr_length: u32,
+}
+
 pub struct LineNumberTableEntry {
     pub start_pc: u16,
     pub line_nbr: u16,
 }
 
-impl LineNumberTableEntry {
-    pub fn to_string(&self) -> String {
-        format!("LineNumberTableEntry:\n\
+impl fmt::Display for LineNumberTableEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LineNumberTableEntry:\n\
             \t\t\t\t- start_pc={}\n\
             \t\t\t\t- line_nbr={}\n", self.start_pc, self.line_nbr)
     }
 }
 
 impl<'a> Attribute<'a> {
-    pub fn from_bytecodes(bytecodes: &'a Vec<u8>, byte_idx: &mut usize,
+    pub fn from_bytecodes(bytecodes: &'a [u8], byte_idx: &mut usize,
                           constant_pool: &HashMap<u16, ConstantPoolEntry<'a>>)
                                 -> Attribute<'a> {
 
-        let attr_name_idx = (bytecodes[*byte_idx]
-                             + bytecodes[*byte_idx + 1]) as u16;
-        *byte_idx = *byte_idx + 2;
+        let attr_name_idx = read_u16(bytecodes, byte_idx);
 
         let attr_name = match constant_pool.get(&attr_name_idx) {
-            Some(&ConstantPoolEntry::Utf8(ref s)) => s.utf8_str,
+            Some(ConstantPoolEntry::Utf8(s)) => s.utf8_str,
             Some(_) => panic!("Expected Utf8 constant at \
                                    attribute name idx: {}", attr_name_idx),
             None => panic!("No entry in constant pool at idx: {}", attr_name_idx),
         };
 
         match attr_name {
+            "BootstrapMethods" => Attribute::BootstrapMethods(
+                    BootstrapMethodsAttribute::from_bytecodes(
+                        attr_name_idx, attr_name,
+                        bytecodes, byte_idx)),
             "Code" => Attribute::Code(
                     CodeAttribute::from_bytecodes(
                         attr_name_idx, attr_name,
                         bytecodes, byte_idx, constant_pool)),
+            "ConstantValue" => Attribute::ConstantValue(
+                    ConstantValueAttribute::from_bytecodes(
+                        attr_name_idx, attr_name,
+                        bytecodes, byte_idx)),
             "LineNumberTable" => Attribute::LineNumberTable(
                     LineNumberTableAttribute::from_bytecodes(
                         attr_name_idx, attr_name,
@@ -80,13 +130,49 @@ impl<'a> Attribute<'a> {
                     SourceFileAttribute::from_bytecodes(
                         attr_name_idx, attr_name,
                         bytecodes, byte_idx)),
-            _ => panic!("Unexpected attribute name encountered: {}",
-                    attr_name),
+            _ => {
+                let attr_length = read_u32(bytecodes, byte_idx);
+                *byte_idx += attr_length as usize;
+                Attribute::Unknown(UnknownAttribute {
+                    attr_name_idx,
+                    attr_name,
+                    attr_length,
+                })
+            },
         }
     }
 
-    pub fn to_string(&self) -> String {
+    pub fn name(&self) -> &'a str {
         match *self {
+            Attribute::BootstrapMethods(ref s) => s.attr_name,
+            Attribute::Code(ref s) => s.attr_name,
+            Attribute::ConstantValue(ref s) => s.attr_name,
+            Attribute::LineNumberTable(ref s) => s.attr_name,
+            Attribute::SourceFile(ref s) => s.attr_name,
+            Attribute::Unknown(ref s) => s.attr_name,
+        }
+    }
+}
+
+impl<'a> fmt::Display for Attribute<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Attribute::BootstrapMethods(ref s) => {
+                let mut string_rep = format!("BootstrapMethodsAttribute:\n\
+                    \t- attr_name_idx={}\n\
+                    \t- attr_length={}\n\
+                    \t- num_bootstrap_methods={}\n",
+                    s.attr_name_idx, s.attr_length, s.num_bootstrap_methods);
+
+                for method in s.bootstrap_methods.iter() {
+                    string_rep += &format!(
+                        "\t\tBootstrapMethod: ref={}, args={:?}\n",
+                        method.bootstrap_method_ref,
+                        method.bootstrap_arguments);
+                }
+
+                write!(f, "{}", string_rep)
+            },
             Attribute::Code(ref s) => {
                 let mut string_rep = format!("CodeAttribute:\n\
                     \t\t- attr_name_idx={}\n\
@@ -101,16 +187,23 @@ impl<'a> Attribute<'a> {
                     s.attribute_count);
 
                 for entry in s.exception_table.iter() {
-                    string_rep = string_rep + &format!(
-                        "\t\tException Handler:{}", entry.to_string());
+                    string_rep += &format!(
+                        "\t\tException Handler:{}", entry);
                 }
 
                 for attr in s.attributes.iter() {
-                    string_rep = string_rep + &format!(
-                        "\t\tAttribute:{}", attr.to_string());
+                    string_rep += &format!(
+                        "\t\tAttribute:{}", attr);
                 }
 
-                string_rep
+                write!(f, "{}", string_rep)
+            },
+            Attribute::ConstantValue(ref s) => {
+                write!(f, "ConstantValueAttribute:\n\
+                    \t- attr_name_idx={}\n\
+                    \t- attr_length={}\n\
+                    \t- constant_value_idx={}",
+                    s.attr_name_idx, s.attr_length, s.constant_value_idx)
             },
             Attribute::LineNumberTable(ref s) => {
                 let mut string_rep = format!("LineNumberTableAttribute:\n\
@@ -120,18 +213,24 @@ impl<'a> Attribute<'a> {
                     s.attr_name_idx, s.attr_length, s.line_number_table_length);
 
                 for entry in s.line_nbr_table_entries.iter() {
-                    string_rep = string_rep + &format!(
-                        "\t\t\t{}", entry.to_string());
+                    string_rep += &format!(
+                        "\t\t\t{}", entry);
                 }
 
-                string_rep
+                write!(f, "{}", string_rep)
             },
             Attribute::SourceFile(ref s) => {
-                format!("SourceFileAttribute:\n\
+                write!(f, "SourceFileAttribute:\n\
                     \t- attr_name_idx={}\n\
                     \t- attr_length={}\n\
                     \t- src_file_idx={}",
                     s.attr_name_idx, s.attr_length, s.src_file_idx)
+            },
+            Attribute::Unknown(ref s) => {
+                write!(f, "UnknownAttribute ({}):\n\
+                    \t- attr_name_idx={}\n\
+                    \t- attr_length={}",
+                    s.attr_name, s.attr_name_idx, s.attr_length)
             }
         }
     }
@@ -139,35 +238,22 @@ impl<'a> Attribute<'a> {
 
 impl<'a> CodeAttribute<'a> {
     pub fn from_bytecodes(attr_name_idx: u16, attr_name: &'a str,
-                          bytecodes: &'a Vec<u8>,
+                          bytecodes: &'a [u8],
                           byte_idx: &mut usize,
                           constant_pool: &HashMap<u16, ConstantPoolEntry<'a>>)
                           -> CodeAttribute<'a> {
 
-        let attr_length = bytecodes[*byte_idx..*byte_idx+4]
-                .iter().fold(0, |s, &x| s + x) as u16;
-        *byte_idx = *byte_idx + 4;
-
-        let max_stack = (bytecodes[*byte_idx]
-                         + bytecodes[*byte_idx + 1]) as u16;
-        *byte_idx = *byte_idx + 2;
-
-        let max_locals = (bytecodes[*byte_idx]
-                          + bytecodes[*byte_idx + 1]) as u16;
-        *byte_idx = *byte_idx + 2;
-
-        let code_length: usize = bytecodes[*byte_idx..*byte_idx+4]
-                .iter().fold(0, |s, &x| s + x) as usize;
-        *byte_idx = *byte_idx + 4;
+        let attr_length = read_u32(bytecodes, byte_idx);
+        let max_stack = read_u16(bytecodes, byte_idx);
+        let max_locals = read_u16(bytecodes, byte_idx);
+        let code_length = read_u32(bytecodes, byte_idx) as usize;
 
         let code_start_byte = *byte_idx;
         let code_end_byte = *byte_idx + code_length;
         let code_slice: &[u8] = &bytecodes[code_start_byte..code_end_byte];
         *byte_idx = code_end_byte;
 
-        let exception_table_length: usize = (bytecodes[*byte_idx]
-                                             + bytecodes[*byte_idx + 1]) as usize;
-        *byte_idx = *byte_idx + 2;
+        let exception_table_length = read_u16(bytecodes, byte_idx) as usize;
 
         let mut exception_table = Vec::new();
         for _ in 0 .. exception_table_length {
@@ -175,9 +261,7 @@ impl<'a> CodeAttribute<'a> {
             exception_table.push(entry);
         }
 
-        let attribute_count: u16 = (bytecodes[*byte_idx]
-                                    + bytecodes[*byte_idx + 1]) as u16;
-        *byte_idx = *byte_idx + 2;
+        let attribute_count = read_u16(bytecodes, byte_idx);
 
         let mut attributes: Vec<Attribute> = Vec::new();
         for _ in 0 .. attribute_count {
@@ -187,80 +271,131 @@ impl<'a> CodeAttribute<'a> {
         }
 
         CodeAttribute {
-            attr_name_idx: attr_name_idx,
-            attr_name: attr_name,
-            attr_length: attr_length,
-            max_stack: max_stack,
-            max_locals: max_locals,
-            code_length: code_length,
-            code_slice: code_slice,
-            exception_table_length: exception_table_length,
-            exception_table: exception_table,
-            attribute_count: attribute_count,
-            attributes: attributes,
+            attr_name_idx,
+            attr_name,
+            attr_length,
+            max_stack,
+            max_locals,
+            code_length,
+            code_slice,
+            exception_table_length,
+            exception_table,
+            attribute_count,
+            attributes,
+        }
+    }
+
+    /*
+     * Returns the source line of the instruction at the given pc,
+     * which lies within the range of the LineNumberTable entry with
+     * the greatest start pc not beyond it. Compilers may split the
+     * table across several attributes, and needn't order its entries.
+     */
+    pub fn line_number(&self, pc: usize) -> Option<u16> {
+        self.attributes.iter().filter_map(|attr| match *attr {
+            Attribute::LineNumberTable(ref table) =>
+                Some(table.line_nbr_table_entries.iter()),
+            _ => None,
+        }).flatten()
+            .filter(|entry| entry.start_pc as usize <= pc)
+            .max_by_key(|entry| entry.start_pc)
+            .map(|entry| entry.line_nbr)
+    }
+}
+
+impl<'a> ConstantValueAttribute<'a> {
+    pub fn from_bytecodes(attr_name_idx: u16, attr_name: &'a str,
+                          bytecodes: &[u8],
+                          byte_idx: &mut usize)
+                          -> ConstantValueAttribute<'a> {
+
+        let attr_length = read_u32(bytecodes, byte_idx);
+        let constant_value_idx = read_u16(bytecodes, byte_idx);
+
+        ConstantValueAttribute {
+            attr_name_idx,
+            attr_name,
+            attr_length,
+            constant_value_idx,
         }
     }
 }
 
 impl<'a> LineNumberTableAttribute<'a> {
     pub fn from_bytecodes(attr_name_idx: u16, attr_name: &'a str,
-                          bytecodes: &Vec<u8>,
+                          bytecodes: &[u8],
                           byte_idx: &mut usize)
                           -> LineNumberTableAttribute<'a> {
 
-        let attr_length = bytecodes[*byte_idx..*byte_idx+4]
-                .iter().fold(0, |s, &x| s + x) as u16;
-        *byte_idx = *byte_idx + 4;
-
-        let line_number_table_length: u16 = (bytecodes[*byte_idx]
-                + bytecodes[*byte_idx + 1]) as u16;
-        *byte_idx = *byte_idx + 2;
+        let attr_length = read_u32(bytecodes, byte_idx);
+        let line_number_table_length = read_u16(bytecodes, byte_idx);
 
         let mut line_nbr_table_entries: Vec<LineNumberTableEntry> = Vec::new();
         for _ in 0 .. line_number_table_length {
-            let start_pc: u16 = (bytecodes[*byte_idx]
-                                 + bytecodes[*byte_idx + 1]) as u16;
-            *byte_idx = *byte_idx + 2;
-
-            let line_nbr: u16 = (bytecodes[*byte_idx]
-                                 + bytecodes[*byte_idx + 1]) as u16;
-            *byte_idx = *byte_idx + 2;
+            let start_pc = read_u16(bytecodes, byte_idx);
+            let line_nbr = read_u16(bytecodes, byte_idx);
 
             line_nbr_table_entries.push(LineNumberTableEntry {
-                start_pc: start_pc,
-                line_nbr: line_nbr,
+                start_pc,
+                line_nbr,
             });
         }
 
         LineNumberTableAttribute {
-            attr_name_idx: attr_name_idx,
-            attr_name: attr_name,
-            attr_length: attr_length,
-            line_number_table_length: line_number_table_length,
-            line_nbr_table_entries: line_nbr_table_entries,
+            attr_name_idx,
+            attr_name,
+            attr_length,
+            line_number_table_length,
+            line_nbr_table_entries,
+        }
+    }
+}
+
+impl<'a> BootstrapMethodsAttribute<'a> {
+    pub fn from_bytecodes(attr_name_idx: u16, attr_name: &'a str,
+                          bytecodes: &[u8],
+                          byte_idx: &mut usize)
+                          -> BootstrapMethodsAttribute<'a> {
+
+        let attr_length = read_u32(bytecodes, byte_idx);
+        let num_bootstrap_methods = read_u16(bytecodes, byte_idx);
+
+        let mut bootstrap_methods = Vec::new();
+        for _ in 0 .. num_bootstrap_methods {
+            let bootstrap_method_ref = read_u16(bytecodes, byte_idx);
+            let num_bootstrap_arguments = read_u16(bytecodes, byte_idx);
+            let bootstrap_arguments = (0 .. num_bootstrap_arguments)
+                .map(|_| read_u16(bytecodes, byte_idx)).collect();
+            bootstrap_methods.push(BootstrapMethod {
+                bootstrap_method_ref,
+                bootstrap_arguments,
+            });
+        }
+
+        BootstrapMethodsAttribute {
+            attr_name_idx,
+            attr_name,
+            attr_length,
+            num_bootstrap_methods,
+            bootstrap_methods,
         }
     }
 }
 
 impl<'a> SourceFileAttribute<'a> {
     pub fn from_bytecodes(attr_name_idx: u16, attr_name: &'a str,
-                          bytecodes: &Vec<u8>,
+                          bytecodes: &[u8],
                           byte_idx: &mut usize)
                           -> SourceFileAttribute<'a> {
 
-        let attr_length = bytecodes[*byte_idx..*byte_idx+4]
-                .iter().fold(0, |s, &x| s + x) as u16;
-        *byte_idx = *byte_idx + 4;
-
-        let src_file_idx: u16 = (bytecodes[*byte_idx]
-                            + bytecodes[*byte_idx + 1]) as u16;
-        *byte_idx = *byte_idx + 2;
+        let attr_length = read_u32(bytecodes, byte_idx);
+        let src_file_idx = read_u16(bytecodes, byte_idx);
 
         SourceFileAttribute {
-            attr_name_idx: attr_name_idx,
-            attr_name: attr_name,
-            attr_length: attr_length,
-            src_file_idx: src_file_idx,
+            attr_name_idx,
+            attr_name,
+            attr_length,
+            src_file_idx,
         }
     }
 }
@@ -1,49 +1,82 @@
-use std::error::Error;
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::prelude::*;
 use std::path::Path;
-use std::collections::HashMap;
 
 use constants::*;
+use fields::*;
 use methods::*;
 use attributes::*;
+use reader::*;
 
-pub struct ClassFile<'a> {
-    pub class_name: &'a String,
+pub const ACC_SUPER: u16 = 0x0020;
+pub const ACC_INTERFACE: u16 = 0x0200;
+
+pub struct ClassFile {
+    pub class_name: String,
     pub buffer: Vec<u8>,
 }
 
 pub struct Class<'a> {
+    pub name: &'a str,
+    pub super_class: Option<&'a str>,
+    pub interfaces: Vec<&'a str>,
+    pub access_flags: u16,
     pub constant_pool: HashMap<u16, ConstantPoolEntry<'a>>,
-    pub methods: HashMap<&'a str, Method<'a>>,
+    pub fields: Vec<Field<'a>>,
+    pub methods: Vec<Method<'a>>,
+    pub attributes: Vec<Attribute<'a>>,
+}
+
+/*
+ * A symbolic reference to a field or method, as
+ * named by a FieldRef, MethodRef or InterfaceMethodRef constant.
+ */
+pub struct MemberRef<'a> {
+    pub class_name: &'a str,
+    pub name: &'a str,
+    pub descriptor: &'a str,
 }
 
-impl<'a> ClassFile<'a> {
-    pub fn new(class_name: &'a String) -> ClassFile {
+impl ClassFile {
+    pub fn new(class_name: &str) -> ClassFile {
         let class_file_name = &format!("{}.class", class_name);
         let path = Path::new(class_file_name);
         let display = path.display();
 
-        let mut file = match File::open(&path) {
+        let mut file = match File::open(path) {
             Err(why) => panic!("[ERROR] Unable to open {}: {}",
-                    display, Error::description(&why)),
+                    display, why),
             Ok(file) => file,
         };
 
         let mut bytecodes = Vec::new();
-        match file.read_to_end(&mut bytecodes) {
-            Err(why) => panic!("[ERROR] Unable to read {}: {}",
-                    display, Error::description(&why)),
-            Ok(_) => println!("{} contains {} bytes.", display, bytecodes.len()),
-        };
+        if let Err(why) = file.read_to_end(&mut bytecodes) {
+            panic!("[ERROR] Unable to read {}: {}", display, why);
+        }
 
         ClassFile {
-            class_name: class_name,
-            buffer: bytecodes.clone(),
+            class_name: class_name.to_string(),
+            buffer: bytecodes,
         }
     }
 
-    pub fn parse(&self) -> Class {
+    pub fn from_bytes(class_name: &str, bytecodes: &[u8]) -> ClassFile {
+        ClassFile {
+            class_name: class_name.to_string(),
+            buffer: bytecodes.to_vec(),
+        }
+    }
+
+    /*
+     * Parses the class file; when tracing, its contents are dumped
+     * to standard output as they're parsed.
+     */
+    pub fn parse(&self, trace: bool) -> Class<'_> {
+        if trace {
+            println!("{} contains {} bytes.", self.class_name,
+                     self.buffer.len());
+        }
 
         /*
          * TODO(mquinn): The magic header check is best implemented
@@ -55,35 +88,43 @@ impl<'a> ClassFile<'a> {
             && self.buffer[1] == 0xfe
             && self.buffer[2] == 0xba
             && self.buffer[3] == 0xbe {
-            println!("Magic header is present.");
+            if trace {
+                println!("Magic header is present.");
+            }
         } else {
             panic!("[ERROR] File is not a valid class \
                         file (magic header absent).");
         };
 
-        let minor_version = self.buffer[4] + self.buffer[5];
-        let major_version = self.buffer[6] + self.buffer[7];
+        let mut byte_idx = 4;
+        let minor_version = read_u16(&self.buffer, &mut byte_idx);
+        let major_version = read_u16(&self.buffer, &mut byte_idx);
 
-        println!("Major version: {}, minor version: {}",
-                 major_version, minor_version);
+        if trace {
+            println!("Major version: {}, minor version: {}",
+                     major_version, minor_version);
+        }
 
-        let constant_pool_size = self.buffer[8] + self.buffer[9];
+        let constant_pool_size = read_u16(&self.buffer, &mut byte_idx);
 
         // The JVM spec states that the number of
         // entries in the constant pool is actually
         // one less than the actual count, hence the subtraction by 1:
-        println!("BEGIN Constant Pool (Count: {})", constant_pool_size - 1);
-        println!("===================================================");
+        if trace {
+            println!("BEGIN Constant Pool (Count: {})",
+                     constant_pool_size - 1);
+            println!("===================================================");
+        }
 
-        let mut byte_idx = 10;
         let indent = "  ";
         let mut constant_pool = HashMap::new();
 
         // The JVM spec states that the number of entries in the constant
         // pool is actually one less than the actual count, and that entries
-        // start at index 1. Hence: "1 .. constant_pool_size
-        // (latter bound is exclusive)"
-        for n in 1 .. constant_pool_size {
+        // start at index 1. Hence indices run from 1 up to, but excluding,
+        // constant_pool_size; long and double entries consume two indices.
+        let mut n = 1;
+        while n < constant_pool_size {
             let const_pool_entry = match ConstantPoolEntry::from_bytecodes(
                     &self.buffer, &mut byte_idx) {
                 Ok(entry) => entry,
@@ -91,77 +132,239 @@ impl<'a> ClassFile<'a> {
                                      constant pool entry: {:?}", error),
             };
 
-            println!("{}{}:\t{}", indent, n, const_pool_entry.to_string());
-            constant_pool.insert(n as u16, const_pool_entry);
+            if trace {
+                println!("{}{}:\t{}", indent, n, const_pool_entry);
+            }
+            let slot_count = const_pool_entry.slot_count();
+            constant_pool.insert(n, const_pool_entry);
+            n += slot_count;
         };
 
-        println!("END Constant Pool");
-        println!("===================================================");
+        if trace {
+            println!("END Constant Pool");
+            println!("===================================================");
+        }
 
-        let access_flags = self.buffer[byte_idx] + self.buffer[byte_idx + 1];
-        byte_idx = byte_idx + 2;
-        println!("Access flags: 0x{:x}", access_flags);
+        let access_flags = read_u16(&self.buffer, &mut byte_idx);
+        if trace {
+            println!("Access flags: 0x{:x}", access_flags);
+        }
 
         let this_class_const_pool_entry_idx =
-            self.buffer[byte_idx] + self.buffer[byte_idx + 1];
-        byte_idx = byte_idx + 2;
-        println!("This class' constant pool entry idx: 0x{:x}",
-                 this_class_const_pool_entry_idx);
+            read_u16(&self.buffer, &mut byte_idx);
+        if trace {
+            println!("This class' constant pool entry idx: 0x{:x}",
+                     this_class_const_pool_entry_idx);
+        }
+        let name = class_name_at(&constant_pool,
+                                 this_class_const_pool_entry_idx);
 
+        // Only java/lang/Object lacks a superclass, in which
+        // case the index is zero.
         let super_class_const_pool_entry_idx =
-            self.buffer[byte_idx] + self.buffer[byte_idx + 1];
-        byte_idx = byte_idx + 2;
-        println!("Super class' constant pool entry idx: 0x{:x}",
-                 super_class_const_pool_entry_idx);
+            read_u16(&self.buffer, &mut byte_idx);
+        if trace {
+            println!("Super class' constant pool entry idx: 0x{:x}",
+                     super_class_const_pool_entry_idx);
+        }
+        let super_class = match super_class_const_pool_entry_idx {
+            0 => None,
+            idx => Some(class_name_at(&constant_pool, idx)),
+        };
 
-        let interface_count = self.buffer[byte_idx] + self.buffer[byte_idx + 1];
-        byte_idx = byte_idx + 2;
-        println!("Interface count: {}", interface_count);
+        let interface_count = read_u16(&self.buffer, &mut byte_idx);
+        if trace {
+            println!("Interface count: {}", interface_count);
+        }
 
-        assert!(interface_count == 0,
-                "[ERROR] Classes w/ interfaces are not yet supported.");
+        let mut interfaces = Vec::new();
+        for _ in 0 .. interface_count {
+            let interface_idx = read_u16(&self.buffer, &mut byte_idx);
+            interfaces.push(class_name_at(&constant_pool, interface_idx));
+        }
 
-        let field_count = self.buffer[byte_idx] + self.buffer[byte_idx + 1];
-        byte_idx = byte_idx + 2;
-        println!("Field count: {}", field_count);
+        let field_count = read_u16(&self.buffer, &mut byte_idx);
+        if trace {
+            println!("Field count: {}", field_count);
+            println!("BEGIN Fields (Count: {})", field_count);
+            println!("===================================================");
+        }
 
-        assert!(field_count == 0,
-                "[ERROR] Classes w/ fields are not yet supported.");
+        let mut fields = Vec::new();
+        for n in 0 .. field_count {
+            let field = Field::from_bytecodes(
+                &self.buffer, &mut byte_idx, &constant_pool);
+            if trace {
+                println!("{}{}:\t{}", indent, n, field);
+            }
+            fields.push(field);
+        };
 
-        let method_count = self.buffer[byte_idx] + self.buffer[byte_idx + 1];
-        byte_idx = byte_idx + 2;
-        println!("Method count: {}", method_count);
+        if trace {
+            println!("END Fields");
+            println!("===================================================");
+        }
 
-        println!("BEGIN Methods (Count: {})", method_count);
-        println!("===================================================");
+        let method_count = read_u16(&self.buffer, &mut byte_idx);
+        if trace {
+            println!("Method count: {}", method_count);
+            println!("BEGIN Methods (Count: {})", method_count);
+            println!("===================================================");
+        }
 
-        let mut methods = HashMap::new();
+        let mut methods = Vec::new();
         for n in 0 .. method_count {
             let method = Method::from_bytecodes(
                 &self.buffer, &mut byte_idx, &constant_pool);
-            println!("{}{}:\t{}", indent, n, method.to_string());
-            println!("Byte idx is 0x{:x}", byte_idx);
-            methods.insert(method.name, method);
+            if trace {
+                println!("{}{}:\t{}", indent, n, method);
+                println!("Byte idx is 0x{:x}", byte_idx);
+            }
+            methods.push(method);
         };
 
-        println!("END Methods");
-        println!("===================================================");
+        if trace {
+            println!("END Methods");
+            println!("===================================================");
+        }
 
-        let src_file_attr_count = self.buffer[byte_idx] + self.buffer[byte_idx + 1];
-        byte_idx = byte_idx + 2;
-        println!("Source file attr count: {}", src_file_attr_count);
+        let attr_count = read_u16(&self.buffer, &mut byte_idx);
+        if trace {
+            println!("Class attr count: {}", attr_count);
+        }
 
-        for _ in 0 .. src_file_attr_count {
-            let src_file_attr = Attribute::from_bytecodes(
+        let mut attributes = Vec::new();
+        for _ in 0 .. attr_count {
+            let attr = Attribute::from_bytecodes(
                 &self.buffer, &mut byte_idx, &constant_pool);
-            println!("{}", src_file_attr.to_string());
+            if trace {
+                println!("{}", attr);
+            }
+            attributes.push(attr);
         }
 
-        println!("Byte idx is 0x{:x}", byte_idx);
+        if trace {
+            println!("Byte idx is 0x{:x}", byte_idx);
+        }
 
         Class {
-            constant_pool: constant_pool,
-            methods: methods,
+            name,
+            super_class,
+            interfaces,
+            access_flags,
+            constant_pool,
+            fields,
+            methods,
+            attributes,
         }
     }
 }
+
+fn class_name_at<'a>(constant_pool: &HashMap<u16, ConstantPoolEntry<'a>>,
+                     class_idx: u16) -> &'a str {
+    let name_idx = match constant_pool.get(&class_idx) {
+        Some(ConstantPoolEntry::Class(e)) => e.name_idx,
+        _ => panic!("[ERROR] Expected class in constant pool \
+                     at index {}.", class_idx),
+    };
+    match constant_pool.get(&name_idx) {
+        Some(ConstantPoolEntry::Utf8(e)) => e.utf8_str,
+        _ => panic!("[ERROR] Expected utf8 in constant pool \
+                     at index {}.", name_idx),
+    }
+}
+
+impl<'a> Class<'a> {
+    pub fn utf8(&self, idx: u16) -> &'a str {
+        match self.constant_pool.get(&idx) {
+            Some(ConstantPoolEntry::Utf8(e)) => e.utf8_str,
+            _ => panic!("[ERROR] Expected utf8 in constant pool \
+                         at index {}.", idx),
+        }
+    }
+
+    pub fn class_name(&self, idx: u16) -> &'a str {
+        class_name_at(&self.constant_pool, idx)
+    }
+
+    /*
+     * Returns the bootstrap method at the given index of the
+     * class's BootstrapMethods attribute.
+     */
+    pub fn bootstrap_method(&self, idx: u16) -> &BootstrapMethod {
+        let methods = self.attributes.iter().filter_map(|attr| match *attr {
+            Attribute::BootstrapMethods(ref attr) =>
+                Some(&attr.bootstrap_methods),
+            _ => None,
+        }).next();
+        match methods.and_then(|methods| methods.get(idx as usize)) {
+            Some(method) => method,
+            None => panic!("[ERROR] {} lacks bootstrap method {}.",
+                           self.name, idx),
+        }
+    }
+
+    /*
+     * Returns the name of the source file the class was
+     * compiled from, if the compiler recorded it.
+     */
+    pub fn source_file(&self) -> Option<&'a str> {
+        self.attributes.iter().filter_map(|attr| match *attr {
+            Attribute::SourceFile(ref attr) => Some(self.utf8(attr.src_file_idx)),
+            _ => None,
+        }).next()
+    }
+
+    /*
+     * Traverses a FieldRef, MethodRef or InterfaceMethodRef
+     * constant, along with the Class and NameAndType constants
+     * it points to, to determine the member being referenced.
+     */
+    pub fn member_ref(&self, idx: u16) -> MemberRef<'a> {
+        let (class_idx, name_and_type_idx) =
+                match self.constant_pool.get(&idx) {
+            Some(ConstantPoolEntry::FieldRef(e)) =>
+                (e.class_idx, e.name_and_type_idx),
+            Some(ConstantPoolEntry::MethodRef(e)) =>
+                (e.class_idx, e.name_and_type_idx),
+            Some(ConstantPoolEntry::InterfaceMethodRef(e)) =>
+                (e.class_idx, e.name_and_type_idx),
+            _ => panic!("[ERROR] Expected field or method ref in \
+                         constant pool at index {}.", idx),
+        };
+        let (name, descriptor) = self.name_and_type(name_and_type_idx);
+        MemberRef {
+            class_name: self.class_name(class_idx),
+            name,
+            descriptor,
+        }
+    }
+
+    /*
+     * Returns the name and descriptor named by a NameAndType constant.
+     */
+    pub fn name_and_type(&self, idx: u16) -> (&'a str, &'a str) {
+        match self.constant_pool.get(&idx) {
+            Some(ConstantPoolEntry::NameAndType(e)) =>
+                (self.utf8(e.name_idx), self.utf8(e.descriptor_idx)),
+            _ => panic!("[ERROR] Expected name/type in constant pool \
+                         at index {}.", idx),
+        }
+    }
+
+    pub fn method(&self, name: &str, descriptor: &str)
+            -> Option<&Method<'a>> {
+        self.methods.iter()
+            .find(|m| m.name == name && m.descriptor == descriptor)
+    }
+
+    pub fn field(&self, name: &str, descriptor: &str)
+            -> Option<&Field<'a>> {
+        self.fields.iter()
+            .find(|f| f.name == name && f.descriptor == descriptor)
+    }
+
+    pub fn is_interface(&self) -> bool {
+        self.access_flags & ACC_INTERFACE != 0
+    }
+}
@@ -1,12 +1,26 @@
+use std::fmt;
 use std::str;
 
+use reader::*;
+
 pub enum ConstantPoolEntry<'a> {
     Utf8(Utf8Constant<'a>),
+    Integer(i32),
+    Float(f32),
+    Long(i64),
+    Double(f64),
     Class(ClassConstant),
     String(StringConstant),
     FieldRef(FieldRefConstant),
     MethodRef(MethodRefConstant),
+    InterfaceMethodRef(MethodRefConstant),
     NameAndType(NameAndTypeConstant),
+    MethodHandle(MethodHandleConstant),
+    MethodType(MethodTypeConstant),
+    Dynamic(DynamicConstant),
+    InvokeDynamic(DynamicConstant),
+    Module(ModuleConstant),
+    Package(ModuleConstant),
 }
 
 pub struct ClassConstant {
@@ -22,6 +36,10 @@ pub struct FieldRefConstant {
     pub name_and_type_idx: u16,
 }
 
+/*
+ * Interface method refs share their layout with
+ * class method refs, so both are represented by this struct.
+ */
 pub struct MethodRefConstant {
     pub class_idx: u16,
     pub name_and_type_idx: u16,
@@ -32,6 +50,46 @@ pub struct NameAndTypeConstant {
     pub descriptor_idx: u16,
 }
 
+/*
+ * The reference kinds of method handles, as listed in
+ * table 5.4.3.5-A of the JVM specification.
+ */
+pub const REF_GET_FIELD: u8 = 1;
+pub const REF_GET_STATIC: u8 = 2;
+pub const REF_PUT_FIELD: u8 = 3;
+pub const REF_PUT_STATIC: u8 = 4;
+pub const REF_INVOKE_VIRTUAL: u8 = 5;
+pub const REF_INVOKE_STATIC: u8 = 6;
+pub const REF_INVOKE_SPECIAL: u8 = 7;
+pub const REF_NEW_INVOKE_SPECIAL: u8 = 8;
+pub const REF_INVOKE_INTERFACE: u8 = 9;
+
+pub struct MethodHandleConstant {
+    pub reference_kind: u8,
+    pub reference_idx: u16,
+}
+
+pub struct MethodTypeConstant {
+    pub descriptor_idx: u16,
+}
+
+/*
+ * Dynamically-computed constants and invokedynamic call sites
+ * share their layout, so both are represented by this struct.
+ */
+pub struct DynamicConstant {
+    pub bootstrap_method_attr_idx: u16,
+    pub name_and_type_idx: u16,
+}
+
+/*
+ * Module and package constants share their layout,
+ * so both are represented by this struct.
+ */
+pub struct ModuleConstant {
+    pub name_idx: u16,
+}
+
 // Lifetime must be made explict
 // here because utf8_str is only valid
 // for as long as the underlying bytecode array lives.
@@ -40,11 +98,19 @@ pub struct Utf8Constant<'a> {
 }
 
 impl<'a> ConstantPoolEntry<'a> {
-    pub fn from_bytecodes(bytecodes: &'a Vec<u8>, byte_idx: &mut usize)
+    pub fn from_bytecodes(bytecodes: &'a [u8], byte_idx: &mut usize)
             -> Result<ConstantPoolEntry<'a>, String> {
         match bytecodes[*byte_idx] {
             0x1 => Ok(ConstantPoolEntry::Utf8(
                     Utf8Constant::from_bytecodes(bytecodes, byte_idx))),
+            0x3 => Ok(ConstantPoolEntry::Integer(
+                    read_numeric(bytecodes, byte_idx, 4) as i32)),
+            0x4 => Ok(ConstantPoolEntry::Float(f32::from_bits(
+                    read_numeric(bytecodes, byte_idx, 4) as u32))),
+            0x5 => Ok(ConstantPoolEntry::Long(
+                    read_numeric(bytecodes, byte_idx, 8) as i64)),
+            0x6 => Ok(ConstantPoolEntry::Double(f64::from_bits(
+                    read_numeric(bytecodes, byte_idx, 8)))),
             0x7 => Ok(ConstantPoolEntry::Class(
                     ClassConstant::from_bytecodes(bytecodes, byte_idx))),
             0x8 => Ok(ConstantPoolEntry::String(
@@ -53,14 +119,54 @@ impl<'a> ConstantPoolEntry<'a> {
                     FieldRefConstant::from_bytecodes(bytecodes, byte_idx))),
             0xa => Ok(ConstantPoolEntry::MethodRef(
                     MethodRefConstant::from_bytecodes(bytecodes, byte_idx))),
+            0xb => Ok(ConstantPoolEntry::InterfaceMethodRef(
+                    MethodRefConstant::from_bytecodes(bytecodes, byte_idx))),
             0xc => Ok(ConstantPoolEntry::NameAndType(
                     NameAndTypeConstant::from_bytecodes(bytecodes, byte_idx))),
+            0xf => Ok(ConstantPoolEntry::MethodHandle(
+                    MethodHandleConstant::from_bytecodes(bytecodes, byte_idx))),
+            0x10 => Ok(ConstantPoolEntry::MethodType(
+                    MethodTypeConstant::from_bytecodes(bytecodes, byte_idx))),
+            0x11 => Ok(ConstantPoolEntry::Dynamic(
+                    DynamicConstant::from_bytecodes(bytecodes, byte_idx))),
+            0x12 => Ok(ConstantPoolEntry::InvokeDynamic(
+                    DynamicConstant::from_bytecodes(bytecodes, byte_idx))),
+            0x13 => Ok(ConstantPoolEntry::Module(
+                    ModuleConstant::from_bytecodes(bytecodes, byte_idx))),
+            0x14 => Ok(ConstantPoolEntry::Package(
+                    ModuleConstant::from_bytecodes(bytecodes, byte_idx))),
             unsupported_code => Err(format!(
                     "Unsupported bytecode 0x{:x}", unsupported_code)),
         }
     }
 
-    pub fn to_string(&self) -> String {
+    /*
+     * Long and double constants take up two entries in the
+     * constant pool; the entry following them is unusable.
+     */
+    pub fn slot_count(&self) -> u16 {
+        match *self {
+            ConstantPoolEntry::Long(_) | ConstantPoolEntry::Double(_) => 2,
+            _ => 1,
+        }
+    }
+}
+
+/*
+ * Reads the big-endian bytes of a numeric constant
+ * following its tag byte.
+ */
+fn read_numeric(bytecodes: &[u8], byte_idx: &mut usize, length: usize)
+        -> u64 {
+    let start_byte = *byte_idx + 1;
+    let end_byte = start_byte + length;
+    *byte_idx = end_byte;
+    bytecodes[start_byte..end_byte].iter()
+        .fold(0, |s, &x| (s << 8) | x as u64)
+}
+
+impl<'a> fmt::Display for ConstantPoolEntry<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             /*
              * For all of the below matches, a reference to
@@ -68,21 +174,50 @@ impl<'a> ConstantPoolEntry<'a> {
              * borrowed self, and thus cannot take
              * ownership of anything owned by self.
              */
-            ConstantPoolEntry::Utf8(ref s) => format!(
+            ConstantPoolEntry::Utf8(ref s) => write!(f,
                 "Utf8Constant[utf8_str=\"{}\"]", s.utf8_str),
-            ConstantPoolEntry::Class(ref s) => format!(
+            ConstantPoolEntry::Integer(value) => write!(f,
+                "IntegerConstant[value={}]", value),
+            ConstantPoolEntry::Float(value) => write!(f,
+                "FloatConstant[value={}]", value),
+            ConstantPoolEntry::Long(value) => write!(f,
+                "LongConstant[value={}]", value),
+            ConstantPoolEntry::Double(value) => write!(f,
+                "DoubleConstant[value={}]", value),
+            ConstantPoolEntry::Class(ref s) => write!(f,
                 "ClassConstant[name_index={}]", s.name_idx),
-            ConstantPoolEntry::String(ref s) => format!(
+            ConstantPoolEntry::String(ref s) => write!(f,
                 "StringConstant[string_index={}]", s.string_idx),
-            ConstantPoolEntry::FieldRef(ref s) => format!(
+            ConstantPoolEntry::FieldRef(ref s) => write!(f,
                 "FieldRefConstant[class_idx={}, name_and_type_idx={}]",
                     s.class_idx, s.name_and_type_idx),
-            ConstantPoolEntry::MethodRef(ref s) => format!(
+            ConstantPoolEntry::MethodRef(ref s) => write!(f,
                 "MethodRefConstant[class_idx={}, name_and_type_idx={}]",
                     s.class_idx, s.name_and_type_idx),
-            ConstantPoolEntry::NameAndType(ref s) => format!(
+            ConstantPoolEntry::InterfaceMethodRef(ref s) => write!(f,
+                "InterfaceMethodRefConstant[class_idx={}, \
+                    name_and_type_idx={}]",
+                    s.class_idx, s.name_and_type_idx),
+            ConstantPoolEntry::NameAndType(ref s) => write!(f,
                 "NameAndTypeConstant[name_idx={}, descriptor_idx={}]",
                     s.name_idx, s.descriptor_idx),
+            ConstantPoolEntry::MethodHandle(ref s) => write!(f,
+                "MethodHandleConstant[reference_kind={}, reference_idx={}]",
+                    s.reference_kind, s.reference_idx),
+            ConstantPoolEntry::MethodType(ref s) => write!(f,
+                "MethodTypeConstant[descriptor_idx={}]", s.descriptor_idx),
+            ConstantPoolEntry::Dynamic(ref s) => write!(f,
+                "DynamicConstant[bootstrap_method_attr_idx={}, \
+                    name_and_type_idx={}]",
+                    s.bootstrap_method_attr_idx, s.name_and_type_idx),
+            ConstantPoolEntry::InvokeDynamic(ref s) => write!(f,
+                "InvokeDynamicConstant[bootstrap_method_attr_idx={}, \
+                    name_and_type_idx={}]",
+                    s.bootstrap_method_attr_idx, s.name_and_type_idx),
+            ConstantPoolEntry::Module(ref s) => write!(f,
+                "ModuleConstant[name_idx={}]", s.name_idx),
+            ConstantPoolEntry::Package(ref s) => write!(f,
+                "PackageConstant[name_idx={}]", s.name_idx),
         }
     }
 }
@@ -93,89 +228,118 @@ impl<'a> Utf8Constant<'a> {
     // array with the returned struct,
     // because the string slice reference is only
     // valid as long as the bytecode array is alive.
-    pub fn from_bytecodes(bytecodes: &'a Vec<u8>,
+    pub fn from_bytecodes(bytecodes: &'a [u8],
                           byte_idx: &mut usize) -> Utf8Constant<'a> {
+        *byte_idx += 1;
         // Bytecodes are u8, but slicing requires arguments of type usize.
-        let length: usize = (bytecodes[*byte_idx + 1]
-                             + bytecodes[*byte_idx + 2]) as usize;
-        let utf8_start_byte = *byte_idx + 3;
-        let utf8_end_byte = *byte_idx + 3 + length;
+        let length = read_u16(bytecodes, byte_idx) as usize;
+        let utf8_start_byte = *byte_idx;
+        let utf8_end_byte = *byte_idx + length;
         let utf8_byte_slice: &[u8] = &bytecodes[utf8_start_byte..utf8_end_byte];
         let utf8_str = match str::from_utf8(utf8_byte_slice) {
                 Ok(n) => n,
                 Err(e) => panic!("[ERROR] Expected utf8 string, \
                                  but is not valid: {:?}", e),
         };
-        let entry = Utf8Constant {
-            utf8_str: utf8_str,
-        };
         *byte_idx = utf8_end_byte;
-        entry
+        Utf8Constant {
+            utf8_str,
+        }
     }
 }
 
 impl ClassConstant {
-    pub fn from_bytecodes(bytecodes: &Vec<u8>,
+    pub fn from_bytecodes(bytecodes: &[u8],
                           byte_idx: &mut usize) -> ClassConstant {
-        let entry = ClassConstant {
-            name_idx: (bytecodes[*byte_idx + 1]
-                       + bytecodes[*byte_idx + 2]) as u16,
-        };
-        *byte_idx = *byte_idx + 3;
-        entry
+        *byte_idx += 1;
+        ClassConstant {
+            name_idx: read_u16(bytecodes, byte_idx),
+        }
     }
 }
 
 impl StringConstant {
-    pub fn from_bytecodes(bytecodes: &Vec<u8>,
+    pub fn from_bytecodes(bytecodes: &[u8],
                           byte_idx: &mut usize) -> StringConstant {
-        let entry = StringConstant {
-            string_idx: (bytecodes[*byte_idx + 1]
-                         + bytecodes[*byte_idx + 2]) as u16,
-        };
-        *byte_idx = *byte_idx + 3;
-        entry
+        *byte_idx += 1;
+        StringConstant {
+            string_idx: read_u16(bytecodes, byte_idx),
+        }
     }
 }
 
 impl FieldRefConstant {
-    pub fn from_bytecodes(bytecodes: &Vec<u8>,
+    pub fn from_bytecodes(bytecodes: &[u8],
                           byte_idx: &mut usize) -> FieldRefConstant {
-        let entry = FieldRefConstant {
-            class_idx: (bytecodes[*byte_idx + 1]
-                        + bytecodes[*byte_idx + 2]) as u16,
-            name_and_type_idx: (bytecodes[*byte_idx + 3]
-                                + bytecodes[*byte_idx + 4]) as u16,
-        };
-        *byte_idx = *byte_idx + 5;
-        entry
+        *byte_idx += 1;
+        FieldRefConstant {
+            class_idx: read_u16(bytecodes, byte_idx),
+            name_and_type_idx: read_u16(bytecodes, byte_idx),
+        }
     }
 }
 
 impl MethodRefConstant {
-    pub fn from_bytecodes(bytecodes: &Vec<u8>,
+    pub fn from_bytecodes(bytecodes: &[u8],
                           byte_idx: &mut usize) -> MethodRefConstant {
-        let entry = MethodRefConstant {
-            class_idx: (bytecodes[*byte_idx + 1]
-                        + bytecodes[*byte_idx + 2]) as u16,
-            name_and_type_idx: (bytecodes[*byte_idx + 3]
-                                + bytecodes[*byte_idx + 4]) as u16,
-        };
-        *byte_idx = *byte_idx + 5;
-        entry
+        *byte_idx += 1;
+        MethodRefConstant {
+            class_idx: read_u16(bytecodes, byte_idx),
+            name_and_type_idx: read_u16(bytecodes, byte_idx),
+        }
     }
 }
 
 impl NameAndTypeConstant {
-    pub fn from_bytecodes(bytecodes: &Vec<u8>,
+    pub fn from_bytecodes(bytecodes: &[u8],
                           byte_idx: &mut usize) -> NameAndTypeConstant {
-        let entry = NameAndTypeConstant {
-            name_idx: (bytecodes[*byte_idx + 1]
-                       + bytecodes[*byte_idx + 2]) as u16,
-            descriptor_idx: (bytecodes[*byte_idx + 3]
-                             + bytecodes[*byte_idx + 4]) as u16,
-        };
-        *byte_idx = *byte_idx + 5;
-        entry
+        *byte_idx += 1;
+        NameAndTypeConstant {
+            name_idx: read_u16(bytecodes, byte_idx),
+            descriptor_idx: read_u16(bytecodes, byte_idx),
+        }
+    }
+}
+
+impl MethodHandleConstant {
+    pub fn from_bytecodes(bytecodes: &[u8],
+                          byte_idx: &mut usize) -> MethodHandleConstant {
+        let reference_kind = bytecodes[*byte_idx + 1];
+        *byte_idx += 2;
+        MethodHandleConstant {
+            reference_kind,
+            reference_idx: read_u16(bytecodes, byte_idx),
+        }
+    }
+}
+
+impl MethodTypeConstant {
+    pub fn from_bytecodes(bytecodes: &[u8],
+                          byte_idx: &mut usize) -> MethodTypeConstant {
+        *byte_idx += 1;
+        MethodTypeConstant {
+            descriptor_idx: read_u16(bytecodes, byte_idx),
+        }
+    }
+}
+
+impl DynamicConstant {
+    pub fn from_bytecodes(bytecodes: &[u8],
+                          byte_idx: &mut usize) -> DynamicConstant {
+        *byte_idx += 1;
+        DynamicConstant {
+            bootstrap_method_attr_idx: read_u16(bytecodes, byte_idx),
+            name_and_type_idx: read_u16(bytecodes, byte_idx),
+        }
+    }
+}
+
+impl ModuleConstant {
+    pub fn from_bytecodes(bytecodes: &[u8],
+                          byte_idx: &mut usize) -> ModuleConstant {
+        *byte_idx += 1;
+        ModuleConstant {
+            name_idx: read_u16(bytecodes, byte_idx),
+        }
     }
 }
@@ -0,0 +1,161 @@
+use std::fmt;
+
+/*
+ * Field and method descriptors, as described in
+ * section 4.3 of the JVM specification.
+ */
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum FieldType {
+    Byte,
+    Char,
+    Double,
+    Float,
+    Int,
+    Long,
+    Short,
+    Boolean,
+    Object(String),
+    Array(Box<FieldType>),
+}
+
+pub struct MethodDescriptor {
+    pub params: Vec<FieldType>,
+    // None represents a void return type.
+    pub ret: Option<FieldType>,
+}
+
+impl FieldType {
+    pub fn parse(descriptor: &str) -> Result<FieldType, String> {
+        let mut chars = descriptor.chars().peekable();
+        let field_type = FieldType::from_chars(descriptor, &mut chars)?;
+        match chars.next() {
+            None => Ok(field_type),
+            Some(_) => Err(format!("Trailing characters in field \
+                                    descriptor: {}", descriptor)),
+        }
+    }
+
+    fn from_chars<I>(descriptor: &str, chars: &mut I)
+            -> Result<FieldType, String>
+            where I: Iterator<Item = char> {
+        match chars.next() {
+            Some('B') => Ok(FieldType::Byte),
+            Some('C') => Ok(FieldType::Char),
+            Some('D') => Ok(FieldType::Double),
+            Some('F') => Ok(FieldType::Float),
+            Some('I') => Ok(FieldType::Int),
+            Some('J') => Ok(FieldType::Long),
+            Some('S') => Ok(FieldType::Short),
+            Some('Z') => Ok(FieldType::Boolean),
+            Some('L') => {
+                let class_name: String = chars
+                    .take_while(|&c| c != ';').collect();
+                if class_name.is_empty() {
+                    return Err(format!("Empty class name in \
+                                        descriptor: {}", descriptor));
+                }
+                Ok(FieldType::Object(class_name))
+            },
+            Some('[') => Ok(FieldType::Array(Box::new(
+                    FieldType::from_chars(descriptor, chars)?))),
+            Some(c) => Err(format!("Unexpected character '{}' in \
+                                    descriptor: {}", c, descriptor)),
+            None => Err(format!("Truncated descriptor: {}", descriptor)),
+        }
+    }
+
+    /*
+     * Longs and doubles occupy two local variable slots;
+     * every other type occupies one.
+     */
+    pub fn slot_size(&self) -> usize {
+        match *self {
+            FieldType::Long | FieldType::Double => 2,
+            _ => 1,
+        }
+    }
+
+    pub fn is_reference(&self) -> bool {
+        matches!(*self, FieldType::Object(_) | FieldType::Array(_))
+    }
+
+    // The type as written in Java source, e.g. "java.lang.String[]".
+    pub fn java_name(&self) -> String {
+        match *self {
+            FieldType::Byte => "byte".to_string(),
+            FieldType::Char => "char".to_string(),
+            FieldType::Double => "double".to_string(),
+            FieldType::Float => "float".to_string(),
+            FieldType::Int => "int".to_string(),
+            FieldType::Long => "long".to_string(),
+            FieldType::Short => "short".to_string(),
+            FieldType::Boolean => "boolean".to_string(),
+            FieldType::Object(ref name) => name.replace('/', "."),
+            FieldType::Array(ref component) =>
+                format!("{}[]", component.java_name()),
+        }
+    }
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FieldType::Byte => write!(f, "B"),
+            FieldType::Char => write!(f, "C"),
+            FieldType::Double => write!(f, "D"),
+            FieldType::Float => write!(f, "F"),
+            FieldType::Int => write!(f, "I"),
+            FieldType::Long => write!(f, "J"),
+            FieldType::Short => write!(f, "S"),
+            FieldType::Boolean => write!(f, "Z"),
+            FieldType::Object(ref name) => write!(f, "L{};", name),
+            FieldType::Array(ref component) => write!(f, "[{}", component),
+        }
+    }
+}
+
+impl MethodDescriptor {
+    pub fn parse(descriptor: &str) -> Result<MethodDescriptor, String> {
+        let mut chars = descriptor.chars().peekable();
+        if chars.next() != Some('(') {
+            return Err(format!("Method descriptor must begin with '(': {}",
+                               descriptor));
+        }
+
+        let mut params = Vec::new();
+        loop {
+            match chars.peek() {
+                Some(&')') => {
+                    chars.next();
+                    break;
+                },
+                Some(_) => params.push(
+                    FieldType::from_chars(descriptor, &mut chars)?),
+                None => return Err(format!("Unterminated parameter list \
+                                            in descriptor: {}", descriptor)),
+            }
+        }
+
+        let ret = match chars.peek() {
+            Some(&'V') => {
+                chars.next();
+                None
+            },
+            _ => Some(FieldType::from_chars(descriptor, &mut chars)?),
+        };
+
+        match chars.next() {
+            None => Ok(MethodDescriptor { params, ret }),
+            Some(_) => Err(format!("Trailing characters in method \
+                                    descriptor: {}", descriptor)),
+        }
+    }
+
+    /*
+     * The number of local variable slots occupied by
+     * the parameters, excluding any receiver.
+     */
+    pub fn param_slots(&self) -> usize {
+        self.params.iter().map(|p| p.slot_size()).sum()
+    }
+}
@@ -1,40 +1,43 @@
+use std::fmt;
+
+use reader::*;
+
+/*
+ * A handler for exceptions thrown between start_pc (inclusive)
+ * and end_pc (exclusive); a catch_type of zero catches every
+ * exception, as is done for finally clauses.
+ */
 pub struct ExceptionTableEntry {
-    start_pc: u16,
-    end_pc: u16,
-    handle_pc: u16,
-    catch_type: u16,
+    pub start_pc: u16,
+    pub end_pc: u16,
+    pub handle_pc: u16,
+    pub catch_type: u16,
 }
 
 impl ExceptionTableEntry {
-    pub fn from_bytecodes(bytecodes: &Vec<u8>, byte_idx: &mut usize)
+    pub fn from_bytecodes(bytecodes: &[u8], byte_idx: &mut usize)
                             -> ExceptionTableEntry {
-
-        let start_pc = (bytecodes[*byte_idx]
-                        + bytecodes[*byte_idx + 1]) as u16;
-        *byte_idx = *byte_idx + 2;
-
-        let end_pc = (bytecodes[*byte_idx]
-                      + bytecodes[*byte_idx + 1]) as u16;
-        *byte_idx = *byte_idx + 2;
-
-        let handle_pc = (bytecodes[*byte_idx]
-                         + bytecodes[*byte_idx + 1]) as u16;
-        *byte_idx = *byte_idx + 2;
-
-        let catch_type = (bytecodes[*byte_idx]
-                          + bytecodes[*byte_idx + 1]) as u16;
-        *byte_idx + *byte_idx + 2;
+        let start_pc = read_u16(bytecodes, byte_idx);
+        let end_pc = read_u16(bytecodes, byte_idx);
+        let handle_pc = read_u16(bytecodes, byte_idx);
+        let catch_type = read_u16(bytecodes, byte_idx);
 
         ExceptionTableEntry {
-            start_pc: start_pc,
-            end_pc: end_pc,
-            handle_pc: handle_pc,
-            catch_type: catch_type,
+            start_pc,
+            end_pc,
+            handle_pc,
+            catch_type,
         }
     }
 
-    pub fn to_string(&self) -> String {
-        format!("ExceptionTableEntry:\n\
+    pub fn covers(&self, pc: usize) -> bool {
+        self.start_pc as usize <= pc && pc < self.end_pc as usize
+    }
+}
+
+impl fmt::Display for ExceptionTableEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ExceptionTableEntry:\n\
                 \t\t\t- start_pc={}\n\
                 \t\t\t- end_pc={}\n\
                 \t\t\t- handle_pc={}\n\
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use attributes::*;
+use constants::*;
+use methods::*;
+use reader::*;
+
+pub struct Field<'a> {
+    pub access_flags: u16,
+    pub name_idx: u16,
+    pub name: &'a str,
+    pub descriptor_idx: u16,
+    pub descriptor: &'a str,
+    pub attrs_count: u16,
+    pub attributes: HashMap<&'a str, Attribute<'a>>,
+}
+
+impl<'a> Field<'a> {
+    pub fn from_bytecodes(bytecodes: &'a [u8], byte_idx: &mut usize,
+                          constant_pool: &HashMap<u16,
+                          ConstantPoolEntry<'a>>) -> Field<'a> {
+
+        let access_flags = read_u16(bytecodes, byte_idx);
+        let name_idx = read_u16(bytecodes, byte_idx);
+        let descriptor_idx = read_u16(bytecodes, byte_idx);
+        let attrs_count = read_u16(bytecodes, byte_idx);
+
+        let name = match constant_pool.get(&name_idx) {
+            Some(ConstantPoolEntry::Utf8(e)) => e.utf8_str,
+            Some(_) | None => panic!("[ERROR] Can't find name at index {} \
+                            in constant pool.", name_idx),
+        };
+
+        let descriptor = match constant_pool.get(&descriptor_idx) {
+            Some(ConstantPoolEntry::Utf8(e)) => e.utf8_str,
+            Some(_) | None => panic!("[ERROR] Can't find descriptor at \
+                            index {} in constant pool.", descriptor_idx),
+        };
+
+        let mut attributes = HashMap::new();
+        for _ in 0 .. attrs_count {
+            let attr = Attribute::from_bytecodes(
+                bytecodes, byte_idx, constant_pool);
+            attributes.insert(attr.name(), attr);
+        };
+
+        Field {
+            access_flags,
+            name_idx,
+            name,
+            descriptor_idx,
+            descriptor,
+            attrs_count,
+            attributes,
+        }
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.access_flags & ACC_STATIC != 0
+    }
+
+    /*
+     * Returns the constant pool index of the initial value of
+     * a static field, if it has one.
+     */
+    pub fn constant_value_idx(&self) -> Option<u16> {
+        match self.attributes.get("ConstantValue") {
+            Some(Attribute::ConstantValue(attr)) =>
+                Some(attr.constant_value_idx),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> fmt::Display for Field<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut string_rep = format!("Field:\n\
+                \t- access_flags=0x{:x}\n\
+                \t- name_idx={}\n\
+                \t- descriptor_idx={}\n\
+                \t- attrs_count={}\n",
+                self.access_flags, self.name_idx, self.descriptor_idx,
+                self.attrs_count);
+
+        for attr in self.attributes.values() {
+            string_rep += &format!("\tAttribute:{}", attr);
+        }
+
+        write!(f, "{}", string_rep)
+    }
+}
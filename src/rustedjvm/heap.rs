@@ -0,0 +1,1021 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::mem;
+use std::time::Duration;
+
+use descriptors::*;
+use interpreter::Operand;
+use loader::*;
+
+/*
+ * A reference to an object on the heap. Handles remain valid
+ * for as long as the object they refer to is alive, regardless
+ * of which frame created it; once the object is collected, its
+ * handle may be reused for another.
+ */
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Handle(usize);
+
+pub struct ObjectHeader {
+    pub class: &'static RuntimeClass,
+    pub identity_hash: i32,
+    // Records the owner and recursion count of the object's monitor.
+    pub lock_word: u64,
+}
+
+/*
+ * The lock word of an object whose monitor no thread owns is 0;
+ * otherwise, its upper half holds the id of the thread that owns it,
+ * and its lower half the number of times that thread has entered it.
+ */
+impl ObjectHeader {
+    pub fn monitor_owner(&self) -> Option<u32> {
+        match (self.lock_word >> 32) as u32 {
+            0 => None,
+            thread => Some(thread),
+        }
+    }
+
+    pub fn monitor_count(&self) -> u32 {
+        self.lock_word as u32
+    }
+
+    /*
+     * Enters the monitor on behalf of the given thread, failing if
+     * another thread owns it.
+     */
+    pub fn try_enter_monitor(&mut self, thread: u32) -> bool {
+        match self.monitor_owner() {
+            Some(owner) if owner != thread => false,
+            _ => {
+                let count = self.monitor_count() + 1;
+                self.lock_word = (thread as u64) << 32 | count as u64;
+                true
+            },
+        }
+    }
+
+    // Exits the monitor, failing if the given thread doesn't own it.
+    pub fn exit_monitor(&mut self, thread: u32) -> bool {
+        if self.monitor_owner() != Some(thread) {
+            return false;
+        }
+        self.lock_word = match self.monitor_count() {
+            1 => 0,
+            count => (thread as u64) << 32 | (count - 1) as u64,
+        };
+        true
+    }
+
+    /*
+     * Releases the monitor however many times the given thread has
+     * entered it, as waiting does, returning that count for the
+     * thread to restore once it reacquires the monitor.
+     */
+    pub fn release_monitor(&mut self, thread: u32) -> Option<u32> {
+        if self.monitor_owner() != Some(thread) {
+            return None;
+        }
+        let count = self.monitor_count();
+        self.lock_word = 0;
+        Some(count)
+    }
+
+    pub fn try_reacquire_monitor(&mut self, thread: u32, count: u32)
+            -> bool {
+        if self.monitor_owner().is_some() {
+            return false;
+        }
+        self.lock_word = (thread as u64) << 32 | count as u64;
+        true
+    }
+}
+
+pub struct Object {
+    pub header: ObjectHeader,
+    pub body: ObjectBody,
+}
+
+pub enum ObjectBody {
+    // Slots laid out as described by the instance fields of the class.
+    Fields(Vec<Operand>),
+    // The elements of an array, each of its component type.
+    Array(Vec<Operand>),
+    // The elements of a byte or boolean array, packed one per byte.
+    Bytes(Vec<i8>),
+}
+
+impl Object {
+    pub fn fields(&self) -> &[Operand] {
+        match self.body {
+            ObjectBody::Fields(ref fields) => fields,
+            _ => panic!("[ERROR] Arrays have no fields."),
+        }
+    }
+
+    pub fn fields_mut(&mut self) -> &mut [Operand] {
+        match self.body {
+            ObjectBody::Fields(ref mut fields) => fields,
+            _ => panic!("[ERROR] Arrays have no fields."),
+        }
+    }
+
+    pub fn elements(&self) -> &[Operand] {
+        match self.body {
+            ObjectBody::Array(ref elements) => elements,
+            _ => panic!("[ERROR] Expected an array of operands, \
+                         but found an instance of {}.",
+                        self.header.class.name),
+        }
+    }
+
+    pub fn elements_mut(&mut self) -> &mut [Operand] {
+        match self.body {
+            ObjectBody::Array(ref mut elements) => elements,
+            _ => panic!("[ERROR] Expected an array of operands, \
+                         but found an instance of {}.",
+                        self.header.class.name),
+        }
+    }
+
+    pub fn bytes(&self) -> &[i8] {
+        match self.body {
+            ObjectBody::Bytes(ref bytes) => bytes,
+            _ => panic!("[ERROR] Expected a byte or boolean array, \
+                         but found an instance of {}.",
+                        self.header.class.name),
+        }
+    }
+
+    pub fn bytes_mut(&mut self) -> &mut [i8] {
+        match self.body {
+            ObjectBody::Bytes(ref mut bytes) => bytes,
+            _ => panic!("[ERROR] Expected a byte or boolean array, \
+                         but found an instance of {}.",
+                        self.header.class.name),
+        }
+    }
+
+    pub fn length(&self) -> usize {
+        match self.body {
+            ObjectBody::Array(ref elements) => elements.len(),
+            ObjectBody::Bytes(ref bytes) => bytes.len(),
+            ObjectBody::Fields(_) => panic!("[ERROR] Expected an array, \
+                                             but found an instance of {}.",
+                                            self.header.class.name),
+        }
+    }
+
+    /*
+     * Reads and writes array elements regardless of how they are
+     * stored; packed bytes are widened to (and truncated from) ints.
+     */
+    pub fn element(&self, idx: usize) -> Operand {
+        match self.body {
+            ObjectBody::Bytes(ref bytes) => Operand::Int(bytes[idx] as i32),
+            _ => self.elements()[idx],
+        }
+    }
+
+    // The bytes the object takes, as the heap's limits count them.
+    pub fn size(&self) -> usize {
+        match self.body {
+            ObjectBody::Fields(_) => Heap::object_size(self.header.class),
+            ObjectBody::Array(ref elements) =>
+                Heap::array_size(self.header.class, elements.len()),
+            ObjectBody::Bytes(ref bytes) =>
+                Heap::array_size(self.header.class, bytes.len()),
+        }
+    }
+
+    /*
+     * Calls the given function with each object the object refers
+     * to, finding its reference fields from the layout of its class.
+     */
+    fn for_each_reference<F: FnMut(Handle)>(&self, f: F) {
+        self.for_each_reference_except(None, f)
+    }
+
+    // As above, but passing over the field at the given index, if any.
+    fn for_each_reference_except<F: FnMut(Handle)>(&self,
+                                                   skipped: Option<usize>,
+                                                   mut f: F) {
+        let class = self.header.class;
+        match self.body {
+            ObjectBody::Fields(ref fields) => {
+                for (idx, (slot, field)) in class.instance_fields.iter()
+                        .zip(fields.iter()).enumerate() {
+                    if Some(idx) == skipped {
+                        continue;
+                    }
+                    if let (true, &Operand::Ref(object)) =
+                            (slot.field_type.is_reference(), field) {
+                        f(object);
+                    }
+                }
+            },
+            ObjectBody::Array(ref elements)
+                    if class.component_type.as_ref()
+                        .is_some_and(|t| t.is_reference()) => {
+                for element in elements.iter() {
+                    if let Operand::Ref(object) = *element {
+                        f(object);
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+
+    pub fn set_element(&mut self, idx: usize, value: Operand) {
+        match (&mut self.body, value) {
+            (&mut ObjectBody::Bytes(ref mut bytes), Operand::Int(value)) =>
+                bytes[idx] = value as i8,
+            (&mut ObjectBody::Bytes(_), _) =>
+                panic!("[ERROR] Only ints may be stored to {}.",
+                       self.header.class.name),
+            _ => self.elements_mut()[idx] = value,
+        }
+    }
+}
+
+/*
+ * Why a garbage collection was started.
+ */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GcCause {
+    // An allocation would have grown the heap past its capacity.
+    AllocationFailure,
+    // Guest code called System.gc(), or the host asked for one.
+    Explicit,
+}
+
+impl fmt::Display for GcCause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GcCause::AllocationFailure => write!(f, "Allocation Failure"),
+            GcCause::Explicit => write!(f, "System.gc()"),
+        }
+    }
+}
+
+/*
+ * The totals of the collections the heap has gone through.
+ */
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct GcStats {
+    // All collections, including the minor ones counted separately.
+    pub collections: u64,
+    pub young_collections: u64,
+    pub objects_freed: u64,
+    pub bytes_freed: u64,
+    // The time spent collecting, during which guest code was paused.
+    pub total_pause: Duration,
+}
+
+/*
+ * The outcome of a single collection.
+ */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Collection {
+    pub used_before: usize,
+    pub used_after: usize,
+    // The heap's capacity once it has been resized after collecting.
+    pub capacity: usize,
+    pub objects_freed: usize,
+}
+
+/*
+ * What the VM has left to do once a collection is over, on behalf of
+ * the reference handler and finalizer threads of other VMs: enqueue
+ * a reference the collector has cleared, or finalize an object it
+ * has found unreachable. The objects involved are kept alive until
+ * then.
+ */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PendingAction {
+    Enqueue(Handle),
+    Finalize(Handle),
+}
+
+impl PendingAction {
+    pub fn object(self) -> Handle {
+        match self {
+            PendingAction::Enqueue(object) | PendingAction::Finalize(object) =>
+                object,
+        }
+    }
+}
+
+/*
+ * How the heap is collected.
+ */
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Collector {
+    // Every collection marks and sweeps the whole heap.
+    #[default]
+    MarkSweep,
+    /*
+     * Objects are allocated in a young generation, whose survivors
+     * minor collections copy out of it, promoting those that have
+     * survived a few to the old generation; the whole heap is marked
+     * and swept only once the old generation fills up.
+     */
+    Generational,
+}
+
+// Where the object a handle refers to is kept.
+#[derive(Copy, Clone, PartialEq)]
+enum Location {
+    Free,
+    Young(usize),
+    Old(usize),
+}
+
+struct Cell {
+    handle: Handle,
+    // The number of minor collections the object has survived.
+    age: u8,
+    object: Object,
+}
+
+// Young objects that survive this many minor collections are promoted.
+const TENURING_THRESHOLD: u8 = 2;
+
+// The number of slots of the old generation each card stands for.
+const CARD_SIZE: usize = 16;
+
+/*
+ * The objects allocated by guest code, which are freed by a tracing
+ * collector once they're no longer reachable from the roots the
+ * interpreter supplies. Handles are indices into a table of where
+ * their objects are kept, so that objects can be moved between
+ * generations; within the old generation, objects aren't moved, and
+ * the slots of freed objects are reused for later allocations.
+ */
+pub struct Heap {
+    collector: Collector,
+    locations: Vec<Location>,
+    free_handles: Vec<usize>,
+    young: Vec<Cell>,
+    old: Vec<Option<Cell>>,
+    free_old_slots: Vec<usize>,
+    /*
+     * The card table, which marks the cards of the old generation
+     * holding objects that might refer to young ones: the write
+     * barrier marks the card of each old object a reference is stored
+     * to. The classes whose static fields references have been stored
+     * to are remembered the same way.
+     */
+    dirty_cards: Vec<bool>,
+    dirty_statics: HashSet<&'static str>,
+    // The objects with finalizers that haven't been found unreachable.
+    finalizable: Vec<Handle>,
+    pending_actions: VecDeque<PendingAction>,
+    hash_seed: u32,
+    // The bytes taken by the live objects (and garbage not yet found).
+    used: usize,
+    young_used: usize,
+    /*
+     * The bytes the old generation (or with mark-and-sweep, the whole
+     * heap) may take before a full collection is due; it starts at
+     * its initial capacity and grows as live objects do.
+     */
+    capacity: usize,
+    initial_capacity: usize,
+    // The bytes the young generation may take before it's collected.
+    young_capacity: usize,
+    // The most bytes the heap may take, if limited.
+    max_size: Option<usize>,
+    stats: GcStats,
+}
+
+// The size a heap starts with, unless -Xms says otherwise.
+pub const DEFAULT_INITIAL_HEAP_SIZE: usize = 8 << 20;
+
+impl Default for Heap {
+    fn default() -> Self {
+        Heap::new()
+    }
+}
+
+impl Heap {
+    pub fn new() -> Heap {
+        Heap::with_max_size(None)
+    }
+
+    pub fn with_max_size(max_size: Option<usize>) -> Heap {
+        Heap::with_sizes(DEFAULT_INITIAL_HEAP_SIZE, max_size,
+                         Collector::MarkSweep)
+    }
+
+    /*
+     * With the generational collector, a third of the initial size
+     * goes to the young generation, as with HotSpot's default
+     * NewRatio of 2.
+     */
+    pub fn with_sizes(initial_size: usize, max_size: Option<usize>,
+                      collector: Collector) -> Heap {
+        let initial_size = match max_size {
+            Some(max_size) => initial_size.min(max_size),
+            None => initial_size,
+        };
+        let young_capacity = match collector {
+            Collector::MarkSweep => 0,
+            Collector::Generational => initial_size / 3,
+        };
+        Heap {
+            collector,
+            locations: Vec::new(),
+            free_handles: Vec::new(),
+            young: Vec::new(),
+            old: Vec::new(),
+            free_old_slots: Vec::new(),
+            dirty_cards: Vec::new(),
+            dirty_statics: HashSet::new(),
+            finalizable: Vec::new(),
+            pending_actions: VecDeque::new(),
+            hash_seed: 0x2545_f491,
+            used: 0,
+            young_used: 0,
+            capacity: initial_size - young_capacity,
+            initial_capacity: initial_size - young_capacity,
+            young_capacity,
+            max_size,
+            stats: GcStats::default(),
+        }
+    }
+
+    pub fn collector(&self) -> Collector {
+        self.collector
+    }
+
+    pub fn used(&self) -> usize {
+        self.used
+    }
+
+    // The bytes the heap may take before it's next collected.
+    pub fn capacity(&self) -> usize {
+        self.capacity + self.young_capacity
+    }
+
+    pub fn max_size(&self) -> Option<usize> {
+        self.max_size
+    }
+
+    pub fn stats(&self) -> &GcStats {
+        &self.stats
+    }
+
+    // The number of objects on the heap, including garbage not yet found.
+    pub fn object_count(&self) -> usize {
+        self.locations.len() - self.free_handles.len()
+    }
+
+    pub fn is_young(&self, handle: Handle) -> bool {
+        matches!(self.locations[handle.0], Location::Young(_))
+    }
+
+    /*
+     * Whether allocating objects of the given total size calls for
+     * a collection of the young generation, or of the whole heap,
+     * before they're allocated. Objects too large for the young
+     * generation are allocated in the old one.
+     */
+    pub fn is_young_collection_due(&self, bytes: usize) -> bool {
+        self.collector == Collector::Generational
+            && self.fits_in_young(bytes)
+            && self.young_used.saturating_add(bytes) > self.young_capacity
+    }
+
+    pub fn is_full_collection_due(&self, bytes: usize) -> bool {
+        let old_bytes = if self.fits_in_young(bytes) { 0 } else { bytes };
+        (self.used - self.young_used).saturating_add(old_bytes)
+            > self.capacity
+    }
+
+    fn fits_in_young(&self, bytes: usize) -> bool {
+        self.collector == Collector::Generational
+            && bytes <= self.young_capacity / 2
+    }
+
+    /*
+     * Whether allocating objects of the given total size would keep
+     * the heap within its maximum size. It's up to the interpreter to
+     * ask before allocating on behalf of guest code, so that it can
+     * throw OutOfMemoryError instead.
+     */
+    pub fn has_room_for(&self, bytes: usize) -> bool {
+        match self.max_size {
+            Some(max_size) => self.used.saturating_add(bytes) <= max_size,
+            None => true,
+        }
+    }
+
+    /*
+     * The sizes of objects are those of their representation in the
+     * VM: a header followed by their slots, or for byte and boolean
+     * arrays, by their packed elements.
+     */
+    pub fn object_size(class: &RuntimeClass) -> usize {
+        mem::size_of::<Object>()
+            + class.instance_fields.len() * mem::size_of::<Operand>()
+    }
+
+    pub fn array_size(class: &RuntimeClass, length: usize) -> usize {
+        let element_size = match class.component_type {
+            Some(FieldType::Byte) | Some(FieldType::Boolean) => 1,
+            _ => mem::size_of::<Operand>(),
+        };
+        mem::size_of::<Object>().saturating_add(
+            length.saturating_mul(element_size))
+    }
+
+    // The total size of the arrays allocate_multi_array would create.
+    pub fn multi_array_size(class: &RuntimeClass, counts: &[i32]) -> usize {
+        let length = counts[0] as usize;
+        let mut size = Heap::array_size(class, length);
+        if let Some(component_class) = class.component_class
+                .filter(|_| counts.len() > 1) {
+            let inner = Heap::multi_array_size(component_class, &counts[1..]);
+            size = size.saturating_add(length.saturating_mul(inner));
+        }
+        size
+    }
+
+    /*
+     * Allocates an instance of the given class, with each of its
+     * fields set to the default value for the field's type.
+     */
+    pub fn allocate(&mut self, class: &'static RuntimeClass) -> Handle {
+        let fields = class.instance_fields.iter()
+            .map(|f| default_value(&f.field_type)).collect();
+        let object = self.insert(class, ObjectBody::Fields(fields));
+        if class.has_finalizer {
+            self.finalizable.push(object);
+        }
+        object
+    }
+
+    /*
+     * Allocates an array of the given array class, with each of
+     * its elements set to the default value for its component type.
+     */
+    pub fn allocate_array(&mut self, class: &'static RuntimeClass,
+                          length: usize) -> Handle {
+        let component_type = match class.component_type {
+            Some(ref component_type) => component_type,
+            None => panic!("[ERROR] {} is not an array class.", class.name),
+        };
+        let body = match *component_type {
+            FieldType::Byte | FieldType::Boolean =>
+                ObjectBody::Bytes(vec![0; length]),
+            _ => ObjectBody::Array(vec![default_value(component_type);
+                                        length]),
+        };
+        self.insert(class, body)
+    }
+
+    /*
+     * Allocates an array of the given array class for each of the
+     * given dimensions; when fewer counts are given than the class has
+     * dimensions, the innermost arrays are left null.
+     */
+    pub fn allocate_multi_array(&mut self, class: &'static RuntimeClass,
+                                counts: &[i32]) -> Handle {
+        let length = counts[0] as usize;
+        let array = self.allocate_array(class, length);
+        if let Some(component_class) = class.component_class
+                .filter(|_| counts.len() > 1) {
+            for idx in 0 .. length {
+                let inner = self.allocate_multi_array(component_class,
+                                                      &counts[1..]);
+                self.get_mut(array).elements_mut()[idx] = Operand::Ref(inner);
+            }
+        }
+        array
+    }
+
+    fn insert(&mut self, class: &'static RuntimeClass, body: ObjectBody)
+            -> Handle {
+        let header = ObjectHeader {
+            class,
+            identity_hash: self.next_identity_hash(),
+            lock_word: 0,
+        };
+        let object = Object { header, body };
+        let size = object.size();
+        self.used += size;
+        let handle = match self.free_handles.pop() {
+            Some(idx) => Handle(idx),
+            None => {
+                self.locations.push(Location::Free);
+                Handle(self.locations.len() - 1)
+            },
+        };
+        let cell = Cell { handle, age: 0, object };
+        self.locations[handle.0] = if self.fits_in_young(size) {
+            self.young_used += size;
+            self.young.push(cell);
+            Location::Young(self.young.len() - 1)
+        } else {
+            /*
+             * The VM initializes some objects after allocating them,
+             * which it does without the write barrier, so an object
+             * allocated in the old generation starts out dirty.
+             */
+            let idx = self.insert_old(cell);
+            self.dirty_cards[idx / CARD_SIZE] = true;
+            Location::Old(idx)
+        };
+        handle
+    }
+
+    fn insert_old(&mut self, cell: Cell) -> usize {
+        let idx = match self.free_old_slots.pop() {
+            Some(idx) => {
+                self.old[idx] = Some(cell);
+                idx
+            },
+            None => {
+                self.old.push(Some(cell));
+                self.old.len() - 1
+            },
+        };
+        let card_count = self.old.len().div_ceil(CARD_SIZE);
+        self.dirty_cards.resize(card_count, false);
+        idx
+    }
+
+    // Frees an object that has been taken from its generation.
+    fn free(&mut self, cell: Cell) {
+        self.used -= cell.object.size();
+        self.locations[cell.handle.0] = Location::Free;
+        self.free_handles.push(cell.handle.0);
+    }
+
+    pub fn get(&self, handle: Handle) -> &Object {
+        let cell = match self.locations.get(handle.0) {
+            Some(&Location::Young(idx)) => Some(&self.young[idx]),
+            Some(&Location::Old(idx)) => self.old[idx].as_ref(),
+            _ => None,
+        };
+        match cell {
+            Some(cell) => &cell.object,
+            None => panic!("[ERROR] {:?} refers to an object that has been \
+                            collected.", handle),
+        }
+    }
+
+    pub fn get_mut(&mut self, handle: Handle) -> &mut Object {
+        let cell = match self.locations.get(handle.0) {
+            Some(&Location::Young(idx)) => Some(&mut self.young[idx]),
+            Some(&Location::Old(idx)) => self.old[idx].as_mut(),
+            _ => None,
+        };
+        match cell {
+            Some(cell) => &mut cell.object,
+            None => panic!("[ERROR] {:?} refers to an object that has been \
+                            collected.", handle),
+        }
+    }
+
+    fn references(&self, handle: Handle) -> Vec<Handle> {
+        let mut references = Vec::new();
+        self.get(handle).for_each_reference(|object| references.push(object));
+        references
+    }
+
+    /*
+     * The write barrier, which the interpreter calls after storing a
+     * reference to a field or element of an object (or to a static
+     * field of a class), so that minor collections can find the old
+     * objects that might refer to young ones.
+     */
+    pub fn write_barrier(&mut self, object: Handle) {
+        if let Location::Old(idx) = self.locations[object.0] {
+            self.dirty_cards[idx / CARD_SIZE] = true;
+        }
+    }
+
+    pub fn static_write_barrier(&mut self, class_name: &'static str) {
+        if self.collector == Collector::Generational {
+            self.dirty_statics.insert(class_name);
+        }
+    }
+
+    /*
+     * Returns the classes whose static fields might refer to young
+     * objects, which are roots of the next minor collection; those
+     * that still do afterward must be marked dirty again.
+     */
+    pub fn take_dirty_statics(&mut self) -> Vec<&'static str> {
+        self.dirty_statics.drain().collect()
+    }
+
+    pub fn has_pending_actions(&self) -> bool {
+        !self.pending_actions.is_empty()
+    }
+
+    pub fn next_pending_action(&mut self) -> Option<PendingAction> {
+        self.pending_actions.pop_front()
+    }
+
+    /*
+     * Frees every object that can't be reached from the given roots,
+     * promoting the young objects that survive, then resizes the heap
+     * so that its live objects, along with the bytes about to be
+     * allocated, fill at most half of it, within its initial and
+     * maximum sizes. The caller records how long the collection took
+     * with record_pause().
+     *
+     * References whose referents are no longer strongly reachable
+     * are cleared, as are soft references if asked to be, and left
+     * for the VM to enqueue; weak ones are cleared before unreachable
+     * objects with finalizers are resurrected to be finalized, and
+     * phantom ones only after.
+     */
+    pub fn collect(&mut self, roots: &[Handle], requested: usize,
+                   clear_soft_references: bool) -> Collection {
+        let used_before = self.used;
+
+        // Mark the objects reachable from the roots.
+        let mut marked = vec![false; self.locations.len()];
+        let mut discovered = Vec::new();
+        let mut roots = roots.to_vec();
+        roots.extend(self.pending_actions.iter()
+                     .map(|action| action.object()));
+        self.mark(roots, &mut marked, &mut discovered, clear_soft_references);
+
+        let mut phantom_references = Vec::new();
+        for reference in discovered.drain(..) {
+            match self.get(reference).header.class.reference_kind {
+                Some(ReferenceKind::Phantom) =>
+                    phantom_references.push(reference),
+                _ => self.clear_unreachable_referent(reference, &marked),
+            }
+        }
+        let (reachable, unreachable) = self.finalizable.iter()
+            .partition(|&&object| marked[object.0]);
+        self.finalizable = reachable;
+        self.mark(unreachable.clone(), &mut marked, &mut discovered,
+                  clear_soft_references);
+        for reference in phantom_references.into_iter().chain(discovered) {
+            self.clear_unreachable_referent(reference, &marked);
+        }
+        self.pending_actions.extend(
+            unreachable.into_iter().map(PendingAction::Finalize));
+
+        // Sweep away the rest.
+        let mut objects_freed = 0;
+        for idx in 0 .. self.old.len() {
+            let is_garbage = match self.old[idx] {
+                Some(ref cell) => !marked[cell.handle.0],
+                None => false,
+            };
+            if let Some(cell) = self.old[idx].take_if(|_| is_garbage) {
+                self.free(cell);
+                self.free_old_slots.push(idx);
+                objects_freed += 1;
+            }
+        }
+        for cell in mem::take(&mut self.young) {
+            if marked[cell.handle.0] {
+                let handle = cell.handle;
+                let idx = self.insert_old(cell);
+                self.locations[handle.0] = Location::Old(idx);
+            } else {
+                self.free(cell);
+                objects_freed += 1;
+            }
+        }
+        self.young_used = 0;
+
+        // With the young generation empty, nothing refers to it.
+        for card in self.dirty_cards.iter_mut() {
+            *card = false;
+        }
+        self.dirty_statics.clear();
+
+        let mut capacity = self.initial_capacity.max(
+            self.used.saturating_add(requested).saturating_mul(2));
+        if let Some(max_size) = self.max_size {
+            capacity = capacity.min(max_size - self.young_capacity);
+        }
+        self.capacity = capacity;
+
+        self.stats.collections += 1;
+        self.record_freed(objects_freed, used_before);
+        Collection {
+            used_before,
+            used_after: self.used,
+            capacity: self.capacity(),
+            objects_freed,
+        }
+    }
+
+    /*
+     * Marks the objects reachable from the given ones, except through
+     * the referents of references, which are added to those
+     * discovered instead, unless they're soft references that aren't
+     * to be cleared.
+     */
+    fn mark(&self, mut pending: Vec<Handle>, marked: &mut [bool],
+            discovered: &mut Vec<Handle>, clear_soft_references: bool) {
+        while let Some(handle) = pending.pop() {
+            if marked[handle.0] {
+                continue;
+            }
+            marked[handle.0] = true;
+            let object = self.get(handle);
+            let class = object.header.class;
+            let referent_idx = match class.reference_kind {
+                Some(ReferenceKind::Soft) if !clear_soft_references => None,
+                Some(_) => {
+                    discovered.push(handle);
+                    Some(referent_idx(class))
+                },
+                None => None,
+            };
+            object.for_each_reference_except(referent_idx, |object| {
+                if !marked[object.0] {
+                    pending.push(object);
+                }
+            });
+        }
+    }
+
+    /*
+     * Clears a reference if its referent hasn't been marked, leaving
+     * it for the VM to enqueue.
+     */
+    fn clear_unreachable_referent(&mut self, reference: Handle,
+                                  marked: &[bool]) {
+        let idx = referent_idx(self.get(reference).header.class);
+        let fields = self.get_mut(reference).fields_mut();
+        if let Operand::Ref(referent) = fields[idx] {
+            if !marked[referent.0] {
+                fields[idx] = Operand::Null;
+                let action = PendingAction::Enqueue(reference);
+                self.pending_actions.push_back(action);
+            }
+        }
+    }
+
+    /*
+     * Frees the young objects that can't be reached from the given
+     * roots or from the old objects in dirty cards, copying those that
+     * can out of the space they were allocated in: to the old
+     * generation if they're old enough, or if there's no room left
+     * for survivors in the young one. Referents are treated as any
+     * other field, as are objects with finalizers, and are left for
+     * a full collection to clear or finalize.
+     */
+    pub fn collect_young(&mut self, roots: &[Handle]) -> Collection {
+        let used_before = self.used;
+        let mut from: Vec<Option<Cell>> = mem::take(&mut self.young)
+            .into_iter().map(Some).collect();
+        self.young_used = 0;
+
+        let remembered: Vec<usize> = (0 .. self.old.len())
+            .filter(|&idx| self.dirty_cards[idx / CARD_SIZE]
+                    && self.old[idx].is_some())
+            .collect();
+        let mut promoted = Vec::new();
+        let mut pending = Vec::new();
+        let mut roots = roots.to_vec();
+        roots.extend(self.finalizable.iter().cloned());
+        roots.extend(self.pending_actions.iter()
+                     .map(|action| action.object()));
+        for root in roots {
+            self.evacuate(root, &mut from, &mut pending, &mut promoted);
+        }
+        for &idx in remembered.iter() {
+            let handle = match self.old[idx] {
+                Some(ref cell) => cell.handle,
+                None => continue,
+            };
+            for object in self.references(handle) {
+                self.evacuate(object, &mut from, &mut pending, &mut promoted);
+            }
+        }
+        // Copied objects are scanned in turn for the objects they refer to.
+        while let Some(handle) = pending.pop() {
+            for object in self.references(handle) {
+                self.evacuate(object, &mut from, &mut pending, &mut promoted);
+            }
+        }
+
+        // Whatever wasn't copied is garbage.
+        let mut objects_freed = 0;
+        for cell in from.into_iter().flatten() {
+            self.free(cell);
+            objects_freed += 1;
+        }
+
+        // Only cards with objects that still refer to young ones stay dirty.
+        for card in self.dirty_cards.iter_mut() {
+            *card = false;
+        }
+        for idx in remembered.into_iter().chain(promoted) {
+            let handle = match self.old[idx] {
+                Some(ref cell) => cell.handle,
+                None => continue,
+            };
+            if self.references(handle).iter().any(|&o| self.is_young(o)) {
+                self.dirty_cards[idx / CARD_SIZE] = true;
+            }
+        }
+
+        self.stats.collections += 1;
+        self.stats.young_collections += 1;
+        self.record_freed(objects_freed, used_before);
+        Collection {
+            used_before,
+            used_after: self.used,
+            capacity: self.capacity(),
+            objects_freed,
+        }
+    }
+
+    /*
+     * Copies a young object out of the space being collected, unless
+     * it has already been copied (or isn't young).
+     */
+    fn evacuate(&mut self, handle: Handle, from: &mut [Option<Cell>],
+                pending: &mut Vec<Handle>, promoted: &mut Vec<usize>) {
+        let idx = match self.locations[handle.0] {
+            Location::Young(idx) => idx,
+            _ => return,
+        };
+        // Once copied, the handle's location is in the new space.
+        let is_uncopied = match from.get(idx) {
+            Some(Some(cell)) => cell.handle == handle,
+            _ => false,
+        };
+        let mut cell = match from[idx].take() {
+            Some(cell) if is_uncopied => cell,
+            cell => {
+                from[idx] = cell;
+                return;
+            },
+        };
+        cell.age += 1;
+        let size = cell.object.size();
+        self.locations[handle.0] = if cell.age >= TENURING_THRESHOLD
+                || self.young_used + size > self.young_capacity / 2 {
+            let idx = self.insert_old(cell);
+            promoted.push(idx);
+            Location::Old(idx)
+        } else {
+            self.young_used += size;
+            self.young.push(cell);
+            Location::Young(self.young.len() - 1)
+        };
+        pending.push(handle);
+    }
+
+    fn record_freed(&mut self, objects_freed: usize, used_before: usize) {
+        self.stats.objects_freed += objects_freed as u64;
+        self.stats.bytes_freed += (used_before - self.used) as u64;
+    }
+
+    pub fn record_pause(&mut self, pause: Duration) {
+        self.stats.total_pause += pause;
+    }
+
+    /*
+     * Identity hash codes are drawn from a xorshift generator,
+     * and are always positive, as they are in HotSpot.
+     */
+    fn next_identity_hash(&mut self) -> i32 {
+        loop {
+            self.hash_seed ^= self.hash_seed << 13;
+            self.hash_seed ^= self.hash_seed >> 17;
+            self.hash_seed ^= self.hash_seed << 5;
+            let hash = (self.hash_seed & 0x7fff_ffff) as i32;
+            if hash != 0 {
+                return hash;
+            }
+        }
+    }
+}
+
+pub fn default_value(field_type: &FieldType) -> Operand {
+    match *field_type {
+        FieldType::Long => Operand::Long(0),
+        FieldType::Float => Operand::Float(0.0),
+        FieldType::Double => Operand::Double(0.0),
+        FieldType::Object(_) | FieldType::Array(_) => Operand::Null,
+        FieldType::Byte | FieldType::Char | FieldType::Int
+            | FieldType::Short | FieldType::Boolean => Operand::Int(0),
+    }
+}
+
+// The index of the referent field of java/lang/ref/Reference's subclasses.
+fn referent_idx(class: &RuntimeClass) -> usize {
+    match class.instance_field_idx("referent", "Ljava/lang/Object;") {
+        Some(idx) => idx,
+        None => panic!("[ERROR] {} lacks field referent.", class.name),
+    }
+}
@@ -0,0 +1,250 @@
+/*
+ * A decoder for the DEFLATE format described in RFC 1951, which
+ * jar files use to compress their entries. It favors simplicity
+ * over speed, decoding Huffman codes a bit at a time, as the
+ * reference decoder in zlib's contrib/puff does.
+ */
+
+const MAX_BITS: usize = 15;
+
+// The base lengths and extra bits of length codes 257 to 285.
+const LENGTH_BASES: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31,
+    35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA_BITS: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2,
+    3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+
+// The base distances and extra bits of distance codes 0 to 29.
+const DISTANCE_BASES: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193,
+    257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577];
+const DISTANCE_EXTRA_BITS: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6,
+    7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+
+// The order in which the lengths of the code length code are sent.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+/*
+ * Decompresses raw DEFLATE data (without a zlib or gzip wrapper).
+ */
+pub fn inflate(input: &[u8]) -> Result<Vec<u8>, String> {
+    let mut bits = BitReader { input, idx: 0, buffer: 0, count: 0 };
+    let mut output = Vec::new();
+    loop {
+        let is_final = bits.read(1)? == 1;
+        match bits.read(2)? {
+            0 => copy_stored(&mut bits, &mut output)?,
+            1 => {
+                let (lengths, distances) = fixed_codes();
+                inflate_block(&mut bits, &mut output, &lengths, &distances)?;
+            },
+            2 => {
+                let (lengths, distances) = dynamic_codes(&mut bits)?;
+                inflate_block(&mut bits, &mut output, &lengths, &distances)?;
+            },
+            _ => return Err("Invalid block type".to_string()),
+        }
+        if is_final {
+            return Ok(output);
+        }
+    }
+}
+
+/*
+ * Reads bits least significant first, as DEFLATE packs them.
+ */
+struct BitReader<'a> {
+    input: &'a [u8],
+    idx: usize,
+    buffer: u32,
+    count: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn read(&mut self, count: u32) -> Result<u32, String> {
+        while self.count < count {
+            let byte = match self.input.get(self.idx) {
+                Some(&byte) => byte,
+                None => return Err("Unexpected end of data".to_string()),
+            };
+            self.idx += 1;
+            self.buffer |= (byte as u32) << self.count;
+            self.count += 8;
+        }
+        let value = self.buffer & ((1u64 << count) - 1) as u32;
+        self.buffer >>= count;
+        self.count -= count;
+        Ok(value)
+    }
+
+    // Discards the bits left in the current byte.
+    fn align(&mut self) {
+        self.buffer = 0;
+        self.count = 0;
+    }
+}
+
+/*
+ * A canonical Huffman code, held as the number of codes of each
+ * length along with the symbols ordered by their codes.
+ */
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Result<Huffman, String> {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &length in lengths.iter() {
+            counts[length as usize] += 1;
+        }
+
+        // Codes may leave some bit patterns unused, but not overlap.
+        let mut left: i32 = 1;
+        for &count in counts[1 ..].iter() {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err("Oversubscribed Huffman code".to_string());
+            }
+        }
+
+        let mut offsets = [0usize; MAX_BITS + 1];
+        for length in 1 .. MAX_BITS {
+            offsets[length + 1] = offsets[length] + counts[length] as usize;
+        }
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize]] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+        Ok(Huffman { counts, symbols })
+    }
+
+    fn decode(&self, bits: &mut BitReader) -> Result<u16, String> {
+        // The first code of the current length, and its symbol's index.
+        let (mut code, mut first, mut idx) = (0i32, 0i32, 0i32);
+        for &count in self.counts[1 ..].iter() {
+            code |= bits.read(1)? as i32;
+            let count = count as i32;
+            if code - first < count {
+                return Ok(self.symbols[(idx + code - first) as usize]);
+            }
+            idx += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err("Invalid Huffman code".to_string())
+    }
+}
+
+fn copy_stored(bits: &mut BitReader, output: &mut Vec<u8>)
+        -> Result<(), String> {
+    bits.align();
+    let length = bits.read(16)?;
+    if bits.read(16)? != !length & 0xffff {
+        return Err("Stored block length is corrupt".to_string());
+    }
+    let end = bits.idx + length as usize;
+    match bits.input.get(bits.idx .. end) {
+        Some(bytes) => output.extend_from_slice(bytes),
+        None => return Err("Unexpected end of data".to_string()),
+    }
+    bits.idx = end;
+    Ok(())
+}
+
+fn fixed_codes() -> (Huffman, Huffman) {
+    let mut lengths = [8u8; 288];
+    for length in lengths[144 .. 256].iter_mut() {
+        *length = 9;
+    }
+    for length in lengths[256 .. 280].iter_mut() {
+        *length = 7;
+    }
+    match (Huffman::new(&lengths), Huffman::new(&[5; 30])) {
+        (Ok(lengths), Ok(distances)) => (lengths, distances),
+        _ => panic!("[ERROR] The fixed Huffman codes are invalid."),
+    }
+}
+
+fn dynamic_codes(bits: &mut BitReader)
+        -> Result<(Huffman, Huffman), String> {
+    let length_count = bits.read(5)? as usize + 257;
+    let distance_count = bits.read(5)? as usize + 1;
+    let code_length_count = bits.read(4)? as usize + 4;
+
+    let mut code_lengths = [0u8; 19];
+    for &idx in CODE_LENGTH_ORDER[.. code_length_count].iter() {
+        code_lengths[idx] = bits.read(3)? as u8;
+    }
+    let code_length_code = Huffman::new(&code_lengths)?;
+
+    // The lengths of both codes are sent as one run-length coded list.
+    let mut lengths = Vec::with_capacity(length_count + distance_count);
+    while lengths.len() < length_count + distance_count {
+        let (length, repeat) = match code_length_code.decode(bits)? {
+            symbol @ 0 ..= 15 => (symbol as u8, 1),
+            16 => match lengths.last() {
+                Some(&previous) => (previous, 3 + bits.read(2)?),
+                None => return Err("No length to repeat".to_string()),
+            },
+            17 => (0, 3 + bits.read(3)?),
+            _ => (0, 11 + bits.read(7)?),
+        };
+        for _ in 0 .. repeat {
+            lengths.push(length);
+        }
+    }
+    if lengths.len() > length_count + distance_count {
+        return Err("Too many code lengths".to_string());
+    }
+    if lengths[256] == 0 {
+        return Err("No code for the end of the block".to_string());
+    }
+    Ok((Huffman::new(&lengths[.. length_count])?,
+        Huffman::new(&lengths[length_count ..])?))
+}
+
+fn inflate_block(bits: &mut BitReader, output: &mut Vec<u8>,
+                 lengths: &Huffman, distances: &Huffman)
+        -> Result<(), String> {
+    loop {
+        let symbol = lengths.decode(bits)? as usize;
+        if symbol < 256 {
+            output.push(symbol as u8);
+            continue;
+        }
+        if symbol == 256 {
+            return Ok(());
+        }
+
+        // Otherwise, repeat bytes already output.
+        let idx = symbol - 257;
+        if idx >= LENGTH_BASES.len() {
+            return Err("Invalid length code".to_string());
+        }
+        let length = LENGTH_BASES[idx] as usize
+            + bits.read(LENGTH_EXTRA_BITS[idx] as u32)? as usize;
+        let idx = distances.decode(bits)? as usize;
+        if idx >= DISTANCE_BASES.len() {
+            return Err("Invalid distance code".to_string());
+        }
+        let distance = DISTANCE_BASES[idx] as usize
+            + bits.read(DISTANCE_EXTRA_BITS[idx] as u32)? as usize;
+        if distance > output.len() {
+            return Err("Distance reaches before the start".to_string());
+        }
+        let start = output.len() - distance;
+        for offset in 0 .. length {
+            let byte = output[start + offset];
+            output.push(byte);
+        }
+    }
+}
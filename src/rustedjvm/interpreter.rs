@@ -1,9 +1,86 @@
+use std::any::Any;
+use std::cell::UnsafeCell;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fmt;
+use std::io::{self, Write};
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{self, Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
 use attributes::*;
 use classes::*;
 use constants::*;
+use descriptors::*;
+use heap::*;
+use loader::*;
+use methods::*;
+use natives::*;
+use npe;
+use strings;
+use threads::*;
+use value::Value;
+
+/*
+ * An exception thrown by the instruction being executed,
+ * which propagates until a handler for it is found.
+ */
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct JavaThrowable(pub Handle);
+
+/*
+ * A violation of an invariant the interpreter relies upon, such as
+ * an operand of the wrong type at the top of the stack. These point
+ * to a bug in the VM or to a class file that verification would have
+ * rejected, and unlike Java exceptions, guest code can't catch them.
+ */
+#[derive(Debug, PartialEq)]
+pub struct VmError(pub String);
+
+impl VmError {
+    /*
+     * Internal errors are raised as panics by the code that detects
+     * them; this recovers the message from the panic's payload.
+     */
+    fn from_panic(payload: Box<dyn Any + Send>) -> VmError {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast::<&str>() {
+                Ok(message) => message.to_string(),
+                Err(_) => "Unknown internal error".to_string(),
+            },
+        };
+        VmError(message.trim_start_matches("[ERROR] ").to_string())
+    }
+}
+
+impl fmt::Display for VmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Internal VM error: {}", self.0)
+    }
+}
 
-struct Object<'a> {
-    class: &'a Class<'a>,
+/*
+ * The ways in which running a program can fail: the main class
+ * can't be run, guest code exits with a non-zero status or throws
+ * an exception that it never catches, or the VM itself fails.
+ */
+#[derive(Debug, PartialEq)]
+pub enum RunError {
+    // Holds the reason the launcher gives for not running the class.
+    InvalidMainClass(String),
+    Exit(i32),
+    /*
+     * Holds the description of the exception that escaped main, or
+     * when exploring schedules, any thread.
+     */
+    UncaughtException(String),
+    Internal(VmError),
 }
 
 /*
@@ -13,234 +90,4445 @@ struct Object<'a> {
  * stack.
  */
 #[derive(Copy, Clone)]
-enum Operand<'a> {
-    Ref(&'a Object<'a>),
+pub enum Operand {
+    Ref(Handle),
+    Null,
     Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    // Pushed by jsr and consumed by ret.
+    ReturnAddress(usize),
 }
 
-pub fn run(class: Class) {
-    if !class.methods.contains_key("main") {
-        panic!("[ERROR] Class provided to interpreter does not have \
-                a main method.");
-    };
+impl Operand {
+    /*
+     * Longs and doubles are category 2 values, which count
+     * as two words for the purposes of the stack manipulation
+     * instructions; all other values are a single word.
+     */
+    fn word_count(&self) -> usize {
+        match *self {
+            Operand::Long(_) | Operand::Double(_) => 2,
+            _ => 1,
+        }
+    }
+}
 
-    let object = Object { class: &class };
-    run_method(&object, "<init>");
-    run_method(&object, "main");
+/*
+ * The types distinguished by the typed load and store instructions,
+ * in the order in which the instruction set encodes them
+ * (e.g., iload, lload, fload, dload, aload).
+ */
+#[derive(Copy, Clone)]
+enum OperandType {
+    Int,
+    Long,
+    Float,
+    Double,
+    Reference,
 }
 
-fn run_method(obj: &Object, method_name: &str) -> () {
+impl OperandType {
+    fn from_encoding(offset: u8) -> OperandType {
+        match offset {
+            0 => OperandType::Int,
+            1 => OperandType::Long,
+            2 => OperandType::Float,
+            3 => OperandType::Double,
+            4 => OperandType::Reference,
+            _ => panic!("[ERROR] Invalid operand type encoding: {}", offset),
+        }
+    }
 
-    let method = match obj.class.methods.get(method_name) {
-        Some(e) => e,
-        None => panic!("[ERROR] Class lacks method: {}", method_name),
-    };
+    fn matches(&self, operand: &Operand) -> bool {
+        matches!((*self, *operand),
+                 (OperandType::Int, Operand::Int(_))
+                 | (OperandType::Long, Operand::Long(_))
+                 | (OperandType::Float, Operand::Float(_))
+                 | (OperandType::Double, Operand::Double(_))
+                 | (OperandType::Reference, Operand::Ref(_))
+                 | (OperandType::Reference, Operand::Null))
+    }
+}
 
+/*
+ * The activation record of a single method invocation.
+ * Values of type long and double occupy two entries
+ * in the local variable array (the second of which is
+ * left empty), but only a single entry on the operand stack.
+ */
+struct Frame {
+    class: &'static RuntimeClass,
+    // The class file whose constant pool the code refers to.
+    definition: &'static Class<'static>,
+    method: &'static Method<'static>,
+    code: &'static CodeAttribute<'static>,
+    local_var_arr: Vec<Option<Operand>>,
+    operand_stack: Vec<Operand>,
+    pc: usize,
+    // The pc of the instruction being executed.
+    opcode_pc: usize,
+    // Whether an instruction has been executed yet; the initializers
+    // of subclasses wait, unstarted, beneath those of their supertypes.
+    started: bool,
+    // For synchronized methods, the object whose monitor is held.
+    monitor: Option<Handle>,
+}
 
-    let code_attr = match method.attributes.get("Code") {
-        Some(&Attribute::Code(ref s)) => s,
-        _ => panic!("[ERROR] Code attribute not found."),
-    };
+impl Frame {
+    /*
+     * The bytes the frame takes from the stack, as -Xss counts them:
+     * the frame itself along with its local variables and operands.
+     */
+    fn size(&self) -> usize {
+        mem::size_of::<Frame>() + (self.code.max_locals as usize
+            + self.code.max_stack as usize) * mem::size_of::<Operand>()
+    }
 
     /*
-     * Set up the local variable array;
-     * the first entry of the local variable array is always
-     * the "this" reference to the contextual object.
+     * Whether the instruction at the pc, should it block, can be
+     * executed again from the start: those that block only before
+     * they've done anything they can't undo, such as initializing a
+     * class, entering a monitor, or calling a native method, which
+     * in turn blocks only before it has.
      */
-    let mut local_var_arr : Vec<Operand>
-        = Vec::with_capacity(code_attr.max_locals as usize);
-    local_var_arr.push(Operand::Ref(&obj));
+    fn is_restartable(&self) -> bool {
+        matches!(self.code.code_slice[self.pc],
+                 0xb2 | 0xb3 | 0xb6 ..= 0xb9 | 0xbb | 0xc2)
+    }
+
+    fn new(class: &'static RuntimeClass, method: &'static Method<'static>,
+           args: Vec<Operand>) -> Frame {
+        let definition = match class.definition {
+            Some(definition) => definition,
+            None => panic!("[ERROR] Array class {} declares no methods.",
+                           class.name),
+        };
+        let code = match method.code() {
+            Some(code) => code,
+            None => panic!("[ERROR] Code attribute not found for {}.{}{}.",
+                           class.name, method.name, method.descriptor),
+        };
+
+        /*
+         * Set up the local variable array; for instance methods,
+         * the first entry of the local variable array is always
+         * the "this" reference to the contextual object, followed
+         * by the method's arguments.
+         */
+        let descriptor = parse_method_descriptor(method.descriptor);
+        let mut local_var_arr: Vec<Option<Operand>> =
+            vec![None; code.max_locals as usize];
+        let mut args = args.into_iter();
+        let mut local_idx = 0;
+        if !method.is_static() {
+            local_var_arr[0] = args.next();
+            local_idx = 1;
+        }
+        for (param, arg) in descriptor.params.iter().zip(args) {
+            local_var_arr[local_idx] = Some(arg);
+            local_idx += param.slot_size();
+        }
+
+        Frame {
+            class,
+            definition,
+            method,
+            code,
+            local_var_arr,
+            operand_stack: Vec::with_capacity(code.max_stack as usize),
+            pc: 0,
+            opcode_pc: 0,
+            started: false,
+            monitor: None,
+        }
+    }
+
+    fn read_u8(&mut self) -> u8 {
+        let value = self.code.code_slice[self.pc];
+        self.pc += 1;
+        value
+    }
+
+    fn read_u16(&mut self) -> u16 {
+        let indexbyte1 = self.read_u8() as u16;
+        let indexbyte2 = self.read_u8() as u16;
+        (indexbyte1 << 8) | indexbyte2
+    }
+
+    fn read_i32(&mut self) -> i32 {
+        let high = self.read_u16() as u32;
+        let low = self.read_u16() as u32;
+        ((high << 16) | low) as i32
+    }
 
     /*
-     * Set up the operand stack, which is initially empty.
+     * Branch offsets are signed, and relative to the address of
+     * the opcode of the branch instruction.
      */
-    let mut operand_stack: Vec<Operand>
-        = Vec::with_capacity(code_attr.max_stack as usize);
+    fn branch(&mut self, opcode_pc: usize, offset: i32) {
+        self.pc = (opcode_pc as i64 + offset as i64) as usize;
+    }
+
+    // Branches using the 16-bit offset that follows the opcode if
+    // the condition holds, and otherwise falls through.
+    fn branch_if(&mut self, opcode_pc: usize, condition: bool) {
+        let offset = self.read_u16() as i16;
+        if condition {
+            self.branch(opcode_pc, offset as i32);
+        }
+    }
 
     /*
-     * Begin executing method bytecodes.
+     * The operands of tableswitch and lookupswitch begin at
+     * the next address that is a multiple of four from the start
+     * of the method's code.
      */
-    println!("Interpreting...");
-    let mut bytecode_idx: usize = 0;
-    while bytecode_idx < code_attr.code_slice.len() {
-        match code_attr.code_slice[bytecode_idx] {
-            0x06 => {
-                iconst_3(&mut operand_stack);
-                bytecode_idx += 1;
-            },
-            0x2a => {
-                aload_0(&local_var_arr, &mut operand_stack);
-                bytecode_idx += 1;
-            },
-            0x3c => {
-                istore_1(&local_var_arr, &mut operand_stack);
-                bytecode_idx += 1;
+    fn skip_switch_padding(&mut self) {
+        while !self.pc.is_multiple_of(4) {
+            self.pc += 1;
+        }
+    }
+
+    fn tableswitch(&mut self, opcode_pc: usize) {
+        self.skip_switch_padding();
+        let default = self.read_i32();
+        let low = self.read_i32();
+        let high = self.read_i32();
+        let key = self.pop_int();
+        let offset = if key < low || key > high {
+            default
+        } else {
+            self.pc += 4 * (key as i64 - low as i64) as usize;
+            self.read_i32()
+        };
+        self.branch(opcode_pc, offset);
+    }
+
+    fn lookupswitch(&mut self, opcode_pc: usize) {
+        self.skip_switch_padding();
+        let default = self.read_i32();
+        let npairs = self.read_i32();
+        let key = self.pop_int();
+        let mut offset = default;
+        for _ in 0 .. npairs {
+            let match_value = self.read_i32();
+            let match_offset = self.read_i32();
+            if match_value == key {
+                offset = match_offset;
+                break;
             }
-            0xb1 => {
-                return;
-            },
-            0xb2 => {
-                getstatic(&obj, &mut operand_stack,
-                          code_attr.code_slice[bytecode_idx+1] as u16,
-                          code_attr.code_slice[bytecode_idx+2] as u16);
-                bytecode_idx += 3;
-            },
-            0xb7 => {
-                invokespecial(&mut operand_stack,
-                              code_attr.code_slice[bytecode_idx+1] as u16,
-                              code_attr.code_slice[bytecode_idx+2] as u16);
-                bytecode_idx += 3;
-            },
-            unsup_code => panic!("[ERROR] Encountered unsupported \
-                                  bytecode: {:x}", unsup_code),
         }
+        self.branch(opcode_pc, offset);
+    }
+
+    fn ret(&mut self, idx: usize) {
+        match self.load(idx) {
+            Operand::ReturnAddress(address) => self.pc = address,
+            _ => panic!("[ERROR] ret expected a return address in local \
+                         variable {} but didn't find one.", idx),
+        }
+    }
+
+    fn push(&mut self, operand: Operand) {
+        self.operand_stack.push(operand);
+    }
+
+    fn pop(&mut self) -> Operand {
+        match self.operand_stack.pop() {
+            Some(operand) => operand,
+            None => panic!("[ERROR] Operand stack underflow in {}.{}{}.",
+                           self.class.name, self.method.name,
+                           self.method.descriptor),
+        }
+    }
+
+    fn pop_int(&mut self) -> i32 {
+        match self.pop() {
+            Operand::Int(value) => value,
+            _ => panic!("[ERROR] Expected Int at top of stack \
+                         but didn't get one."),
+        }
+    }
+
+    fn pop_long(&mut self) -> i64 {
+        match self.pop() {
+            Operand::Long(value) => value,
+            _ => panic!("[ERROR] Expected Long at top of stack \
+                         but didn't get one."),
+        }
+    }
+
+    fn pop_float(&mut self) -> f32 {
+        match self.pop() {
+            Operand::Float(value) => value,
+            _ => panic!("[ERROR] Expected Float at top of stack \
+                         but didn't get one."),
+        }
+    }
+
+    fn pop_double(&mut self) -> f64 {
+        match self.pop() {
+            Operand::Double(value) => value,
+            _ => panic!("[ERROR] Expected Double at top of stack \
+                         but didn't get one."),
+        }
+    }
+
+    /*
+     * Pops as many values as make up the given number of words,
+     * returning them in the order in which they were pushed.
+     */
+    fn pop_words(&mut self, word_count: usize) -> Vec<Operand> {
+        let mut values = Vec::new();
+        let mut popped_words = 0;
+        while popped_words < word_count {
+            let value = self.pop();
+            popped_words += value.word_count();
+            values.push(value);
+        }
+        if popped_words != word_count {
+            panic!("[ERROR] Stack manipulation would split a \
+                    category 2 value.");
+        }
+        values.reverse();
+        values
+    }
+
+    /*
+     * Duplicates the given number of words at the top of the
+     * stack, inserting the copy beneath the words under them; this
+     * describes every form of the dup family of instructions.
+     */
+    fn dup_words(&mut self, word_count: usize, beneath_word_count: usize) {
+        let top = self.pop_words(word_count);
+        let beneath = self.pop_words(beneath_word_count);
+        self.operand_stack.extend(top.iter().cloned());
+        self.operand_stack.extend(beneath);
+        self.operand_stack.extend(top);
+    }
+
+    fn load(&self, idx: usize) -> Operand {
+        match self.local_var_arr[idx] {
+            Some(operand) => operand,
+            None => panic!("[ERROR] Local variable {} is uninitialized.",
+                           idx),
+        }
+    }
+
+    fn store(&mut self, idx: usize, operand: Operand) {
+        self.local_var_arr[idx] = Some(operand);
+        if operand.word_count() == 2 {
+            self.local_var_arr[idx + 1] = None;
+        }
+    }
+
+    fn typed_load(&mut self, operand_type: OperandType, idx: usize) {
+        let operand = self.load(idx);
+        if !operand_type.matches(&operand) {
+            panic!("[ERROR] Load of local variable {} found a value \
+                    of the wrong type.", idx);
+        }
+        self.push(operand);
+    }
+
+    fn typed_store(&mut self, operand_type: OperandType, idx: usize) {
+        let operand = self.pop();
+        // astore also stores the return addresses pushed by jsr.
+        let is_return_address = matches!(
+            (operand_type, operand),
+            (OperandType::Reference, Operand::ReturnAddress(_)));
+        if !operand_type.matches(&operand) && !is_return_address {
+            panic!("[ERROR] Store to local variable {} found a value \
+                    of the wrong type at top of stack.", idx);
+        }
+        self.store(idx, operand);
+    }
+
+    fn iinc(&mut self, idx: usize, increment: i32) {
+        match self.load(idx) {
+            Operand::Int(value) =>
+                self.store(idx, Operand::Int(value.wrapping_add(increment))),
+            _ => panic!("[ERROR] iinc expected Int in local \
+                         variable {} but didn't find one.", idx),
+        }
+    }
+
+    fn int_unary_op<F>(&mut self, op: F) where F: Fn(i32) -> i32 {
+        let value = self.pop_int();
+        self.push(Operand::Int(op(value)));
+    }
+
+    fn int_binary_op<F>(&mut self, op: F) where F: Fn(i32, i32) -> i32 {
+        let value2 = self.pop_int();
+        let value1 = self.pop_int();
+        self.push(Operand::Int(op(value1, value2)));
+    }
+
+    fn long_unary_op<F>(&mut self, op: F) where F: Fn(i64) -> i64 {
+        let value = self.pop_long();
+        self.push(Operand::Long(op(value)));
+    }
+
+    fn long_binary_op<F>(&mut self, op: F) where F: Fn(i64, i64) -> i64 {
+        let value2 = self.pop_long();
+        let value1 = self.pop_long();
+        self.push(Operand::Long(op(value1, value2)));
+    }
+
+    // Long shifts take an int shift distance.
+    fn long_shift_op<F>(&mut self, op: F) where F: Fn(i64, u32) -> i64 {
+        let value2 = self.pop_int();
+        let value1 = self.pop_long();
+        self.push(Operand::Long(op(value1, (value2 & 0x3f) as u32)));
+    }
+
+    fn float_binary_op<F>(&mut self, op: F) where F: Fn(f32, f32) -> f32 {
+        let value2 = self.pop_float();
+        let value1 = self.pop_float();
+        self.push(Operand::Float(op(value1, value2)));
+    }
+
+    fn double_binary_op<F>(&mut self, op: F) where F: Fn(f64, f64) -> f64 {
+        let value2 = self.pop_double();
+        let value1 = self.pop_double();
+        self.push(Operand::Double(op(value1, value2)));
+    }
+
+    /*
+     * Pushes -1, 0 or 1 according to how two values compare;
+     * if either is NaN, the values are unordered, in which case
+     * the given result is pushed instead.
+     */
+    fn compare<T>(&mut self, value1: T, value2: T, nan_result: i32)
+            where T: PartialOrd {
+        let result = match value1.partial_cmp(&value2) {
+            Some(Ordering::Less) => -1,
+            Some(Ordering::Equal) => 0,
+            Some(Ordering::Greater) => 1,
+            None => nan_result,
+        };
+        self.push(Operand::Int(result));
     }
 }
 
-fn istore_1(local_var_arr: &Vec<Operand>,
-        operand_stack: &mut Vec<Operand>) {
-    println!("istore_1");
-    let operand: Operand = match operand_stack.pop() {
-        Some(e @ Operand::Int(_)) => e,
-        Some(_) => panic!("[ERROR] istore_1 expected Int at top of stack \
-                but didn't get one."),
-        None => panic!("[ERROR] istore_1 expected Int at top of stack \
-                but none was found."),
-    };
+/*
+ * The kinds of method invocation instruction, which differ in
+ * how the method to invoke is selected once it has been resolved.
+ */
+#[derive(Copy, Clone, PartialEq)]
+enum Invocation {
+    Static,
+    Virtual,
+    Special,
+    Interface,
 }
 
-fn iconst_3(operand_stack: &mut Vec<Operand>) {
-    println!("iconst_3");
-    operand_stack.push(Operand::Int(3));
+/*
+ * The state of the VM that its threads share, which they reach
+ * through their interpreters while they hold the VM lock.
+ */
+struct Runtime {
+    loader: ClassLoader,
+    heap: Heap,
+    /*
+     * Pairs of class names, the first of which has been found to be
+     * assignable to the second; as subtype checks walk the class
+     * hierarchy, the answer for the casts that generic code performs
+     * repeatedly is worth remembering.
+     */
+    subtype_cache: HashSet<(&'static str, &'static str)>,
+    /*
+     * The String objects shared by all equal string constants and
+     * by the results of String.intern(), keyed by their UTF-16 chars
+     * (which, unlike Rust strings, may hold unpaired surrogates).
+     */
+    interned_strings: HashMap<Vec<u16>, Handle>,
+    /*
+     * The java.lang.Class objects standing for each class, keyed by
+     * class name, or for primitive types, by their Java name.
+     */
+    class_mirrors: HashMap<String, Handle>,
+    /*
+     * The values of method type, method handle and dynamic constants,
+     * which must resolve to the same value every time, keyed by the
+     * name of the class whose constant pool they belong to.
+     */
+    resolved_constants: HashMap<(&'static str, u16), Operand>,
+    // Where System.out and System.err are written.
+    stdout: Box<dyn Write + Send>,
+    stderr: Box<dyn Write + Send>,
+    // Set once guest code calls System.exit.
+    exit_status: Option<i32>,
+    /*
+     * Set once the Jvm is dropped, after which the threads that are
+     * still running are stopped.
+     */
+    shutting_down: bool,
+    /*
+     * The message of the panic that stopped a thread other than the
+     * main thread, which the main thread raises in turn.
+     */
+    internal_error: Option<String>,
+    // The threads that have started and not yet died, keyed by id.
+    threads: HashMap<u32, ThreadState>,
+    /*
+     * The descriptions of the exceptions that escaped threads other
+     * than the main thread, in the order they did.
+     */
+    thread_failures: Vec<String>,
+    os_threads: Vec<thread::JoinHandle<()>>,
+    // The turns threads take when they're scheduled deterministically.
+    schedule: Option<Schedule>,
+    // The green threads that are waiting for their next turn, by id.
+    green_threads: HashMap<u32, GreenThread>,
+    // The order in which threads start waiting on monitors.
+    next_wait_order: u64,
+    // Set while the collector's pending actions run, so they don't nest.
+    running_pending_actions: bool,
+    // The bytes the frames of each thread may take.
+    stack_size: usize,
+    system_properties: HashMap<String, String>,
+    environment: HashMap<String, String>,
+    assertions: Assertions,
+    verbose: Verbose,
+    /*
+     * The objects held by the host, which the collector treats as
+     * roots, as JNI does its global references.
+     */
+    global_refs: Vec<Handle>,
+    // When the VM started, which -verbose output logs relative to.
+    started: Instant,
 }
 
-fn aload_0<'a, 'b>(local_var_arr: &'b Vec<Operand<'a>>,
-               operand_stack: &mut Vec<Operand<'a>>) {
-    println!("aload_0");
-    operand_stack.push(local_var_arr[0]);
+/*
+ * The lock that serializes the threads of a VM, along with the state
+ * it guards.
+ */
+struct Vm {
+    lock: VmLock,
+    runtime: UnsafeCell<Runtime>,
 }
 
-fn invokespecial(operand_stack: &mut Vec<Operand>,
-                 indexbyte1: u16,
-                 indexbyte2: u16) {
-    let object_ref: &Object = match operand_stack.pop() {
-        Some(Operand::Ref(ref e)) => e,
-        Some(Operand::Int(_)) => panic!("[ERROR] invokespecial \
-                does not yet support integers."),
-        None => panic!("[ERROR] Expected objectref, found None."),
-    };
+/*
+ * The runtime is only reached through an interpreter whose thread
+ * holds the VM lock, as with the value of a Mutex.
+ */
+unsafe impl Sync for Vm {}
+
+/*
+ * The frames of a green thread that's waiting for its next turn,
+ * along with the rest of what its interpreter needs to pick up where
+ * it left off. A thread that has yet to take its first turn has no
+ * frames, and calls its run() method once it does.
+ */
+struct GreenThread {
+    frames: Vec<Frame>,
+    stack_used: usize,
+    local_refs: Vec<Handle>,
+    safepoint_countdown: u32,
+    resume_deadline: Option<Instant>,
+}
 
+/*
+ * Interprets the code of one thread, which has its own frames and
+ * local references. Each thread holds the VM lock while it runs, and
+ * gives it up only at safepoints and while it's blocked, when it
+ * holds no references into the runtime.
+ */
+struct Interpreter {
+    vm: Arc<Vm>,
+    frames: Vec<Frame>,
+    // The thread running this code, which owns the monitors it enters.
+    thread_id: u32,
+    // The bytes the frames take.
+    stack_used: usize,
     /*
-     * Join the two index bytes according to the JLS,
-     * then traverse the appropriate entries in the constant pool
-     * in order to determine the method name to invoke, along
-     * with the associated class name and method signature.
+     * The objects held by native methods as they run, and by the
+     * host, which the collector treats as roots, as JNI does its
+     * local references.
      */
-    let method_const_idx: u16 = (indexbyte1 << 8) | indexbyte2;
-    let method_const = match object_ref
-            .class.constant_pool.get(&method_const_idx) {
-        Some(&ConstantPoolEntry::MethodRef(ref e)) => e,
-        _ => panic!("[ERROR] Expected method ref in constant \
-                     pool at index {}.", method_const_idx),
-    };
-    let class_const = match object_ref
-            .class.constant_pool.get(&method_const.class_idx) {
-        Some(&ConstantPoolEntry::Class(ref e)) => e,
-        _ => panic!("[ERROR] Expected class in constant pool \
-                     at index {}.", method_const.class_idx),
-    };
-    let class_name = match object_ref
-            .class.constant_pool.get(&class_const.name_idx) {
-        Some(&ConstantPoolEntry::Utf8(ref e)) => e,
-        _ => panic!("[ERROR] Expected utf8 in constant pool \
-                     at index {}.", class_const.name_idx),
-    };
-    let name_type_const = match object_ref
-            .class.constant_pool.get(&method_const.name_and_type_idx) {
-        Some(&ConstantPoolEntry::NameAndType(ref e)) => e,
-        _ => panic!("[ERROR] Expected name/type in constant pool \
-                     at index {}.", method_const.name_and_type_idx),
-    };
-    let method_name = match object_ref
-            .class.constant_pool.get(&name_type_const.name_idx) {
-        Some(&ConstantPoolEntry::Utf8(ref e)) => e,
-        _ => panic!("[ERROR] Expected utf8 in constant pool \
-                     at index {}.", name_type_const.name_idx),
-    };
-    let method_descriptor = match object_ref
-            .class.constant_pool.get(&name_type_const.descriptor_idx) {
-        Some(&ConstantPoolEntry::Utf8(ref e)) => e,
-        _ => panic!("[ERROR] Expected utf8 in constant pool \
-                     at index {}.", name_type_const.descriptor_idx),
-    };
-    println!("invokespecial: Method {}.\"{}\":{}",
-             class_name.utf8_str,
-             method_name.utf8_str,
-             method_descriptor.utf8_str);
-    if class_name.utf8_str == "java/lang/Object"
-        && method_name.utf8_str == "<init>"
-        && method_descriptor.utf8_str == "()V" {
-        /*
-         * For now, do nothing. Eventually, this
-         * conditional will be removed when it makes
-         * sense to add support for dynamic class loading.
-         */
-    } else {
-        panic!("[TODO] Encountered a method other than Object.<init>; \
-                this means it's time to support dynamic class loading.");
-    };
+    local_refs: Vec<Handle>,
+    // The instructions left to execute before the next safepoint.
+    safepoint_countdown: u32,
+    /*
+     * Whether this is a green thread other than the main thread, whose
+     * frames are set aside when it gives up its turn.
+     */
+    green: bool,
+    /*
+     * Whether the instruction being executed can be suspended if it
+     * blocks, to be executed again from the start on the thread's
+     * next turn, rather than giving the other threads turns beneath it.
+     */
+    suspendable: bool,
+    // Set once the thread has given up its turn, until it's resumed.
+    suspended: bool,
+    /*
+     * The deadline the instruction a green thread was suspended in was
+     * blocked until, which it keeps as it's executed again.
+     */
+    resume_deadline: Option<Instant>,
+}
+
+impl Deref for Interpreter {
+    type Target = Runtime;
+
+    fn deref(&self) -> &Runtime {
+        // Safe as the thread holds the VM lock, as Interpreter requires.
+        unsafe { &*self.vm.runtime.get() }
+    }
+}
+
+impl DerefMut for Interpreter {
+    fn deref_mut(&mut self) -> &mut Runtime {
+        unsafe { &mut *self.vm.runtime.get() }
+    }
+}
+
+/*
+ * The view of the VM given to native methods, through which they
+ * can inspect and create objects and throw exceptions.
+ */
+pub struct Env<'a> {
+    interpreter: &'a mut Interpreter,
 }
 
-fn getstatic(object_ref: &Object, operand_stack: &mut Vec<Operand>,
-             indexbyte1: u16,
-             indexbyte2: u16) {
+impl<'a> Env<'a> {
+    pub fn heap(&self) -> &Heap {
+        &self.interpreter.heap
+    }
+
+    pub fn heap_mut(&mut self) -> &mut Heap {
+        &mut self.interpreter.heap
+    }
+
     /*
-     * Join the two index bytes according to the JLS,
-     * then traverse the appropriate entries in the constant pool
-     * in order to determine the static field to retrieve, along
-     * with the associated class name and method signature.
+     * The objects a native method creates or reads from fields are
+     * kept alive until it returns, as are those it's passed.
      */
-    let field_const_idx: u16 = (indexbyte1 << 8) | indexbyte2;
-    let field_const = match object_ref
-            .class.constant_pool.get(&field_const_idx) {
-        Some(&ConstantPoolEntry::FieldRef(ref e)) => e,
-        _ => panic!("[ERROR] Expected field ref in constant \
-                     pool at index {}.", field_const_idx),
-    };
-    let class_const = match object_ref
-            .class.constant_pool.get(&field_const.class_idx) {
-        Some(&ConstantPoolEntry::Class(ref e)) => e,
-        _ => panic!("[ERROR] Expected class in constant pool \
-                     at index {}.", field_const.class_idx),
-    };
-    let class_name = match object_ref
-            .class.constant_pool.get(&class_const.name_idx) {
-        Some(&ConstantPoolEntry::Utf8(ref e)) => e,
-        _ => panic!("[ERROR] Expected utf8 in constant pool \
-                     at index {}.", class_const.name_idx),
-    };
-    let name_type_const = match object_ref
-            .class.constant_pool.get(&field_const.name_and_type_idx) {
-        Some(&ConstantPoolEntry::NameAndType(ref e)) => e,
-        _ => panic!("[ERROR] Expected name/type in constant pool \
-                     at index {}.", field_const.name_and_type_idx),
-    };
-    let field_name = match object_ref
-            .class.constant_pool.get(&name_type_const.name_idx) {
-        Some(&ConstantPoolEntry::Utf8(ref e)) => e,
-        _ => panic!("[ERROR] Expected utf8 in constant pool \
-                     at index {}.", name_type_const.name_idx),
-    };
-    let field_descriptor = match object_ref
-            .class.constant_pool.get(&name_type_const.descriptor_idx) {
-        Some(&ConstantPoolEntry::Utf8(ref e)) => e,
-        _ => panic!("[ERROR] Expected utf8 in constant pool \
-                     at index {}.", name_type_const.descriptor_idx),
-    };
-    println!("getstatic: Field {}.{}:{}",
-             class_name.utf8_str,
-             field_name.utf8_str,
-             field_descriptor.utf8_str);
+    pub fn new_string(&mut self, value: &str)
+            -> Result<Handle, JavaThrowable> {
+        let string = self.interpreter.new_string(value)?;
+        self.interpreter.local_refs.push(string);
+        Ok(string)
+    }
+
+    pub fn string_value(&self, string: Handle) -> String {
+        self.interpreter.string_value(string)
+    }
+
+    pub fn intern_string(&mut self, string: Handle) -> Handle {
+        self.interpreter.intern_string(string)
+    }
+
+    // Returns the java.lang.Class object for the class of an object.
+    pub fn class_of(&mut self, object: Handle)
+            -> Result<Handle, JavaThrowable> {
+        let class = self.interpreter.heap.get(object).header.class;
+        self.interpreter.class_mirror(class)
+    }
+
+    pub fn get_field(&mut self, object: Handle, name: &str,
+                     descriptor: &str) -> Value {
+        let operand = self.interpreter.get_field(object, name, descriptor);
+        if let Operand::Ref(object) = operand {
+            self.interpreter.local_refs.push(object);
+        }
+        Value::from_operand(operand, &parse_field_descriptor(descriptor))
+    }
+
+    pub fn set_field(&mut self, object: Handle, name: &str,
+                     descriptor: &str, value: Value) {
+        if !value.is_of_type(Some(&parse_field_descriptor(descriptor))) {
+            panic!("[ERROR] Cannot store {:?} to field {}:{}.",
+                   value, name, descriptor);
+        }
+        if let Some(operand) = value.to_operand() {
+            self.interpreter.set_field(object, name, descriptor, operand);
+        }
+    }
+
+    /*
+     * Creates an exception of the given class with the given message,
+     * which the native method throws by returning it as its error.
+     */
+    pub fn throw(&mut self, class_name: &str, message: &str)
+            -> JavaThrowable {
+        self.interpreter.throw_exception(class_name, message)
+    }
+
+    /*
+     * Waits on the monitor of an object, which the current thread
+     * must own, until another thread notifies it or interrupts this
+     * one, or the given number of milliseconds (if not 0) pass. The
+     * monitor is given up while the thread waits.
+     */
+    pub fn wait(&mut self, object: Handle, timeout: i64)
+            -> Result<(), JavaThrowable> {
+        if timeout < 0 {
+            return Err(self.throw("java/lang/IllegalArgumentException",
+                                  "timeout value is negative"));
+        }
+        let interpreter = &mut *self.interpreter;
+        let thread = interpreter.thread_id;
+        // A green thread suspended while it waits calls this again.
+        let count = match interpreter.thread_state().wait_count {
+            Some(count) => count,
+            None => {
+                if interpreter.heap.get(object).header.monitor_owner()
+                        != Some(thread) {
+                    return Err(interpreter.illegal_monitor_state());
+                }
+                if interpreter.take_interrupt() {
+                    return Err(interpreter.interrupted_exception());
+                }
+                let header = &mut interpreter.heap.get_mut(object).header;
+                let count = match header.release_monitor(thread) {
+                    Some(count) => count,
+                    None => return Err(interpreter.illegal_monitor_state()),
+                };
+                interpreter.wake_blocked_threads();
+                interpreter.next_wait_order += 1;
+                let wait_order = interpreter.next_wait_order;
+                let state = interpreter.thread_state();
+                state.waiting_on = Some(object);
+                state.wait_order = wait_order;
+                state.notified = false;
+                state.wait_count = Some(count);
+                count
+            },
+        };
+
+        if interpreter.thread_state().waiting_on.is_some() {
+            let deadline = match timeout {
+                0 => None,
+                _ => interpreter.deadline_after(
+                    Duration::from_millis(timeout as u64)),
+            };
+            interpreter.block_until(deadline, |interpreter| {
+                let state = interpreter.thread_state();
+                state.notified || state.interrupted
+            })?;
+            interpreter.thread_state().waiting_on = None;
+        }
+        interpreter.block_until(None, |interpreter| {
+            interpreter.heap.get_mut(object).header
+                .try_reacquire_monitor(thread, count)
+        })?;
+        interpreter.thread_state().wait_count = None;
+        if interpreter.take_interrupt() {
+            return Err(interpreter.interrupted_exception());
+        }
+        Ok(())
+    }
+
+    /*
+     * Wakes one (or every) thread waiting on the monitor of an
+     * object, which the current thread must own; threads are woken
+     * in the order in which they started waiting.
+     */
+    pub fn notify(&mut self, object: Handle, all: bool)
+            -> Result<(), JavaThrowable> {
+        let interpreter = &mut *self.interpreter;
+        if interpreter.heap.get(object).header.monitor_owner()
+                != Some(interpreter.thread_id) {
+            return Err(interpreter.illegal_monitor_state());
+        }
+        let mut waiting: Vec<&mut ThreadState> = interpreter.threads
+            .values_mut()
+            .filter(|state| state.waiting_on == Some(object)
+                    && !state.notified)
+            .collect();
+        waiting.sort_by_key(|state| state.wait_order);
+        let count = if all { waiting.len() } else { 1 };
+        for state in waiting.into_iter().take(count) {
+            state.notified = true;
+        }
+        interpreter.wake_blocked_threads();
+        Ok(())
+    }
+
+    // Returns the java.lang.Thread standing for the current thread.
+    pub fn current_thread(&mut self) -> Result<Handle, JavaThrowable> {
+        let thread = self.interpreter.current_thread()?;
+        self.interpreter.local_refs.push(thread);
+        Ok(thread)
+    }
+
+    /*
+     * Starts a java.lang.Thread, whose run() method is called on a
+     * thread of its own, or on its first turn, if it's a green thread.
+     */
+    pub fn start_thread(&mut self, thread: Handle)
+            -> Result<(), JavaThrowable> {
+        self.interpreter.start_thread(thread)
+    }
+
+    /*
+     * Sleeps for the given number of milliseconds, unless the thread
+     * is interrupted first.
+     */
+    pub fn sleep(&mut self, millis: i64) -> Result<(), JavaThrowable> {
+        if millis < 0 {
+            return Err(self.throw("java/lang/IllegalArgumentException",
+                                  "timeout value is negative"));
+        }
+        let deadline = self.interpreter.deadline_after(
+            Duration::from_millis(millis as u64));
+        self.interpreter.block_until(deadline, |interpreter| {
+            interpreter.thread_state().interrupted
+        })?;
+        if self.interpreter.take_interrupt() {
+            return Err(self.throw("java/lang/InterruptedException",
+                                  "sleep interrupted"));
+        }
+        Ok(())
+    }
+
+    // Lets the other threads run before the current one continues.
+    pub fn yield_now(&mut self) {
+        self.interpreter.yield_now();
+    }
+
+    /*
+     * Waits for a java.lang.Thread to die, for at most the given number
+     * of milliseconds, or if it's 0, indefinitely, unless the current
+     * thread is interrupted first.
+     */
+    pub fn join(&mut self, thread: Handle, millis: i64)
+            -> Result<(), JavaThrowable> {
+        if millis < 0 {
+            return Err(self.throw("java/lang/IllegalArgumentException",
+                                  "timeout value is negative"));
+        }
+        let id = self.interpreter.thread_id_of(thread);
+        let deadline = match millis {
+            0 => None,
+            _ => self.interpreter.deadline_after(
+                Duration::from_millis(millis as u64)),
+        };
+        self.interpreter.block_until(deadline, |interpreter| {
+            !interpreter.threads.contains_key(&id)
+                || interpreter.thread_state().interrupted
+        })?;
+        if self.interpreter.take_interrupt() {
+            return Err(self.interpreter.interrupted_exception());
+        }
+        Ok(())
+    }
+
+    // Whether a java.lang.Thread has been started and has yet to die.
+    pub fn is_alive(&self, thread: Handle) -> bool {
+        let id = self.interpreter.thread_id_of(thread);
+        self.interpreter.threads.contains_key(&id)
+    }
+
+    /*
+     * Sets the interrupt status of a java.lang.Thread, waking it if
+     * it's sleeping, joining, waiting or parked. Threads that aren't
+     * alive are unaffected.
+     */
+    pub fn interrupt(&mut self, thread: Handle) {
+        let id = self.interpreter.thread_id_of(thread);
+        if let Some(state) = self.interpreter.threads.get_mut(&id) {
+            state.interrupted = true;
+        }
+        self.interpreter.wake_blocked_threads();
+    }
+
+    // Tests the interrupt status of a java.lang.Thread, clearing it if asked.
+    pub fn is_interrupted(&mut self, thread: Handle, clear: bool) -> bool {
+        let id = self.interpreter.thread_id_of(thread);
+        match self.interpreter.threads.get_mut(&id) {
+            Some(state) if clear => mem::replace(&mut state.interrupted,
+                                                 false),
+            Some(state) => state.interrupted,
+            None => false,
+        }
+    }
+
+    // Whether the current thread owns the monitor of an object.
+    pub fn holds_lock(&self, object: Handle) -> bool {
+        self.interpreter.heap.get(object).header.monitor_owner()
+            == Some(self.interpreter.thread_id)
+    }
+
+    /*
+     * Blocks the current thread until its permit is available, which
+     * it consumes, it's interrupted, or the given number of nanoseconds
+     * (if not 0) pass, as LockSupport.park does.
+     */
+    pub fn park(&mut self, nanos: i64) -> Result<(), JavaThrowable> {
+        let deadline = match nanos {
+            0 => None,
+            _ => self.interpreter.deadline_after(
+                Duration::from_nanos(nanos as u64)),
+        };
+        self.interpreter.block_until(deadline, |interpreter| {
+            let state = interpreter.thread_state();
+            state.permit || state.interrupted
+        })?;
+        self.interpreter.thread_state().permit = false;
+        Ok(())
+    }
+
+    // Makes the permit of a java.lang.Thread available, if it's alive.
+    pub fn unpark(&mut self, thread: Handle) {
+        let id = self.interpreter.thread_id_of(thread);
+        if let Some(state) = self.interpreter.threads.get_mut(&id) {
+            state.permit = true;
+        }
+        self.interpreter.wake_blocked_threads();
+    }
+
+    /*
+     * Terminates the program with the given status, as System.exit
+     * does. The native returns the ThreadDeath this creates as its
+     * error, which unwinds every frame without running handlers, and
+     * the other threads throw ThreadDeaths of their own.
+     */
+    pub fn exit(&mut self, status: i32) -> JavaThrowable {
+        self.interpreter.exit_status = Some(status);
+        self.interpreter.wake_blocked_threads();
+        let class = self.interpreter.load_class("java/lang/ThreadDeath");
+        JavaThrowable(self.interpreter.allocate_throwable(class))
+    }
+
+    /*
+     * Whether assertions are enabled for the class with the given
+     * dotted name, e.g. "com.acme.Main".
+     */
+    pub fn desired_assertion_status(&self, class_name: &str) -> bool {
+        self.interpreter.assertions.desired_status(class_name)
+    }
+
+    pub fn system_property(&self, key: &str) -> Option<&str> {
+        self.interpreter.system_properties.get(key).map(|value| &value[..])
+    }
+
+    // Sets a system property, returning its previous value, if any.
+    pub fn set_system_property(&mut self, key: &str, value: &str)
+            -> Option<String> {
+        self.interpreter.system_properties.insert(key.to_string(),
+                                                  value.to_string())
+    }
+
+    pub fn clear_system_property(&mut self, key: &str) -> Option<String> {
+        self.interpreter.system_properties.remove(key)
+    }
+
+    pub fn system_property_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.interpreter.system_properties
+            .keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    pub fn environment_variable(&self, name: &str) -> Option<&str> {
+        self.interpreter.environment.get(name).map(|value| &value[..])
+    }
+
+    /*
+     * Creates a String[] holding the given strings, or throws an
+     * OutOfMemoryError if the heap has no room for the array.
+     */
+    pub fn new_string_array<S: AsRef<str>>(&mut self, values: &[S])
+            -> Result<Handle, JavaThrowable> {
+        let array = self.interpreter.new_string_array(values)?;
+        self.interpreter.local_refs.push(array);
+        Ok(array)
+    }
+
+    pub fn collect_garbage(&mut self) {
+        self.interpreter.collect_garbage(GcCause::Explicit, 0, false);
+    }
+
+    pub fn stdout(&mut self) -> &mut dyn Write {
+        &mut *self.interpreter.stdout
+    }
+
+    pub fn stderr(&mut self) -> &mut dyn Write {
+        &mut *self.interpreter.stderr
+    }
+}
+
+/*
+ * The options a Jvm is created with.
+ */
+pub struct JvmOptions {
+    // The directories and jar files searched, in order, for classes.
+    pub class_path: Vec<PathBuf>,
+    // The bytes the heap may take before it's first collected.
+    pub initial_heap_size: Option<usize>,
+    // The most bytes the heap may take, if limited.
+    pub max_heap_size: Option<usize>,
+    pub collector: Collector,
+    pub scheduler: Scheduler,
+    // The most bytes the frames of the stack may take.
+    pub stack_size: usize,
+    /*
+     * The system properties set in addition to the standard ones
+     * (e.g., "java.version"), any of which they override.
+     */
+    pub system_properties: HashMap<String, String>,
+    /*
+     * The environment variables System.getenv sees, by default those
+     * of this process.
+     */
+    pub environment: HashMap<String, String>,
+    pub assertions: Assertions,
+    pub verbose: Verbose,
+    // Where System.out and System.err are written.
+    pub stdout: Box<dyn Write + Send>,
+    pub stderr: Box<dyn Write + Send>,
+    pub natives: NativeRegistry,
+}
+
+// The id of the thread that runs the main method.
+pub const MAIN_THREAD_ID: u32 = 1;
+
+// As in HotSpot on 64-bit Linux.
+pub const DEFAULT_STACK_SIZE: usize = 1 << 20;
+
+impl Default for JvmOptions {
+    fn default() -> Self {
+        JvmOptions {
+            class_path: vec![PathBuf::from(".")],
+            initial_heap_size: None,
+            max_heap_size: None,
+            collector: Collector::default(),
+            scheduler: Scheduler::default(),
+            stack_size: DEFAULT_STACK_SIZE,
+            system_properties: HashMap::new(),
+            environment: env::vars_os()
+                .filter_map(|(name, value)| {
+                    Some((name.into_string().ok()?, value.into_string().ok()?))
+                })
+                .collect(),
+            assertions: Assertions::default(),
+            verbose: Verbose::default(),
+            stdout: Box::new(io::stdout()),
+            stderr: Box::new(io::stderr()),
+            natives: NativeRegistry::new(),
+        }
+    }
+}
+
+/*
+ * How the threads guest code starts take turns running.
+ */
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Scheduler {
+    /*
+     * Each thread runs on a thread of its own, and they take turns as
+     * the OS runs them, letting each other run at safepoints.
+     */
+    #[default]
+    Os,
+    /*
+     * The threads are green threads, which all run on the thread that
+     * created the VM and take turns in an order chosen at random from
+     * the given seed, switching between their frames at safepoints that
+     * come after random numbers of instructions, with time made
+     * virtual, so that each seed gives a program an interleaving of
+     * its own that runs the same way every time.
+     */
+    Deterministic(u64),
+}
+
+/*
+ * What the VM reports on standard output as it runs.
+ */
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Verbose {
+    // Each class as it's loaded, as -verbose:class does.
+    pub class: bool,
+    // Each garbage collection, as -verbose:gc does.
+    pub gc: bool,
+    // Each class file parsed and method invoked, for debugging the VM.
+    pub trace: bool,
+}
+
+/*
+ * Which classes have assertions enabled, as set by -ea and -da. A
+ * setting for a class overrides any for its package, and a setting
+ * for a package overrides any for the packages enclosing it, which
+ * in turn override the default. Names are dotted, and the unnamed
+ * package is named "".
+ */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Assertions {
+    pub enabled_by_default: bool,
+    pub packages: HashMap<String, bool>,
+    pub classes: HashMap<String, bool>,
+}
+
+impl Assertions {
+    pub fn desired_status(&self, class_name: &str) -> bool {
+        if let Some(&enabled) = self.classes.get(class_name) {
+            return enabled;
+        }
+        let mut package = match class_name.rfind('.') {
+            Some(idx) => &class_name[.. idx],
+            None => "",
+        };
+        loop {
+            if let Some(&enabled) = self.packages.get(package) {
+                return enabled;
+            }
+            // Packages in the unnamed package don't inherit its setting.
+            match package.rfind('.') {
+                Some(idx) => package = &package[.. idx],
+                None => return self.enabled_by_default,
+            }
+        }
+    }
+}
+
+/*
+ * The system properties every VM starts with, describing the
+ * platform, the user and the class path.
+ */
+fn standard_properties(class_path: &[PathBuf]) -> HashMap<String, String> {
+    let (os_name, line_separator, path_separator) = match env::consts::OS {
+        "linux" => ("Linux", "\n", ":"),
+        "macos" => ("Mac OS X", "\n", ":"),
+        "windows" => ("Windows", "\r\n", ";"),
+        os => (os, "\n", ":"),
+    };
+    let os_arch = match env::consts::ARCH {
+        "x86_64" => "amd64",
+        "x86" => "x86",
+        arch => arch,
+    };
+    let class_path: Vec<String> = class_path.iter()
+        .map(|entry| entry.to_string_lossy().into_owned())
+        .collect();
+    let mut properties: HashMap<String, String> = [
+        ("file.separator", path::MAIN_SEPARATOR.to_string()),
+        ("java.class.path", class_path.join(path_separator)),
+        ("java.version", "1.8.0".to_string()),
+        ("java.vm.name", "RustedJVM".to_string()),
+        ("java.vm.version", env!("CARGO_PKG_VERSION").to_string()),
+        ("line.separator", line_separator.to_string()),
+        ("os.arch", os_arch.to_string()),
+        ("os.name", os_name.to_string()),
+        ("path.separator", path_separator.to_string()),
+    ].iter().map(|&(key, ref value)| (key.to_string(), value.clone()))
+        .collect();
+    if let Ok(dir) = env::current_dir() {
+        properties.insert("user.dir".to_string(),
+                          dir.to_string_lossy().into_owned());
+    }
+    let user_vars = [("user.home", "HOME"), ("user.home", "USERPROFILE"),
+                     ("user.name", "USER"), ("user.name", "USERNAME")];
+    for &(key, var) in user_vars.iter() {
+        if let Ok(value) = env::var(var) {
+            properties.entry(key.to_string()).or_insert(value);
+        }
+    }
+    properties
+}
+
+/*
+ * A virtual machine embedded in a Rust program, through which the
+ * program can load classes, create objects and call Java methods.
+ * Java exceptions that escape are returned as errors, while internal
+ * VM errors panic, as they do while running a program's main method.
+ */
+pub struct Jvm {
+    interpreter: Interpreter,
+}
+
+/*
+ * Dropping the Jvm stops the threads guest code started that are
+ * still running, much as the VM stops daemon threads as it exits.
+ */
+impl Drop for Jvm {
+    fn drop(&mut self) {
+        self.interpreter.shutting_down = true;
+        loop {
+            let os_threads = mem::take(&mut self.interpreter.os_threads);
+            if os_threads.is_empty() {
+                break;
+            }
+            let vm = self.interpreter.vm.clone();
+            vm.lock.signal_event();
+            vm.lock.release();
+            for os_thread in os_threads {
+                let _ = os_thread.join();
+            }
+            vm.lock.acquire();
+        }
+        // As with PrintStream, failures to write are not reported.
+        let _ = self.interpreter.stdout.flush();
+        let _ = self.interpreter.stderr.flush();
+    }
+}
+
+impl Jvm {
+    /*
+     * Creates a VM, whose main thread is the host's. Threads that
+     * guest code starts run only while the host is calling into the
+     * VM, as the host's thread holds the VM lock the rest of the time.
+     */
+    pub fn new(options: JvmOptions) -> Jvm {
+        let mut system_properties = standard_properties(&options.class_path);
+        system_properties.extend(options.system_properties);
+        let started = Instant::now();
+        let initial_heap_size = options.initial_heap_size
+            .unwrap_or(DEFAULT_INITIAL_HEAP_SIZE);
+        let mut threads = HashMap::new();
+        threads.insert(MAIN_THREAD_ID, ThreadState::new(None, false));
+        let schedule = match options.scheduler {
+            Scheduler::Os => None,
+            Scheduler::Deterministic(seed) => {
+                let mut schedule = Schedule::new(seed);
+                schedule.admit(MAIN_THREAD_ID);
+                Some(schedule)
+            },
+        };
+        let runtime = Runtime {
+            loader: ClassLoader::new(options.class_path, options.natives,
+                                     options.verbose, started),
+            heap: Heap::with_sizes(initial_heap_size, options.max_heap_size,
+                                   options.collector),
+            subtype_cache: HashSet::new(),
+            interned_strings: HashMap::new(),
+            class_mirrors: HashMap::new(),
+            resolved_constants: HashMap::new(),
+            stdout: options.stdout,
+            stderr: options.stderr,
+            exit_status: None,
+            shutting_down: false,
+            internal_error: None,
+            threads,
+            thread_failures: Vec::new(),
+            os_threads: Vec::new(),
+            schedule,
+            green_threads: HashMap::new(),
+            next_wait_order: 0,
+            running_pending_actions: false,
+            stack_size: options.stack_size,
+            system_properties,
+            environment: options.environment,
+            assertions: options.assertions,
+            verbose: options.verbose,
+            global_refs: Vec::new(),
+            started,
+        };
+        let vm = Arc::new(Vm {
+            lock: VmLock::new(),
+            runtime: UnsafeCell::new(runtime),
+        });
+        vm.lock.acquire();
+        Jvm { interpreter: Interpreter::new(vm, MAIN_THREAD_ID) }
+    }
+
+    /*
+     * Waits for every thread other than the main thread that isn't
+     * a daemon thread to die, as the java launcher does once main
+     * returns, before the VM exits.
+     */
+    pub fn wait_for_non_daemon_threads(&mut self) {
+        // A thread calling System.exit ends the wait, so its result is moot.
+        let _ = self.interpreter.block_until(None, |interpreter| {
+            interpreter.threads.iter().all(
+                |(&id, thread)| id == MAIN_THREAD_ID || thread.daemon)
+        });
+    }
+
+    pub fn heap(&self) -> &Heap {
+        &self.interpreter.heap
+    }
+
+    /*
+     * The status guest code passed to System.exit, if it called it.
+     * The call unwinds to the host as a ThreadDeath, and the VM
+     * shouldn't be asked to run any more code afterward.
+     */
+    pub fn exit_status(&self) -> Option<i32> {
+        self.interpreter.exit_status
+    }
+
+    pub fn system_property(&self, key: &str) -> Option<&str> {
+        self.interpreter.system_properties.get(key).map(|value| &value[..])
+    }
+
+    // Sets a system property, returning its previous value, if any.
+    pub fn set_system_property(&mut self, key: &str, value: &str)
+            -> Option<String> {
+        self.interpreter.system_properties.insert(key.to_string(),
+                                                  value.to_string())
+    }
+
+    pub fn clear_system_property(&mut self, key: &str) -> Option<String> {
+        self.interpreter.system_properties.remove(key)
+    }
+
+    /*
+     * The handles given to the host, along with the exceptions
+     * thrown to it, are local references, which keep their objects
+     * alive until the host deletes them all at once, as returning
+     * from a JNI native method does. Objects the host holds on to
+     * for longer should be kept alive by global references.
+     */
+    pub fn delete_local_refs(&mut self) {
+        self.interpreter.local_refs.clear();
+    }
+
+    pub fn new_global_ref(&mut self, object: Handle) -> Handle {
+        self.interpreter.global_refs.push(object);
+        object
+    }
+
+    // Deletes one of the global references to the object.
+    pub fn delete_global_ref(&mut self, object: Handle) {
+        let global_refs = &mut self.interpreter.global_refs;
+        if let Some(idx) = global_refs.iter().position(|&o| o == object) {
+            global_refs.swap_remove(idx);
+        }
+    }
+
+    // Frees the objects that are no longer reachable, as System.gc() does.
+    pub fn collect_garbage(&mut self) -> Collection {
+        self.interpreter.collect_garbage(GcCause::Explicit, 0, false)
+    }
+
+    /*
+     * Loads and links the class with the given binary name
+     * (e.g., "com/acme/Main"), which is initialized only once
+     * it's first used.
+     */
+    pub fn load_class(&mut self, class_name: &str)
+            -> Result<&'static RuntimeClass, JavaThrowable> {
+        match self.interpreter.loader.load(class_name) {
+            Some(class) => Ok(class),
+            None => Err(self.throw(
+                "java/lang/NoClassDefFoundError", class_name)),
+        }
+    }
+
+    /*
+     * Runs the main method of the given class with the given
+     * command line arguments.
+     */
+    pub fn run_main(&mut self, class_name: &str, args: &[&str])
+            -> Result<(), JavaThrowable> {
+        let class = self.load_class(class_name)?;
+        let array = self.interpreter.new_string_array(args);
+        let array = self.rooted(array)?;
+        self.interpreter.local_refs.push(array);
+        self.invoke_static(class, "main", "([Ljava/lang/String;)V",
+                           &[Value::Ref(array)])?;
+        Ok(())
+    }
+
+    /*
+     * Calls a static method declared by the given class or inherited
+     * from its superclasses, initializing the class first if need be.
+     */
+    pub fn invoke_static(&mut self, class: &'static RuntimeClass,
+                         name: &str, descriptor: &str, args: &[Value])
+            -> Result<Value, JavaThrowable> {
+        let (class, method) = self.find_method(class, name, descriptor)?;
+        if !method.is_static() {
+            return Err(self.throw(
+                "java/lang/IncompatibleClassChangeError",
+                &format!("Expected static method '{}'",
+                         describe_method(class.name, name, descriptor))));
+        }
+        let args = self.check_args(class, method, args)?;
+        let initialized = self.interpreter.initialize_now(class);
+        self.rooted(initialized)?;
+        let result = self.interpreter.call_method(class, method, args);
+        let result = to_value(self.rooted(result)?, method);
+        Ok(self.local_ref(result))
+    }
+
+    /*
+     * Calls an instance method on the given object, selecting the
+     * implementation by the object's class as invokevirtual does.
+     */
+    pub fn invoke_virtual(&mut self, object: Handle, name: &str,
+                          descriptor: &str, args: &[Value])
+            -> Result<Value, JavaThrowable> {
+        let class = self.interpreter.heap.get(object).header.class;
+        let (class, method) = self.find_method(class, name, descriptor)?;
+        if method.is_static() {
+            return Err(self.throw(
+                "java/lang/IncompatibleClassChangeError",
+                &format!("Expecting non-static method '{}'",
+                         describe_method(class.name, name, descriptor))));
+        }
+        if method.is_abstract() {
+            return Err(self.throw(
+                "java/lang/AbstractMethodError",
+                &describe_method(class.name, name, descriptor)));
+        }
+        let mut operands = vec![Operand::Ref(object)];
+        operands.extend(self.check_args(class, method, args)?);
+        let result = self.interpreter.call_method(class, method, operands);
+        let result = to_value(self.rooted(result)?, method);
+        Ok(self.local_ref(result))
+    }
+
+    /*
+     * Creates an instance of the given class, initializing the class
+     * if need be, and calls the constructor with the given descriptor.
+     */
+    pub fn new_object(&mut self, class: &'static RuntimeClass,
+                      descriptor: &str, args: &[Value])
+            -> Result<Handle, JavaThrowable> {
+        if class.is_interface() || class.is_abstract() || class.is_array() {
+            return Err(self.throw(
+                "java/lang/InstantiationError",
+                &class.name.replace('/', ".")));
+        }
+        let constructor = match class.declared_method("<init>", descriptor) {
+            Some(constructor) => constructor,
+            None => return Err(self.throw(
+                "java/lang/NoSuchMethodError",
+                &format!("'{}'", describe_method(class.name, "<init>",
+                                                 descriptor)))),
+        };
+        let args = self.check_args(class, constructor, args)?;
+        let initialized = self.interpreter.initialize_now(class);
+        self.rooted(initialized)?;
+        let reserved = self.interpreter.reserve(Heap::object_size(class));
+        self.rooted(reserved)?;
+        let throwable_class =
+            self.interpreter.load_class("java/lang/Throwable");
+        let object = if class.is_subclass_of(throwable_class) {
+            self.interpreter.allocate_throwable(class)
+        } else {
+            self.interpreter.heap.allocate(class)
+        };
+        self.interpreter.local_refs.push(object);
+        let mut operands = vec![Operand::Ref(object)];
+        operands.extend(args);
+        let result = self.interpreter.call_method(class, constructor,
+                                                  operands);
+        self.rooted(result)?;
+        Ok(object)
+    }
+
+    pub fn get_field(&mut self, object: Handle, name: &str,
+                     descriptor: &str) -> Result<Value, JavaThrowable> {
+        let idx = self.instance_field_idx(object, name, descriptor)?;
+        let value = self.interpreter.heap.get(object).fields()[idx];
+        let value = Value::from_operand(value,
+                                        &parse_field_descriptor(descriptor));
+        Ok(self.local_ref(value))
+    }
+
+    pub fn set_field(&mut self, object: Handle, name: &str, descriptor: &str,
+                     value: Value) -> Result<(), JavaThrowable> {
+        let idx = self.instance_field_idx(object, name, descriptor)?;
+        let operand = self.check_arg(&parse_field_descriptor(descriptor),
+                                     value)?;
+        self.interpreter.heap.get_mut(object).fields_mut()[idx] = operand;
+        self.interpreter.heap.write_barrier(object);
+        Ok(())
+    }
+
+    /*
+     * Gets and sets static fields, which are resolved as getstatic
+     * and putstatic resolve them, initializing the declaring class
+     * first if need be.
+     */
+    pub fn get_static(&mut self, class: &'static RuntimeClass, name: &str,
+                      descriptor: &str) -> Result<Value, JavaThrowable> {
+        let (class, idx) = self.static_field(class, name, descriptor)?;
+        let value = self.interpreter.loader.statics(class).values[idx];
+        let value = Value::from_operand(value,
+                                        &parse_field_descriptor(descriptor));
+        Ok(self.local_ref(value))
+    }
+
+    pub fn set_static(&mut self, class: &'static RuntimeClass, name: &str,
+                      descriptor: &str, value: Value)
+            -> Result<(), JavaThrowable> {
+        let (class, idx) = self.static_field(class, name, descriptor)?;
+        let operand = self.check_arg(&parse_field_descriptor(descriptor),
+                                     value)?;
+        self.interpreter.loader.statics_mut(class).values[idx] = operand;
+        self.interpreter.heap.static_write_barrier(class.name);
+        Ok(())
+    }
+
+    pub fn new_string(&mut self, value: &str)
+            -> Result<Handle, JavaThrowable> {
+        let string = self.interpreter.new_string(value);
+        let string = self.rooted(string)?;
+        self.interpreter.local_refs.push(string);
+        Ok(string)
+    }
+
+    pub fn string_value(&self, string: Handle) -> String {
+        self.interpreter.string_value(string)
+    }
+
+    /*
+     * Describes an exception as Throwable's toString does,
+     * e.g. "java.lang.ArithmeticException: / by zero".
+     */
+    pub fn describe_exception(&mut self, exception: JavaThrowable) -> String {
+        self.interpreter.describe_throwable(exception.0)
+    }
+
+    /*
+     * Writes an exception, its stack trace and its causes to the
+     * VM's standard error, as if it escaped the main thread.
+     */
+    pub fn report_uncaught(&mut self, exception: JavaThrowable) {
+        self.interpreter.report_uncaught(exception);
+    }
+
+    // Throws an exception to the host, as a local reference.
+    fn throw(&mut self, class_name: &str, message: &str) -> JavaThrowable {
+        let exception = self.interpreter.throw_exception(class_name, message);
+        self.interpreter.local_refs.push(exception.0);
+        exception
+    }
+
+    fn rooted<T>(&mut self, result: Result<T, JavaThrowable>)
+            -> Result<T, JavaThrowable> {
+        if let Err(JavaThrowable(exception)) = result {
+            self.interpreter.local_refs.push(exception);
+        }
+        result
+    }
+
+    fn local_ref(&mut self, value: Value) -> Value {
+        if let Value::Ref(object) = value {
+            self.interpreter.local_refs.push(object);
+        }
+        value
+    }
+
+    fn find_method(&mut self, class: &'static RuntimeClass, name: &str,
+                   descriptor: &str)
+            -> Result<(&'static RuntimeClass, &'static Method<'static>),
+                      JavaThrowable> {
+        match class.find_method(name, descriptor) {
+            Some(found) => Ok(found),
+            None => Err(self.throw(
+                "java/lang/NoSuchMethodError",
+                &format!("'{}'", describe_method(class.name, name,
+                                                 descriptor)))),
+        }
+    }
+
+    fn instance_field_idx(&mut self, object: Handle, name: &str,
+                          descriptor: &str) -> Result<usize, JavaThrowable> {
+        let class = self.interpreter.heap.get(object).header.class;
+        match class.instance_field_idx(name, descriptor) {
+            Some(idx) => Ok(idx),
+            None => Err(self.throw(
+                "java/lang/NoSuchFieldError", name)),
+        }
+    }
+
+    fn static_field(&mut self, class: &'static RuntimeClass, name: &str,
+                    descriptor: &str)
+            -> Result<(&'static RuntimeClass, usize), JavaThrowable> {
+        match class.find_static_field(name, descriptor) {
+            Some((class, idx)) => {
+                let initialized = self.interpreter.initialize_now(class);
+                self.rooted(initialized)?;
+                Ok((class, idx))
+            },
+            None => Err(self.throw(
+                "java/lang/NoSuchFieldError", name)),
+        }
+    }
+
+    /*
+     * Checks arguments against the parameters of a method, as
+     * reflective calls do, and converts them to operands.
+     */
+    fn check_args(&mut self, class: &RuntimeClass, method: &Method,
+                  args: &[Value]) -> Result<Vec<Operand>, JavaThrowable> {
+        let descriptor = parse_method_descriptor(method.descriptor);
+        if descriptor.params.len() != args.len() {
+            return Err(self.throw(
+                "java/lang/IllegalArgumentException",
+                &format!("wrong number of arguments for '{}': {} \
+                          expected, {} given",
+                         describe_method(class.name, method.name,
+                                         method.descriptor),
+                         descriptor.params.len(), args.len())));
+        }
+        descriptor.params.iter().zip(args.iter())
+            .map(|(param, &arg)| self.check_arg(param, arg))
+            .collect()
+    }
+
+    fn check_arg(&mut self, field_type: &FieldType, value: Value)
+            -> Result<Operand, JavaThrowable> {
+        let is_assignable = match value {
+            Value::Ref(object) if field_type.is_reference() => {
+                let class = self.interpreter.heap.get(object).header.class;
+                let target = match *field_type {
+                    FieldType::Object(ref class_name) =>
+                        self.load_class(class_name)?,
+                    _ => self.load_class(&field_type.to_string())?,
+                };
+                self.interpreter.is_subtype(class, target)
+            },
+            _ => value.is_of_type(Some(field_type)),
+        };
+        match value.to_operand() {
+            Some(operand) if is_assignable => Ok(operand),
+            _ => Err(self.throw(
+                "java/lang/IllegalArgumentException",
+                &format!("argument type mismatch: {:?} is not a {}",
+                         value, field_type.java_name()))),
+        }
+    }
+}
+
+fn to_value(result: Option<Operand>, method: &Method) -> Value {
+    let descriptor = parse_method_descriptor(method.descriptor);
+    match (result, descriptor.ret) {
+        (Some(result), Some(ret)) => Value::from_operand(result, &ret),
+        (None, None) => Value::Void,
+        _ => panic!("[ERROR] {}{} returned a value of the wrong type.",
+                    method.name, method.descriptor),
+    }
+}
+
+/*
+ * Runs the main method of the given class, and then waits for the
+ * threads it started that aren't daemon threads to die. If an
+ * exception escapes main, the exception is reported, and its
+ * description is returned.
+ */
+pub fn run(class_path: &Path, class_name: &str) -> Result<(), RunError> {
+    run_with_options(JvmOptions {
+        class_path: vec![class_path.to_path_buf()],
+        ..JvmOptions::default()
+    }, class_name, &[])
+}
+
+/*
+ * Runs the main method of the given class as run() does, passing it
+ * the given arguments, in a VM created with the given options.
+ */
+pub fn run_with_options(options: JvmOptions, class_name: &str,
+                        args: &[&str]) -> Result<(), RunError> {
+    run_program(options, class_name, args, false)
+}
+
+/*
+ * Runs a program under each of the given deterministic schedules,
+ * with the options the given function creates for the seed of each,
+ * and returns the seeds under which it failed, along with how: by
+ * exiting with a non-zero status, by letting an exception escape any
+ * of its threads, or by deadlocking, which the VM fails on. Running
+ * the program again with one of those seeds reproduces its failure.
+ */
+pub fn explore_schedules<F, I>(mut options: F, class_name: &str,
+                               args: &[&str], seeds: I)
+        -> Result<Vec<(u64, RunError)>, RunError>
+        where F: FnMut(u64) -> JvmOptions, I: IntoIterator<Item = u64> {
+    let mut failures = Vec::new();
+    for seed in seeds {
+        let options = JvmOptions {
+            scheduler: Scheduler::Deterministic(seed),
+            ..options(seed)
+        };
+        match run_program(options, class_name, args, true) {
+            Ok(()) => {},
+            Err(RunError::InvalidMainClass(reason)) =>
+                return Err(RunError::InvalidMainClass(reason)),
+            Err(error) => failures.push((seed, error)),
+        }
+    }
+    Ok(failures)
+}
+
+/*
+ * Runs a program as run_with_options does, but if asked, fails if
+ * an exception escaped any of its threads, not only the main thread.
+ */
+fn run_program(options: JvmOptions, class_name: &str, args: &[&str],
+               fail_on_thread_failure: bool) -> Result<(), RunError> {
+    match panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut jvm = Jvm::new(options);
+        check_main_class(&mut jvm, class_name)?;
+        let uncaught = match jvm.run_main(class_name, args) {
+            Err(exception) if jvm.exit_status().is_none() =>
+                Some(jvm.interpreter.report_uncaught(exception)),
+            _ => None,
+        };
+        jvm.wait_for_non_daemon_threads();
+        let uncaught = match uncaught {
+            None if fail_on_thread_failure =>
+                jvm.interpreter.thread_failures.first().cloned(),
+            uncaught => uncaught,
+        };
+        match (uncaught, jvm.exit_status()) {
+            (_, Some(0)) | (None, None) => Ok(()),
+            (_, Some(status)) => Err(RunError::Exit(status)),
+            (Some(description), None) =>
+                Err(RunError::UncaughtException(description)),
+        }
+    })) {
+        Ok(result) => result,
+        Err(payload) => Err(RunError::Internal(VmError::from_panic(payload))),
+    }
+}
+
+/*
+ * As the java launcher does, refuses to run a class unless it
+ * declares or inherits a public static void main(String[]).
+ */
+fn check_main_class(jvm: &mut Jvm, class_name: &str) -> Result<(), RunError> {
+    let class = match jvm.interpreter.loader.load(class_name) {
+        Some(class) => class,
+        None => return Err(RunError::InvalidMainClass(format!(
+            "Could not find or load main class {}",
+            class_name.replace('/', ".")))),
+    };
+    let java_name = class.name.replace('/', ".");
+    match class.find_method("main", "([Ljava/lang/String;)V") {
+        Some((_, main)) if main.is_public() && main.is_static() => Ok(()),
+        Some((_, main)) if main.is_public() =>
+            Err(RunError::InvalidMainClass(format!(
+                "Main method is not static in class {}, please define \
+                 the main method as:\n   public static void \
+                 main(String[] args)", java_name))),
+        _ => Err(RunError::InvalidMainClass(format!(
+            "Main method not found in class {}, please define the main \
+             method as:\n   public static void main(String[] args)",
+            java_name))),
+    }
+}
+
+/*
+ * The kinds of array accessed by the <t>aload and <t>astore
+ * instructions, in the order in which they are encoded.
+ */
+const ARRAY_KINDS: [&str; 8] = [
+    "int", "long", "float", "double", "object", "byte/boolean", "char",
+    "short",
+];
+
+fn is_array_kind(kind: u8, component_type: &FieldType) -> bool {
+    match (kind, component_type) {
+        (0, &FieldType::Int)
+        | (1, &FieldType::Long)
+        | (2, &FieldType::Float)
+        | (3, &FieldType::Double)
+        | (5, &FieldType::Byte)
+        | (5, &FieldType::Boolean)
+        | (6, &FieldType::Char)
+        | (7, &FieldType::Short) => true,
+        (4, component_type) => component_type.is_reference(),
+        _ => false,
+    }
+}
+
+/*
+ * Evaluates the condition of an if<cond> or if_icmp<cond>
+ * instruction, given its offset from the first instruction of
+ * its family; the conditions are encoded in the order
+ * eq, ne, lt, ge, gt, le.
+ */
+fn compare_with(condition: u8, value1: i32, value2: i32) -> bool {
+    match condition {
+        0 => value1 == value2,
+        1 => value1 != value2,
+        2 => value1 < value2,
+        3 => value1 >= value2,
+        4 => value1 > value2,
+        5 => value1 <= value2,
+        _ => panic!("[ERROR] Invalid branch condition: {}", condition),
+    }
+}
+
+fn is_same_reference(value1: Operand, value2: Operand) -> bool {
+    match (value1, value2) {
+        (Operand::Null, Operand::Null) => true,
+        (Operand::Ref(object1), Operand::Ref(object2)) => object1 == object2,
+        (Operand::Ref(_), Operand::Null)
+        | (Operand::Null, Operand::Ref(_)) => false,
+        _ => panic!("[ERROR] Expected two references at top of stack \
+                     but didn't get them."),
+    }
+}
+
+/*
+ * Checks that a value being stored to a field is of the field's
+ * type; booleans are stored as ints, of which only the lowest
+ * bit is retained.
+ */
+fn coerce_to_field_type(field_type: &FieldType, value: Operand) -> Operand {
+    match (field_type, value) {
+        (&FieldType::Boolean, Operand::Int(value)) => Operand::Int(value & 1),
+        (&FieldType::Byte, Operand::Int(_))
+        | (&FieldType::Char, Operand::Int(_))
+        | (&FieldType::Short, Operand::Int(_))
+        | (&FieldType::Int, Operand::Int(_))
+        | (&FieldType::Long, Operand::Long(_))
+        | (&FieldType::Float, Operand::Float(_))
+        | (&FieldType::Double, Operand::Double(_))
+        | (&FieldType::Object(_), Operand::Ref(_))
+        | (&FieldType::Object(_), Operand::Null)
+        | (&FieldType::Array(_), Operand::Ref(_))
+        | (&FieldType::Array(_), Operand::Null) => value,
+        _ => panic!("[ERROR] Value stored to field of type {} \
+                     is of the wrong type.", field_type),
+    }
+}
+
+fn parse_method_descriptor(descriptor: &str) -> MethodDescriptor {
+    match MethodDescriptor::parse(descriptor) {
+        Ok(descriptor) => descriptor,
+        Err(error) => panic!("[ERROR] Malformed method descriptor: {}",
+                             error),
+    }
+}
+
+fn parse_field_descriptor(descriptor: &str) -> FieldType {
+    match FieldType::parse(descriptor) {
+        Ok(field_type) => field_type,
+        Err(error) => panic!("[ERROR] Malformed field descriptor: {}",
+                             error),
+    }
+}
+
+// The instructions a thread executes between safepoints.
+const SAFEPOINT_INTERVAL: u32 = 1024;
+
+impl Interpreter {
+    fn new(vm: Arc<Vm>, thread_id: u32) -> Interpreter {
+        let mut interpreter = Interpreter {
+            vm,
+            frames: Vec::new(),
+            thread_id,
+            stack_used: 0,
+            local_refs: Vec::new(),
+            safepoint_countdown: 0,
+            green: false,
+            suspendable: false,
+            suspended: false,
+            resume_deadline: None,
+        };
+        interpreter.safepoint_countdown = interpreter.quantum();
+        interpreter
+    }
+
+    // Picks up a green thread where it left off, for its next turn.
+    fn resume(vm: Arc<Vm>, thread_id: u32, thread: GreenThread)
+            -> Interpreter {
+        Interpreter {
+            vm,
+            frames: thread.frames,
+            thread_id,
+            stack_used: thread.stack_used,
+            local_refs: thread.local_refs,
+            safepoint_countdown: thread.safepoint_countdown,
+            green: true,
+            suspendable: false,
+            suspended: false,
+            resume_deadline: thread.resume_deadline,
+        }
+    }
+
+    // Sets a green thread's frames aside until its next turn.
+    fn suspend(self) -> GreenThread {
+        GreenThread {
+            frames: self.frames,
+            stack_used: self.stack_used,
+            local_refs: self.local_refs,
+            safepoint_countdown: self.safepoint_countdown,
+            resume_deadline: self.resume_deadline,
+        }
+    }
+
+    /*
+     * Invokes the given method and interprets bytecodes
+     * until it returns, yielding its return value, if any.
+     */
+    fn call_method(&mut self, class: &'static RuntimeClass,
+                   method: &'static Method<'static>, args: Vec<Operand>)
+            -> Result<Option<Operand>, JavaThrowable> {
+        if method.is_native() {
+            return self.call_native(class, method, args);
+        }
+        let base_depth = self.frames.len();
+        self.push_frame(class, method, args)?;
+        self.execute(base_depth)
+    }
+
+    /*
+     * Initializes a class (if it isn't already) on behalf of the
+     * host, running any static initializers to completion.
+     */
+    fn initialize_now(&mut self, class: &'static RuntimeClass)
+            -> Result<(), JavaThrowable> {
+        let base_depth = self.frames.len();
+        if self.initialize_class(class)? {
+            self.execute(base_depth)?;
+        }
+        Ok(())
+    }
+
+    fn push_frame(&mut self, class: &'static RuntimeClass,
+                  method: &'static Method<'static>,
+                  args: Vec<Operand>) -> Result<(), JavaThrowable> {
+        if self.verbose.trace {
+            println!("Interpreting {}.{}{}...",
+                     class.name, method.name, method.descriptor);
+        }
+        let monitor = if method.is_synchronized() {
+            Some(self.method_monitor(class, method, &args)?)
+        } else {
+            None
+        };
+        let frame = Frame::new(class, method, args);
+        if self.stack_used + frame.size() > self.stack_size {
+            let error_class = self.load_class("java/lang/StackOverflowError");
+            return Err(JavaThrowable(self.allocate_throwable(error_class)));
+        }
+        self.stack_used += frame.size();
+        self.frames.push(frame);
+        // The frame holds the arguments while the thread waits for the monitor.
+        if let Some(object) = monitor {
+            if let Err(exception) = self.monitor_enter(object) {
+                self.pop_frame();
+                return Err(exception);
+            }
+            self.frame().monitor = monitor;
+        }
+        Ok(())
+    }
+
+    /*
+     * Pops the current frame, exiting the monitor its method holds
+     * if it's synchronized, whether it returns or throws.
+     */
+    fn pop_frame(&mut self) -> Option<Frame> {
+        let frame = self.frames.pop();
+        if let Some(ref frame) = frame {
+            self.stack_used -= frame.size();
+            if let Some(object) = frame.monitor {
+                let thread = self.thread_id;
+                self.heap.get_mut(object).header.exit_monitor(thread);
+                self.wake_blocked_threads();
+            }
+        }
+        frame
+    }
+
+    /*
+     * The object a synchronized method locks: the receiver, or for
+     * static methods, the mirror of the class.
+     */
+    fn method_monitor(&mut self, class: &'static RuntimeClass,
+                      method: &Method, args: &[Operand])
+            -> Result<Handle, JavaThrowable> {
+        if method.is_static() {
+            // The arguments aren't on a frame yet, so they're rooted
+            // while the mirror is created.
+            let local_refs = self.local_refs.len();
+            for &arg in args.iter() {
+                if let Operand::Ref(arg) = arg {
+                    self.local_refs.push(arg);
+                }
+            }
+            let mirror = self.class_mirror(class);
+            self.local_refs.truncate(local_refs);
+            return mirror;
+        }
+        match args.first() {
+            Some(&Operand::Ref(object)) => Ok(object),
+            _ => panic!("[ERROR] Expected a receiver for synchronized \
+                         method {}.{}{}.", class.name, method.name,
+                        method.descriptor),
+        }
+    }
+
+    /*
+     * Enters the monitor of an object, blocking while another thread
+     * owns it.
+     */
+    fn monitor_enter(&mut self, object: Handle) -> Result<(), JavaThrowable> {
+        let thread = self.thread_id;
+        if self.heap.get_mut(object).header.try_enter_monitor(thread) {
+            return Ok(());
+        }
+        let local_refs = self.local_refs.len();
+        self.local_refs.push(object);
+        let entered = self.block_until(None, |interpreter| {
+            interpreter.heap.get_mut(object).header.try_enter_monitor(thread)
+        });
+        self.local_refs.truncate(local_refs);
+        entered.map(|_| ())
+    }
+
+    fn monitor_exit(&mut self, object: Handle) -> Result<(), JavaThrowable> {
+        let thread = self.thread_id;
+        if self.heap.get_mut(object).header.exit_monitor(thread) {
+            self.wake_blocked_threads();
+            Ok(())
+        } else {
+            Err(self.illegal_monitor_state())
+        }
+    }
+
+    fn illegal_monitor_state(&mut self) -> JavaThrowable {
+        self.throw_exception("java/lang/IllegalMonitorStateException",
+                             "current thread is not owner")
+    }
+
+    /*
+     * Safepoints come between every SAFEPOINT_INTERVAL instructions,
+     * or when threads are scheduled, a random number of them up to it.
+     * At each, the thread stops if the VM is stopping, and otherwise
+     * lets the threads waiting for the VM lock have it first, if any
+     * are. The collector runs only while one thread holds the lock, so
+     * the rest are stopped at safepoints or blocked.
+     *
+     * Green threads instead give up their turn at each: by suspending,
+     * if asked, and otherwise by giving the others turns beneath them.
+     */
+    fn safepoint(&mut self, suspend: bool) -> Result<(), JavaThrowable> {
+        self.safepoint_countdown = self.quantum();
+        self.check_stopping()?;
+        if suspend {
+            self.suspended = true;
+        } else if self.schedule.is_some() {
+            self.publish_roots();
+            self.switch_threads();
+        } else if self.vm.lock.is_contended() {
+            self.publish_roots();
+            self.vm.lock.yield_now();
+        }
+        Ok(())
+    }
+
+    // The instructions to execute before the next safepoint.
+    fn quantum(&mut self) -> u32 {
+        match self.schedule {
+            Some(ref mut schedule) => schedule.quantum(SAFEPOINT_INTERVAL),
+            None => SAFEPOINT_INTERVAL,
+        }
+    }
+
+    // Lets the other threads run before this one continues.
+    fn yield_now(&mut self) {
+        if self.schedule.is_none() {
+            self.publish_roots();
+            self.vm.lock.yield_now();
+        } else if self.suspendable {
+            // The turn ends once the current instruction completes.
+            self.safepoint_countdown = 1;
+        } else {
+            self.publish_roots();
+            self.switch_threads();
+        }
+    }
+
+    /*
+     * Gives the green threads that are waiting for their turns the
+     * turns the schedule picks for them, running each on top of this
+     * thread's frames, until this thread's own turn comes. A thread
+     * that's running beneath this one can't take a turn until this
+     * one returns to it.
+     */
+    fn switch_threads(&mut self) {
+        let current = self.thread_id;
+        loop {
+            let next = {
+                let runtime = &mut **self;
+                let green_threads = &runtime.green_threads;
+                match runtime.schedule {
+                    Some(ref mut schedule) => schedule.next_turn(
+                        |thread| thread == current
+                            || green_threads.contains_key(&thread)),
+                    None => return,
+                }
+            };
+            match next {
+                Some(thread) if thread == current => return,
+                Some(thread) => self.run_green_thread(thread),
+                None => panic!("[ERROR] Deadlock: every thread is blocked."),
+            }
+        }
+    }
+
+    /*
+     * Runs a green thread until it gives up its turn, setting its
+     * frames aside for the next, or dies.
+     */
+    fn run_green_thread(&mut self, id: u32) {
+        let thread = match self.green_threads.remove(&id) {
+            Some(thread) => thread,
+            None => panic!("[ERROR] Thread {} isn't waiting for a turn.", id),
+        };
+        let mut interpreter = Interpreter::resume(self.vm.clone(), id, thread);
+        let result = if interpreter.frames.is_empty() {
+            interpreter.call_run()
+        } else {
+            interpreter.execute(0).map(|_| ())
+        };
+        if interpreter.suspended {
+            interpreter.publish_roots();
+            let thread = interpreter.suspend();
+            self.green_threads.insert(id, thread);
+        } else {
+            interpreter.report_thread_failure(result);
+            interpreter.end_thread();
+        }
+    }
+
+    /*
+     * The VM stops once a thread calls System.exit or the Jvm is
+     * dropped, after which every thread throws a ThreadDeath, which
+     * unwinds all its frames, as soon as it reaches a safepoint or
+     * blocks. If another thread failed, this one fails in turn.
+     */
+    fn check_stopping(&mut self) -> Result<(), JavaThrowable> {
+        if let Some(ref message) = self.internal_error {
+            panic!("[ERROR] {}", message);
+        }
+        if self.is_stopping() {
+            let class = self.load_class("java/lang/ThreadDeath");
+            return Err(JavaThrowable(self.allocate_throwable(class)));
+        }
+        Ok(())
+    }
+
+    fn is_stopping(&self) -> bool {
+        self.exit_status.is_some() || self.shutting_down
+    }
+
+    /*
+     * Blocks the thread until the given condition holds, giving up the
+     * VM lock while it waits, and returns whether it holds, which it
+     * may not once the deadline (if any) has passed. The condition is
+     * checked again each time another thread signals an event.
+     *
+     * A green thread gives the others turns instead, or if it can,
+     * suspends the instruction it's executing, to try it again on its
+     * next turn, once an event is signalled or the deadline passes.
+     */
+    fn block_until<F>(&mut self, deadline: Option<Instant>, mut condition: F)
+            -> Result<bool, JavaThrowable>
+            where F: FnMut(&mut Interpreter) -> bool {
+        let deadline = self.resume_deadline.take().or(deadline);
+        loop {
+            self.check_stopping()?;
+            if condition(self) {
+                return Ok(true);
+            }
+            if deadline.is_some_and(|deadline| self.now() >= deadline) {
+                return Ok(false);
+            }
+            self.publish_roots();
+            let thread = self.thread_id;
+            match self.schedule {
+                Some(ref mut schedule) => schedule.block(thread, deadline),
+                None => {
+                    self.vm.lock.wait_for_event(deadline);
+                    continue;
+                },
+            }
+            if self.suspendable {
+                self.suspended = true;
+                self.resume_deadline = deadline;
+                return Err(self.suspension());
+            }
+            self.switch_threads();
+        }
+    }
+
+    /*
+     * What blocking returns as its error to suspend a green thread,
+     * which unwinds the instruction it blocked in, and which execute()
+     * then rewinds to execute again on the thread's next turn, rather
+     * than throwing it; the thread's java.lang.Thread stands in for it.
+     */
+    fn suspension(&mut self) -> JavaThrowable {
+        match self.thread_state().object {
+            Some(thread) => JavaThrowable(thread),
+            None => panic!("[ERROR] Thread {} can't be suspended.",
+                           self.thread_id),
+        }
+    }
+
+    // The current time, which is virtual if threads are scheduled.
+    fn now(&self) -> Instant {
+        match self.schedule {
+            Some(ref schedule) => schedule.now(),
+            None => Instant::now(),
+        }
+    }
+
+    /*
+     * The instant the given time from now, unless it's too far off to
+     * tell, by the VM's clock, which is virtual if threads are scheduled.
+     */
+    fn deadline_after(&self, duration: Duration) -> Option<Instant> {
+        self.now().checked_add(duration)
+    }
+
+    /*
+     * Signals an event that threads blocked in block_until may be
+     * waiting for, such as a monitor being exited.
+     */
+    fn wake_blocked_threads(&mut self) {
+        match self.schedule {
+            Some(ref mut schedule) => schedule.signal_event(),
+            None => self.vm.lock.signal_event(),
+        }
+    }
+
+    /*
+     * Records the objects this thread holds, for the collector to find
+     * while another thread has the VM lock.
+     */
+    fn publish_roots(&mut self) {
+        let roots = self.thread_roots();
+        let thread = self.thread_id;
+        if let Some(state) = self.threads.get_mut(&thread) {
+            state.roots = roots;
+        }
+    }
+
+    fn thread_state(&mut self) -> &mut ThreadState {
+        let thread = self.thread_id;
+        match self.threads.get_mut(&thread) {
+            Some(state) => state,
+            None => panic!("[ERROR] Thread {} isn't running.", thread),
+        }
+    }
+
+    // Clears the interrupt status of this thread, returning what it was.
+    fn take_interrupt(&mut self) -> bool {
+        mem::replace(&mut self.thread_state().interrupted, false)
+    }
+
+    fn interrupted_exception(&mut self) -> JavaThrowable {
+        let class = self.load_class("java/lang/InterruptedException");
+        JavaThrowable(self.allocate_throwable(class))
+    }
+
+    // The id of the thread a java.lang.Thread stands for.
+    fn thread_id_of(&self, thread: Handle) -> u32 {
+        match self.get_field(thread, "tid", "J") {
+            Operand::Long(id) => id as u32,
+            _ => panic!("[ERROR] Thread {:?} lacks an id.", thread),
+        }
+    }
+
+    /*
+     * Returns the java.lang.Thread standing for this thread, creating
+     * it for the main thread the first time it's asked for.
+     */
+    fn current_thread(&mut self) -> Result<Handle, JavaThrowable> {
+        if let Some(thread) = self.thread_state().object {
+            return Ok(thread);
+        }
+        let class = self.load_class("java/lang/Thread");
+        self.initialize_now(class)?;
+        let name = self.new_string("main")?;
+        let local_refs = self.local_refs.len();
+        self.local_refs.push(name);
+        let reserved = self.reserve(Heap::object_size(class));
+        self.local_refs.truncate(local_refs);
+        reserved?;
+        let thread = self.heap.allocate(class);
+        let id = self.thread_id;
+        self.set_field(thread, "tid", "J", Operand::Long(id as i64));
+        self.set_field(thread, "name", "Ljava/lang/String;",
+                       Operand::Ref(name));
+        self.set_field(thread, "priority", "I", Operand::Int(5));
+        self.thread_state().object = Some(thread);
+        Ok(thread)
+    }
+
+    // The name of this thread, as uncaught exceptions are reported.
+    fn thread_name(&self) -> String {
+        let object = self.threads.get(&self.thread_id)
+            .and_then(|state| state.object);
+        match object.map(|thread| self.get_field(thread, "name",
+                                                 "Ljava/lang/String;")) {
+            Some(Operand::Ref(name)) => self.string_value(name),
+            _ => "main".to_string(),
+        }
+    }
+
+    /*
+     * Starts running a java.lang.Thread on a thread of its own, which
+     * has its own interpreter, or if threads are scheduled, as a green
+     * thread, which calls run() on its first turn.
+     */
+    fn start_thread(&mut self, thread: Handle) -> Result<(), JavaThrowable> {
+        self.check_stopping()?;
+        let id = self.thread_id_of(thread);
+        let daemon = self.get_field(thread, "daemon", "Z");
+        let daemon = matches!(daemon, Operand::Int(1));
+        self.threads.insert(id, ThreadState::new(Some(thread), daemon));
+        let interpreter = Interpreter::new(self.vm.clone(), id);
+        if let Some(ref mut schedule) = self.schedule {
+            schedule.admit(id);
+            self.green_threads.insert(id, interpreter.suspend());
+            return Ok(());
+        }
+        match thread::Builder::new()
+                .spawn(move || interpreter.run_thread()) {
+            Ok(os_thread) => {
+                self.os_threads.push(os_thread);
+                Ok(())
+            },
+            Err(_) => {
+                self.threads.remove(&id);
+                Err(self.throw_exception("java/lang/OutOfMemoryError",
+                                         "unable to create new native \
+                                          thread"))
+            },
+        }
+    }
+
+    /*
+     * Runs the run() method of a thread that guest code started on a
+     * thread of its own, after which the thread dies. If the VM fails
+     * while the thread runs, the failure is recorded for the other
+     * threads to raise.
+     */
+    fn run_thread(mut self) {
+        self.vm.lock.acquire();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let result = self.call_run();
+            self.report_thread_failure(result);
+        }));
+        if let Err(payload) = result {
+            if self.internal_error.is_none() {
+                self.internal_error = Some(VmError::from_panic(payload).0);
+            }
+        }
+        self.end_thread();
+        self.vm.lock.release();
+    }
+
+    // Calls the run() method of the java.lang.Thread this thread runs.
+    fn call_run(&mut self) -> Result<(), JavaThrowable> {
+        let thread = match self.thread_state().object {
+            Some(thread) => thread,
+            None => panic!("[ERROR] Thread {} lacks a java.lang.Thread.",
+                           self.thread_id),
+        };
+        let class = self.heap.get(thread).header.class;
+        let (class, method) = match class.find_method("run", "()V") {
+            Some(found) => found,
+            None => panic!("[ERROR] {} lacks method run.", class.name),
+        };
+        let args = vec![Operand::Ref(thread)];
+        self.call_method(class, method, args).map(|_| ())
+    }
+
+    // Reports an exception that escaped a thread other than main.
+    fn report_thread_failure(&mut self, result: Result<(), JavaThrowable>) {
+        if let Err(exception) = result {
+            if !self.is_stopping() {
+                let description = self.report_uncaught(exception);
+                self.thread_failures.push(description);
+            }
+        }
+    }
+
+    fn end_thread(&mut self) {
+        let id = self.thread_id;
+        self.threads.remove(&id);
+        if let Some(ref mut schedule) = self.schedule {
+            schedule.forget(id);
+        }
+        self.wake_blocked_threads();
+    }
+
+    fn frame(&mut self) -> &mut Frame {
+        match self.frames.last_mut() {
+            Some(frame) => frame,
+            None => panic!("[ERROR] No frame is executing."),
+        }
+    }
+
+    /*
+     * Executes bytecodes until the frame stack unwinds to the given
+     * depth, either by returning normally or by throwing an exception
+     * that none of the frames above that depth handle.
+     */
+    fn execute(&mut self, base_depth: usize)
+            -> Result<Option<Operand>, JavaThrowable> {
+        /*
+         * Only the outermost execution of a green thread's frames can
+         * suspend it, returning with them left as they are; any other
+         * has Rust code waiting for it to return.
+         */
+        let outermost = self.green && base_depth == 0;
+        loop {
+            self.safepoint_countdown -= 1;
+            if self.safepoint_countdown == 0 {
+                if let Err(exception) = self.safepoint(outermost) {
+                    self.unwind(exception, base_depth)?;
+                }
+                if self.suspended {
+                    return Ok(None);
+                }
+            }
+            if self.heap.has_pending_actions()
+                    && !self.running_pending_actions {
+                if let Err(exception) = self.run_pending_actions() {
+                    self.unwind(exception, base_depth)?;
+                }
+            }
+            self.suspendable = outermost && self.frame().is_restartable();
+            let result = self.step(base_depth);
+            self.suspendable = false;
+            if self.suspended {
+                let frame = self.frame();
+                frame.pc = frame.opcode_pc;
+                return Ok(None);
+            }
+            self.resume_deadline = None;
+            match result {
+                Ok(Some(return_value)) => return Ok(return_value),
+                Ok(None) => {},
+                Err(exception) => self.unwind(exception, base_depth)?,
+            }
+        }
+    }
+
+    /*
+     * Enqueues the references the collector has cleared and runs the
+     * finalizers of the objects it has found unreachable, before the
+     * next instruction is executed. Exceptions they throw are
+     * ignored, except for the ThreadDeath of System.exit.
+     */
+    fn run_pending_actions(&mut self) -> Result<(), JavaThrowable> {
+        self.running_pending_actions = true;
+        let mut result = Ok(());
+        while let Some(action) = self.heap.next_pending_action() {
+            let name = match action {
+                PendingAction::Enqueue(_) => "enqueuePending",
+                PendingAction::Finalize(_) => "finalize",
+            };
+            let object = action.object();
+            let class = self.heap.get(object).header.class;
+            let (class, method) = match class.find_method(name, "()V") {
+                Some(found) => found,
+                None => panic!("[ERROR] {} lacks method {}.",
+                               class.name, name),
+            };
+            let args = vec![Operand::Ref(object)];
+            if let Err(exception) = self.call_method(class, method, args) {
+                if self.is_stopping() {
+                    result = Err(exception);
+                    break;
+                }
+            }
+        }
+        self.running_pending_actions = false;
+        result
+    }
+
+    /*
+     * Executes the instruction at the pc of the current frame.
+     * Once a return unwinds the frame stack to the given depth, the
+     * return value is handed back, and otherwise None is returned.
+     */
+    fn step(&mut self, base_depth: usize)
+            -> Result<Option<Option<Operand>>, JavaThrowable> {
+        let frame = self.frame();
+        let opcode_pc = frame.pc;
+        frame.opcode_pc = opcode_pc;
+        frame.started = true;
+        let opcode = frame.read_u8();
+        match opcode {
+            0x01 => frame.push(Operand::Null),
+            // iconst_<i>, whose values range from -1 to 5.
+            0x02 ..= 0x08 =>
+                frame.push(Operand::Int(opcode as i32 - 0x03)),
+            0x09 | 0x0a => frame.push(Operand::Long((opcode - 0x09) as i64)),
+            0x0b ..= 0x0d =>
+                frame.push(Operand::Float((opcode - 0x0b) as f32)),
+            0x0e | 0x0f =>
+                frame.push(Operand::Double((opcode - 0x0e) as f64)),
+            0x10 => {
+                let value = frame.read_u8() as i8;
+                frame.push(Operand::Int(value as i32));
+            },
+            0x11 => {
+                let value = frame.read_u16() as i16;
+                frame.push(Operand::Int(value as i32));
+            },
+            0x12 => {
+                let const_idx = frame.read_u8() as u16;
+                let constant = self.resolve_constant(const_idx)?;
+                self.frame().push(constant);
+            },
+            0x13 | 0x14 => {
+                let const_idx = frame.read_u16();
+                let constant = self.resolve_constant(const_idx)?;
+                self.frame().push(constant);
+            },
+            0x15 ..= 0x19 => {
+                let idx = frame.read_u8() as usize;
+                frame.typed_load(
+                    OperandType::from_encoding(opcode - 0x15), idx);
+            },
+            // <t>load_<n>, grouped by type, with n ranging from 0 to 3.
+            0x1a ..= 0x2d => {
+                let offset = opcode - 0x1a;
+                frame.typed_load(OperandType::from_encoding(offset / 4),
+                                 (offset % 4) as usize);
+            },
+            // <t>aload, in the order given by ARRAY_KINDS.
+            0x2e ..= 0x35 => self.array_load(opcode - 0x2e)?,
+            0x36 ..= 0x3a => {
+                let idx = frame.read_u8() as usize;
+                frame.typed_store(
+                    OperandType::from_encoding(opcode - 0x36), idx);
+            },
+            // <t>store_<n>, grouped by type, with n ranging from 0 to 3.
+            0x3b ..= 0x4e => {
+                let offset = opcode - 0x3b;
+                frame.typed_store(OperandType::from_encoding(offset / 4),
+                                  (offset % 4) as usize);
+            },
+            // <t>astore, in the order given by ARRAY_KINDS.
+            0x4f ..= 0x56 => self.array_store(opcode - 0x4f)?,
+            0x57 => {
+                frame.pop_words(1);
+            },
+            0x58 => {
+                frame.pop_words(2);
+            },
+            0x59 => frame.dup_words(1, 0),
+            0x5a => frame.dup_words(1, 1),
+            0x5b => frame.dup_words(1, 2),
+            0x5c => frame.dup_words(2, 0),
+            0x5d => frame.dup_words(2, 1),
+            0x5e => frame.dup_words(2, 2),
+            0x5f => {
+                let value1 = frame.pop_words(1);
+                let value2 = frame.pop_words(1);
+                frame.operand_stack.extend(value1);
+                frame.operand_stack.extend(value2);
+            },
+            0x60 => frame.int_binary_op(|a, b| a.wrapping_add(b)),
+            0x61 => frame.long_binary_op(|a, b| a.wrapping_add(b)),
+            0x62 => frame.float_binary_op(|a, b| a + b),
+            0x63 => frame.double_binary_op(|a, b| a + b),
+            0x64 => frame.int_binary_op(|a, b| a.wrapping_sub(b)),
+            0x65 => frame.long_binary_op(|a, b| a.wrapping_sub(b)),
+            0x66 => frame.float_binary_op(|a, b| a - b),
+            0x67 => frame.double_binary_op(|a, b| a - b),
+            0x68 => frame.int_binary_op(|a, b| a.wrapping_mul(b)),
+            0x69 => frame.long_binary_op(|a, b| a.wrapping_mul(b)),
+            0x6a => frame.float_binary_op(|a, b| a * b),
+            0x6b => frame.double_binary_op(|a, b| a * b),
+            0x6c | 0x70 => {
+                let value2 = frame.pop_int();
+                let value1 = frame.pop_int();
+                if value2 == 0 {
+                    return Err(self.throw_exception(
+                        "java/lang/ArithmeticException", "/ by zero"));
+                }
+                /*
+                 * Wrapping semantics cover the one overflow case,
+                 * Integer.MIN_VALUE / -1, whose result is
+                 * Integer.MIN_VALUE with a remainder of zero.
+                 */
+                let result = if opcode == 0x6c {
+                    value1.wrapping_div(value2)
+                } else {
+                    value1.wrapping_rem(value2)
+                };
+                self.frame().push(Operand::Int(result));
+            },
+            0x6d | 0x71 => {
+                let value2 = frame.pop_long();
+                let value1 = frame.pop_long();
+                if value2 == 0 {
+                    return Err(self.throw_exception(
+                        "java/lang/ArithmeticException", "/ by zero"));
+                }
+                let result = if opcode == 0x6d {
+                    value1.wrapping_div(value2)
+                } else {
+                    value1.wrapping_rem(value2)
+                };
+                self.frame().push(Operand::Long(result));
+            },
+            /*
+             * Floating-point division by zero yields an infinity
+             * or NaN rather than an exception, and the remainder
+             * truncates towards zero, as Rust's does.
+             */
+            0x6e => frame.float_binary_op(|a, b| a / b),
+            0x6f => frame.double_binary_op(|a, b| a / b),
+            0x72 => frame.float_binary_op(|a, b| a % b),
+            0x73 => frame.double_binary_op(|a, b| a % b),
+            0x74 => frame.int_unary_op(|a| a.wrapping_neg()),
+            0x75 => frame.long_unary_op(|a| a.wrapping_neg()),
+            0x76 => {
+                let value = frame.pop_float();
+                frame.push(Operand::Float(-value));
+            },
+            0x77 => {
+                let value = frame.pop_double();
+                frame.push(Operand::Double(-value));
+            },
+            // Only the low five bits of the shift distance are used.
+            0x78 => frame.int_binary_op(|a, b| a << (b & 0x1f)),
+            0x79 => frame.long_shift_op(|a, b| a << b),
+            0x7a => frame.int_binary_op(|a, b| a >> (b & 0x1f)),
+            0x7b => frame.long_shift_op(|a, b| a >> b),
+            0x7c => frame.int_binary_op(
+                |a, b| ((a as u32) >> (b & 0x1f)) as i32),
+            0x7d => frame.long_shift_op(|a, b| ((a as u64) >> b) as i64),
+            0x7e => frame.int_binary_op(|a, b| a & b),
+            0x7f => frame.long_binary_op(|a, b| a & b),
+            0x80 => frame.int_binary_op(|a, b| a | b),
+            0x81 => frame.long_binary_op(|a, b| a | b),
+            0x82 => frame.int_binary_op(|a, b| a ^ b),
+            0x83 => frame.long_binary_op(|a, b| a ^ b),
+            0x84 => {
+                let idx = frame.read_u8() as usize;
+                let increment = frame.read_u8() as i8;
+                frame.iinc(idx, increment as i32);
+            },
+            /*
+             * Rust's numeric casts match the JVM's conversions:
+             * narrowing integers truncates, and converting a
+             * floating-point value to an integer rounds towards
+             * zero, saturates at the target type's bounds, and maps
+             * NaN to zero.
+             */
+            0x85 => {
+                let value = frame.pop_int();
+                frame.push(Operand::Long(value as i64));
+            },
+            0x86 => {
+                let value = frame.pop_int();
+                frame.push(Operand::Float(value as f32));
+            },
+            0x87 => {
+                let value = frame.pop_int();
+                frame.push(Operand::Double(value as f64));
+            },
+            0x88 => {
+                let value = frame.pop_long();
+                frame.push(Operand::Int(value as i32));
+            },
+            0x89 => {
+                let value = frame.pop_long();
+                frame.push(Operand::Float(value as f32));
+            },
+            0x8a => {
+                let value = frame.pop_long();
+                frame.push(Operand::Double(value as f64));
+            },
+            0x8b => {
+                let value = frame.pop_float();
+                frame.push(Operand::Int(value as i32));
+            },
+            0x8c => {
+                let value = frame.pop_float();
+                frame.push(Operand::Long(value as i64));
+            },
+            0x8d => {
+                let value = frame.pop_float();
+                frame.push(Operand::Double(value as f64));
+            },
+            0x8e => {
+                let value = frame.pop_double();
+                frame.push(Operand::Int(value as i32));
+            },
+            0x8f => {
+                let value = frame.pop_double();
+                frame.push(Operand::Long(value as i64));
+            },
+            0x90 => {
+                let value = frame.pop_double();
+                frame.push(Operand::Float(value as f32));
+            },
+            0x91 => frame.int_unary_op(|a| a as i8 as i32),
+            0x92 => frame.int_unary_op(|a| a as u16 as i32),
+            0x93 => frame.int_unary_op(|a| a as i16 as i32),
+            0x94 => {
+                let value2 = frame.pop_long();
+                let value1 = frame.pop_long();
+                frame.compare(value1, value2, 0);
+            },
+            // The l and g variants differ only in their NaN result.
+            0x95 | 0x96 => {
+                let value2 = frame.pop_float();
+                let value1 = frame.pop_float();
+                frame.compare(value1, value2,
+                              if opcode == 0x95 { -1 } else { 1 });
+            },
+            0x97 | 0x98 => {
+                let value2 = frame.pop_double();
+                let value1 = frame.pop_double();
+                frame.compare(value1, value2,
+                              if opcode == 0x97 { -1 } else { 1 });
+            },
+            // if<cond>
+            0x99 ..= 0x9e => {
+                let value = frame.pop_int();
+                let condition = compare_with(opcode - 0x99, value, 0);
+                frame.branch_if(opcode_pc, condition);
+            },
+            // if_icmp<cond>
+            0x9f ..= 0xa4 => {
+                let value2 = frame.pop_int();
+                let value1 = frame.pop_int();
+                let condition =
+                    compare_with(opcode - 0x9f, value1, value2);
+                frame.branch_if(opcode_pc, condition);
+            },
+            0xa5 | 0xa6 => {
+                let value2 = frame.pop();
+                let value1 = frame.pop();
+                let equal = is_same_reference(value1, value2);
+                frame.branch_if(opcode_pc,
+                                if opcode == 0xa5 { equal } else { !equal });
+            },
+            0xa7 => frame.branch_if(opcode_pc, true),
+            0xa8 => {
+                let offset = frame.read_u16() as i16;
+                frame.push(Operand::ReturnAddress(frame.pc));
+                frame.branch(opcode_pc, offset as i32);
+            },
+            0xa9 => {
+                let idx = frame.read_u8() as usize;
+                frame.ret(idx);
+            },
+            0xaa => frame.tableswitch(opcode_pc),
+            0xab => frame.lookupswitch(opcode_pc),
+            0xac ..= 0xb0 => {
+                let return_value = frame.pop();
+                return Ok(self.return_from_frame(Some(return_value),
+                                                 base_depth));
+            },
+            0xb1 => return Ok(self.return_from_frame(None, base_depth)),
+            0xb2 => {
+                let field_const_idx = frame.read_u16();
+                self.getstatic(opcode_pc, field_const_idx)?;
+            },
+            0xb3 => {
+                let field_const_idx = frame.read_u16();
+                self.putstatic(opcode_pc, field_const_idx)?;
+            },
+            0xb4 => {
+                let field_const_idx = frame.read_u16();
+                self.getfield(field_const_idx)?;
+            },
+            0xb5 => {
+                let field_const_idx = frame.read_u16();
+                self.putfield(field_const_idx)?;
+            },
+            0xb6 => {
+                let method_const_idx = frame.read_u16();
+                self.invoke(Invocation::Virtual, opcode_pc,
+                            method_const_idx)?;
+            },
+            0xb7 => {
+                let method_const_idx = frame.read_u16();
+                self.invoke(Invocation::Special, opcode_pc,
+                            method_const_idx)?;
+            },
+            0xb8 => {
+                let method_const_idx = frame.read_u16();
+                self.invoke(Invocation::Static, opcode_pc,
+                            method_const_idx)?;
+            },
+            0xb9 => {
+                let method_const_idx = frame.read_u16();
+                // The historical count operand is redundant with
+                // the descriptor, and the byte after it is always zero.
+                frame.read_u8();
+                frame.read_u8();
+                self.invoke(Invocation::Interface, opcode_pc,
+                            method_const_idx)?;
+            },
+            0xbb => {
+                let class_const_idx = frame.read_u16();
+                let class_name =
+                    frame.definition.class_name(class_const_idx);
+                let class = self.resolve_class(class_name)?;
+                if class.is_interface() || class.is_abstract() {
+                    return Err(self.throw_exception(
+                        "java/lang/InstantiationError",
+                        &class.name.replace('/', ".")));
+                }
+                if !self.ensure_initialized(class, opcode_pc)? {
+                    return Ok(None);
+                }
+                self.reserve(Heap::object_size(class))?;
+                let throwable_class = self.load_class("java/lang/Throwable");
+                let object = if class.is_subclass_of(throwable_class) {
+                    self.allocate_throwable(class)
+                } else {
+                    self.heap.allocate(class)
+                };
+                self.frame().push(Operand::Ref(object));
+            },
+            0xbc => {
+                let array_class_name = match frame.read_u8() {
+                    4 => "[Z",
+                    5 => "[C",
+                    6 => "[F",
+                    7 => "[D",
+                    8 => "[B",
+                    9 => "[S",
+                    10 => "[I",
+                    11 => "[J",
+                    atype => panic!("[ERROR] Invalid newarray type: {}",
+                                    atype),
+                };
+                self.new_array(array_class_name)?;
+            },
+            0xbd => {
+                let class_const_idx = frame.read_u16();
+                let component_name =
+                    frame.definition.class_name(class_const_idx);
+                let array_class_name = if component_name.starts_with('[') {
+                    format!("[{}", component_name)
+                } else {
+                    format!("[L{};", component_name)
+                };
+                self.new_array(&array_class_name)?;
+            },
+            0xbe => {
+                let length = match frame.pop() {
+                    Operand::Ref(array) => self.heap.get(array).length(),
+                    Operand::Null => return Err(self.null_pointer_exception()),
+                    _ => panic!("[ERROR] arraylength expected arrayref \
+                                 at top of stack but didn't get one."),
+                };
+                self.frame().push(Operand::Int(length as i32));
+            },
+            0xc5 => {
+                let class_const_idx = frame.read_u16();
+                let dimensions = frame.read_u8() as usize;
+                let class_name =
+                    frame.definition.class_name(class_const_idx);
+                let mut counts: Vec<i32> = (0 .. dimensions)
+                    .map(|_| frame.pop_int()).collect();
+                counts.reverse();
+                if let Some(count) = counts.iter().find(|&&c| c < 0) {
+                    return Err(self.throw_exception(
+                        "java/lang/NegativeArraySizeException",
+                        &count.to_string()));
+                }
+                let class = self.resolve_class(class_name)?;
+                self.reserve(Heap::multi_array_size(class, &counts))?;
+                let array = self.heap.allocate_multi_array(class, &counts);
+                self.frame().push(Operand::Ref(array));
+            },
+            0xbf => {
+                let exception = match frame.pop() {
+                    Operand::Ref(exception) => exception,
+                    Operand::Null => return Err(self.null_pointer_exception()),
+                    _ => panic!("[ERROR] athrow expected objectref at top \
+                                 of stack but didn't get one."),
+                };
+                return Err(JavaThrowable(exception));
+            },
+            0xc0 => {
+                let class_const_idx = frame.read_u16();
+                let class_name = frame.definition.class_name(class_const_idx);
+                let object = match frame.pop() {
+                    // Null may be cast to any reference type.
+                    Operand::Null => {
+                        frame.push(Operand::Null);
+                        return Ok(None);
+                    },
+                    Operand::Ref(object) => object,
+                    _ => panic!("[ERROR] checkcast expected objectref at \
+                                 top of stack but didn't get one."),
+                };
+                let target = self.resolve_class(class_name)?;
+                let class = self.heap.get(object).header.class;
+                if !self.is_subtype(class, target) {
+                    return Err(self.throw_exception(
+                        "java/lang/ClassCastException",
+                        &format!("class {} cannot be cast to class {}",
+                                 class.name.replace('/', "."),
+                                 target.name.replace('/', "."))));
+                }
+                self.frame().push(Operand::Ref(object));
+            },
+            0xc1 => {
+                let class_const_idx = frame.read_u16();
+                let class_name = frame.definition.class_name(class_const_idx);
+                let result = match frame.pop() {
+                    Operand::Null => false,
+                    Operand::Ref(object) => {
+                        let target = self.resolve_class(class_name)?;
+                        let class = self.heap.get(object).header.class;
+                        self.is_subtype(class, target)
+                    },
+                    _ => panic!("[ERROR] instanceof expected objectref at \
+                                 top of stack but didn't get one."),
+                };
+                self.frame().push(Operand::Int(result as i32));
+            },
+            0xc2 | 0xc3 => {
+                let object = match frame.pop() {
+                    Operand::Ref(object) => object,
+                    Operand::Null => return Err(self.null_pointer_exception()),
+                    _ => panic!("[ERROR] Expected objectref at top of stack \
+                                 for {} but didn't get one.",
+                                if opcode == 0xc2 { "monitorenter" }
+                                else { "monitorexit" }),
+                };
+                if opcode == 0xc2 {
+                    let entered = self.monitor_enter(object);
+                    if self.suspended {
+                        self.frame().push(Operand::Ref(object));
+                    }
+                    entered?;
+                } else {
+                    self.monitor_exit(object)?;
+                }
+            },
+            0xc6 | 0xc7 => {
+                let is_null = match frame.pop() {
+                    Operand::Null => true,
+                    Operand::Ref(_) => false,
+                    _ => panic!("[ERROR] Expected reference at top \
+                                 of stack but didn't get one."),
+                };
+                frame.branch_if(opcode_pc,
+                                if opcode == 0xc6 { is_null } else { !is_null });
+            },
+            0xc8 => {
+                let offset = frame.read_i32();
+                frame.branch(opcode_pc, offset);
+            },
+            0xc9 => {
+                let offset = frame.read_i32();
+                frame.push(Operand::ReturnAddress(frame.pc));
+                frame.branch(opcode_pc, offset);
+            },
+            0xc4 => {
+                /*
+                 * wide extends the local variable index of the
+                 * instruction it modifies to two bytes (and for iinc,
+                 * its increment as well).
+                 */
+                let modified_opcode = frame.read_u8();
+                let idx = frame.read_u16() as usize;
+                match modified_opcode {
+                    0x15 ..= 0x19 => frame.typed_load(
+                        OperandType::from_encoding(modified_opcode - 0x15),
+                        idx),
+                    0x36 ..= 0x3a => frame.typed_store(
+                        OperandType::from_encoding(modified_opcode - 0x36),
+                        idx),
+                    0x84 => {
+                        let increment = frame.read_u16() as i16;
+                        frame.iinc(idx, increment as i32);
+                    },
+                    0xa9 => frame.ret(idx),
+                    unsup_code => panic!("[ERROR] Encountered \
+                            unsupported bytecode after wide: {:x}",
+                            unsup_code),
+                }
+            },
+            unsup_code => panic!("[ERROR] Encountered unsupported \
+                                  bytecode: {:x}", unsup_code),
+        }
+        Ok(None)
+    }
+
+    /*
+     * Pops the current frame, pushing the return value (if any)
+     * onto the invoker's operand stack. Once the frame stack has
+     * unwound to the given depth, the return value is handed back
+     * to the caller of execute() instead.
+     */
+    fn return_from_frame(&mut self, return_value: Option<Operand>,
+                         base_depth: usize) -> Option<Option<Operand>> {
+        if let Some(frame) = self.pop_frame() {
+            if frame.method.name == "<clinit>" {
+                self.loader.statics_mut(frame.class).init_state =
+                    InitState::Initialized;
+                self.wake_blocked_threads();
+            }
+        }
+        if self.frames.len() <= base_depth {
+            return Some(return_value);
+        }
+        if let Some(value) = return_value {
+            self.frame().push(value);
+        }
+        None
+    }
+
+    /*
+     * Creates an exception of the given class on behalf of the VM,
+     * for the current instruction to throw.
+     */
+    fn throw_exception(&mut self, class_name: &str, message: &str)
+            -> JavaThrowable {
+        let class = self.load_class(class_name);
+        let exception = self.allocate_throwable(class);
+        let message = self.allocate_string(message);
+        let idx = self.throwable_field_idx("detailMessage",
+                                           "Ljava/lang/String;");
+        self.heap.get_mut(exception).fields_mut()[idx] = Operand::Ref(message);
+        JavaThrowable(exception)
+    }
+
+    /*
+     * Determines whether instances of a class may be treated as
+     * instances of another, consulting the cache of earlier checks.
+     */
+    fn is_subtype(&mut self, class: &'static RuntimeClass,
+                  target: &'static RuntimeClass) -> bool {
+        if self.subtype_cache.contains(&(class.name, target.name)) {
+            return true;
+        }
+        let is_subtype = class.is_assignable_to(target);
+        if is_subtype {
+            self.subtype_cache.insert((class.name, target.name));
+        }
+        is_subtype
+    }
+
+    /*
+     * Allocates an instance of a Throwable class, capturing the
+     * stack trace at the point of its creation.
+     */
+    fn allocate_throwable(&mut self, class: &'static RuntimeClass)
+            -> Handle {
+        let throwable = self.heap.allocate(class);
+        let stack_trace = self.capture_stack_trace();
+        let idx = self.throwable_field_idx(
+            "stackTrace", "[Ljava/lang/StackTraceElement;");
+        self.heap.get_mut(throwable).fields_mut()[idx] =
+            Operand::Ref(stack_trace);
+        throwable
+    }
+
+    /*
+     * Creates an array of StackTraceElements describing the frames
+     * that are executing, innermost first, as fillInStackTrace does.
+     * Each frame is placed at the line of the instruction it is
+     * executing; for callers, that is the invocation of the frame
+     * above. As in HotSpot, -1 stands for an unknown line.
+     */
+    fn capture_stack_trace(&mut self) -> Handle {
+        let frames: Vec<(&str, &str, Option<&str>, i32)> = self.frames.iter()
+            .rev().filter(|frame| frame.started).map(|frame| (
+                frame.class.name,
+                frame.method.name,
+                frame.definition.source_file(),
+                frame.code.line_number(frame.opcode_pc)
+                    .map_or(-1, |line| line as i32),
+            )).collect();
+
+        let element_class = self.load_class("java/lang/StackTraceElement");
+        let field_idx = |name| stack_trace_element_field_idx(element_class,
+                                                             name);
+        let mut elements = Vec::with_capacity(frames.len());
+        for (class_name, method_name, file_name, line_number) in frames {
+            let declaring_class =
+                self.allocate_string(&class_name.replace('/', "."));
+            let method_name = self.allocate_string(method_name);
+            let file_name = match file_name {
+                Some(file_name) =>
+                    Operand::Ref(self.allocate_string(file_name)),
+                None => Operand::Null,
+            };
+            let element = self.heap.allocate(element_class);
+            let fields = self.heap.get_mut(element).fields_mut();
+            fields[field_idx("declaringClass")] = Operand::Ref(declaring_class);
+            fields[field_idx("methodName")] = Operand::Ref(method_name);
+            fields[field_idx("fileName")] = file_name;
+            fields[field_idx("lineNumber")] = Operand::Int(line_number);
+            elements.push(Operand::Ref(element));
+        }
+
+        let array_class = self.load_class("[Ljava/lang/StackTraceElement;");
+        let array = self.heap.allocate_array(array_class, elements.len());
+        self.heap.get_mut(array).elements_mut().copy_from_slice(&elements);
+        array
+    }
+
+    /*
+     * Formats the stack trace of a throwable as StackTraceElement's
+     * toString does, e.g. "pkg.Class.method(Class.java:42)".
+     */
+    fn stack_trace(&mut self, throwable: Handle) -> Vec<String> {
+        let idx = self.throwable_field_idx(
+            "stackTrace", "[Ljava/lang/StackTraceElement;");
+        let array = match self.heap.get(throwable).fields()[idx] {
+            Operand::Ref(array) => array,
+            _ => return Vec::new(),
+        };
+        let element_class = self.load_class("java/lang/StackTraceElement");
+        let field_idx = |name| stack_trace_element_field_idx(element_class,
+                                                             name);
+        let string_field = |interpreter: &Interpreter, fields: &[Operand],
+                            name| match fields[field_idx(name)] {
+            Operand::Ref(string) => Some(interpreter.string_value(string)),
+            _ => None,
+        };
+        let elements = self.heap.get(array).elements().to_vec();
+        elements.iter().filter_map(|element| match *element {
+            Operand::Ref(element) => Some(element),
+            _ => None,
+        }).map(|element| {
+            let fields = self.heap.get(element).fields();
+            let class_name = string_field(self, fields, "declaringClass");
+            let method_name = string_field(self, fields, "methodName");
+            let file_name = string_field(self, fields, "fileName");
+            let location = match (file_name, fields[field_idx("lineNumber")]) {
+                (_, Operand::Int(-2)) => "Native Method".to_string(),
+                (Some(file_name), Operand::Int(line)) if line >= 0 =>
+                    format!("{}:{}", file_name, line),
+                (Some(file_name), _) => file_name,
+                (None, _) => "Unknown Source".to_string(),
+            };
+            format!("{}.{}({})", class_name.unwrap_or_default(),
+                    method_name.unwrap_or_default(), location)
+        }).collect()
+    }
+
+    /*
+     * Creates a NullPointerException for the current instruction to
+     * throw, describing both what the instruction was attempting and,
+     * where it can be determined, the expression that was null.
+     */
+    fn null_pointer_exception(&mut self) -> JavaThrowable {
+        let message = {
+            let frame = self.frame();
+            npe::describe(frame.definition, frame.method, frame.code,
+                          frame.opcode_pc)
+        };
+        self.throw_exception("java/lang/NullPointerException", &message)
+    }
+
+    /*
+     * Returns the index of a field declared by Throwable, which is
+     * the same within the layout of every exception class.
+     */
+    fn throwable_field_idx(&mut self, name: &str, descriptor: &str)
+            -> usize {
+        let throwable_class = self.load_class("java/lang/Throwable");
+        match throwable_class.instance_field_idx(name, descriptor) {
+            Some(idx) => idx,
+            None => panic!("[ERROR] Throwable lacks field {}:{}.",
+                           name, descriptor),
+        }
+    }
+
+    /*
+     * Dispatches an exception to the innermost handler for it,
+     * popping frames that have no such handler. The exception is
+     * handed back if it propagates beyond the given depth.
+     */
+    fn unwind(&mut self, exception: JavaThrowable, base_depth: usize)
+            -> Result<(), JavaThrowable> {
+        let mut exception = exception;
+        if self.is_stopping() {
+            while self.frames.len() > base_depth {
+                self.pop_frame();
+            }
+            return Err(exception);
+        }
+        while self.frames.len() > base_depth {
+            // A static initializer that hasn't started fails along with
+            // that of its superclass, without running its handlers, as
+            // step 7 of section 5.5 of the JVM specification requires.
+            let handler_pc = if self.frame().started {
+                self.find_handler(exception)
+            } else {
+                None
+            };
+            if let Some(handler_pc) = handler_pc {
+                let frame = self.frame();
+                frame.operand_stack.clear();
+                frame.push(Operand::Ref(exception.0));
+                frame.pc = handler_pc;
+                return Ok(());
+            }
+            if let Some(frame) = self.pop_frame() {
+                if frame.method.name == "<clinit>" {
+                    exception = self.fail_initialization(frame.class,
+                                                         exception);
+                }
+            }
+        }
+        Err(exception)
+    }
+
+    /*
+     * Searches the exception table of the current frame, in order,
+     * for a handler whose range covers the current instruction and
+     * which catches the given exception's class or a superclass of it.
+     */
+    fn find_handler(&mut self, exception: JavaThrowable) -> Option<usize> {
+        let exception_class = self.heap.get(exception.0).header.class;
+        let frame = self.frame();
+        let (pc, code, definition) =
+            (frame.opcode_pc, frame.code, frame.definition);
+        for entry in code.exception_table.iter().filter(|e| e.covers(pc)) {
+            if entry.catch_type == 0 {
+                return Some(entry.handle_pc as usize);
+            }
+            let catch_class =
+                self.load_class(definition.class_name(entry.catch_type));
+            if exception_class.is_subclass_of(catch_class) {
+                return Some(entry.handle_pc as usize);
+            }
+        }
+        None
+    }
+
+    /*
+     * Marks a class whose static initializer threw an exception as
+     * erroneous; exceptions other than errors are wrapped in an
+     * ExceptionInInitializerError, as section 5.5 of the JVM
+     * specification requires.
+     */
+    fn fail_initialization(&mut self, class: &'static RuntimeClass,
+                           exception: JavaThrowable) -> JavaThrowable {
+        self.loader.statics_mut(class).init_state = InitState::Erroneous;
+        self.wake_blocked_threads();
+        let error_class = self.load_class("java/lang/Error");
+        if self.heap.get(exception.0).header.class.is_subclass_of(error_class) {
+            return exception;
+        }
+        let wrapper_class =
+            self.load_class("java/lang/ExceptionInInitializerError");
+        let wrapper = self.allocate_throwable(wrapper_class);
+        let idx = self.throwable_field_idx("cause", "Ljava/lang/Throwable;");
+        self.heap.get_mut(wrapper).fields_mut()[idx] =
+            Operand::Ref(exception.0);
+        JavaThrowable(wrapper)
+    }
+
+    /*
+     * Reports an exception that no frame of the thread caught, as the
+     * default uncaught exception handler does, and returns the
+     * description of the exception that was printed.
+     */
+    fn report_uncaught(&mut self, exception: JavaThrowable) -> String {
+        let description = self.describe_throwable(exception.0);
+        let mut report = vec![format!("Exception in thread \"{}\" {}",
+                                      self.thread_name(), description)];
+        let mut trace = self.stack_trace(exception.0);
+        for line in trace.iter() {
+            report.push(format!("\tat {}", line));
+        }
+
+        /*
+         * As in printStackTrace, frames that a cause has in common
+         * with the throwable it caused are elided, as they'd only
+         * repeat the trace printed above.
+         */
+        let cause_idx = self.throwable_field_idx("cause",
+                                                 "Ljava/lang/Throwable;");
+        let mut seen = vec![exception.0];
+        let mut current = exception.0;
+        while let Operand::Ref(cause) = self.heap.get(current).fields()[cause_idx] {
+            if seen.contains(&cause) {
+                break;
+            }
+            let cause_trace = self.stack_trace(cause);
+            let in_common = cause_trace.iter().rev().zip(trace.iter().rev())
+                .take_while(|&(a, b)| a == b).count();
+            report.push(format!("Caused by: {}",
+                                self.describe_throwable(cause)));
+            for line in cause_trace[.. cause_trace.len() - in_common].iter() {
+                report.push(format!("\tat {}", line));
+            }
+            if in_common > 0 {
+                report.push(format!("\t... {} more", in_common));
+            }
+            seen.push(cause);
+            current = cause;
+            trace = cause_trace;
+        }
+        for line in report {
+            let _ = writeln!(self.stderr, "{}", line);
+        }
+        description
+    }
+
+    /*
+     * Describes a throwable as Throwable's toString does: by its
+     * class name, followed by its message if it has one.
+     */
+    fn describe_throwable(&mut self, throwable: Handle) -> String {
+        let class = self.heap.get(throwable).header.class;
+        let idx = self.throwable_field_idx("detailMessage",
+                                           "Ljava/lang/String;");
+        match self.heap.get(throwable).fields()[idx] {
+            Operand::Ref(message) => format!("{}: {}",
+                                             class.name.replace('/', "."),
+                                             self.string_value(message)),
+            _ => class.name.replace('/', "."),
+        }
+    }
+
+    /*
+     * Creates a java.lang.String holding the given value.
+     */
+    fn new_string(&mut self, value: &str) -> Result<Handle, JavaThrowable> {
+        let chars: Vec<u16> = value.encode_utf16().collect();
+        self.new_string_from_chars(&chars)
+    }
+
+    fn new_string_array<S: AsRef<str>>(&mut self, values: &[S])
+            -> Result<Handle, JavaThrowable> {
+        let class = self.load_class("[Ljava/lang/String;");
+        self.reserve(Heap::array_size(class, values.len()))?;
+        let array = self.heap.allocate_array(class, values.len());
+        let local_refs = self.local_refs.len();
+        self.local_refs.push(array);
+        for (idx, value) in values.iter().enumerate() {
+            let string = match self.new_string(value.as_ref()) {
+                Ok(string) => string,
+                Err(exception) => {
+                    self.local_refs.truncate(local_refs);
+                    return Err(exception);
+                },
+            };
+            self.heap.get_mut(array).elements_mut()[idx] = Operand::Ref(string);
+            self.heap.write_barrier(array);
+        }
+        self.local_refs.truncate(local_refs);
+        Ok(array)
+    }
+
+    /*
+     * Room is made for a string and its byte array at once, so the
+     * array needn't be rooted while the string is allocated.
+     */
+    fn new_string_from_chars(&mut self, chars: &[u16])
+            -> Result<Handle, JavaThrowable> {
+        let (bytes, coder) = strings::encode(chars);
+        let bytes_class = self.load_class("[B");
+        let string_class = self.load_class("java/lang/String");
+        self.reserve(Heap::array_size(bytes_class, bytes.len())
+                     + Heap::object_size(string_class))?;
+        Ok(self.allocate_encoded_string(&bytes, coder))
+    }
+
+    /*
+     * Creates a String without making room for it first, for the
+     * strings of throwables the VM creates: collecting garbage could
+     * lose the throwable being built, and running out of memory would
+     * mean throwing another one, recursively for OutOfMemoryError.
+     */
+    fn allocate_string(&mut self, value: &str) -> Handle {
+        let chars: Vec<u16> = value.encode_utf16().collect();
+        let (bytes, coder) = strings::encode(&chars);
+        self.allocate_encoded_string(&bytes, coder)
+    }
+
+    fn allocate_encoded_string(&mut self, bytes: &[i8], coder: u8)
+            -> Handle {
+        let bytes_class = self.load_class("[B");
+        let value = self.heap.allocate_array(bytes_class, bytes.len());
+        self.heap.get_mut(value).bytes_mut().copy_from_slice(bytes);
+
+        let string_class = self.load_class("java/lang/String");
+        let string = self.heap.allocate(string_class);
+        self.set_field(string, "value", "[B", Operand::Ref(value));
+        self.set_field(string, "coder", "B", Operand::Int(coder as i32));
+        string
+    }
+
+    /*
+     * Returns the value of a java.lang.String.
+     */
+    fn string_value(&self, string: Handle) -> String {
+        strings::to_rust_string(&self.heap, string)
+    }
+
+    /*
+     * Returns the String object holding the given value that is
+     * shared by all string constants with that value.
+     */
+    fn intern(&mut self, value: &str) -> Result<Handle, JavaThrowable> {
+        let chars: Vec<u16> = value.encode_utf16().collect();
+        if let Some(&string) = self.interned_strings.get(&chars) {
+            return Ok(string);
+        }
+        let string = self.new_string_from_chars(&chars)?;
+        self.interned_strings.insert(chars, string);
+        Ok(string)
+    }
+
+    /*
+     * Implements String.intern(): a string equal to one already
+     * interned yields that string, and is otherwise interned itself.
+     */
+    fn intern_string(&mut self, string: Handle) -> Handle {
+        let chars = strings::chars(&self.heap, string);
+        *self.interned_strings.entry(chars).or_insert(string)
+    }
+
+    /*
+     * Gets and sets fields of objects on behalf of the VM, which
+     * are found by name in the layout of the object's class.
+     */
+    fn get_field(&self, object: Handle, name: &str, descriptor: &str)
+            -> Operand {
+        let object = self.heap.get(object);
+        let class = object.header.class;
+        match class.instance_field_idx(name, descriptor) {
+            Some(idx) => object.fields()[idx],
+            None => panic!("[ERROR] {} lacks field {}:{}.",
+                           class.name, name, descriptor),
+        }
+    }
+
+    fn set_field(&mut self, object: Handle, name: &str, descriptor: &str,
+                 value: Operand) {
+        let class = self.heap.get(object).header.class;
+        let idx = match class.instance_field_idx(name, descriptor) {
+            Some(idx) => idx,
+            None => panic!("[ERROR] {} lacks field {}:{}.",
+                           class.name, name, descriptor),
+        };
+        self.heap.get_mut(object).fields_mut()[idx] = value;
+        self.heap.write_barrier(object);
+    }
+
+    /*
+     * Returns the java.lang.Class object standing for a class,
+     * creating it the first time it's asked for.
+     */
+    fn class_mirror(&mut self, class: &'static RuntimeClass)
+            -> Result<Handle, JavaThrowable> {
+        if let Some(&mirror) = self.class_mirrors.get(class.name) {
+            return Ok(mirror);
+        }
+        // The component's mirror is kept alive by the cache of mirrors.
+        let component_mirror = match (class.component_class,
+                                      &class.component_type) {
+            (Some(component_class), _) =>
+                Operand::Ref(self.class_mirror(component_class)?),
+            (None, Some(component_type)) =>
+                Operand::Ref(self.type_mirror(Some(component_type))?),
+            (None, &None) => Operand::Null,
+        };
+        let mirror = self.new_mirror(&class.name.replace('/', "."))?;
+        self.set_field(mirror, "componentType", "Ljava/lang/Class;",
+                       component_mirror);
+        self.class_mirrors.insert(class.name.to_string(), mirror);
+        Ok(mirror)
+    }
+
+    /*
+     * Returns the java.lang.Class object standing for a type, which
+     * for primitive types (and void, given as None) has no class.
+     */
+    fn type_mirror(&mut self, field_type: Option<&FieldType>)
+            -> Result<Handle, JavaThrowable> {
+        let name = match field_type {
+            Some(FieldType::Object(class_name)) => {
+                let class = self.load_class(class_name);
+                return self.class_mirror(class);
+            },
+            Some(array_type @ &FieldType::Array(_)) => {
+                let class = self.load_class(&array_type.to_string());
+                return self.class_mirror(class);
+            },
+            Some(&FieldType::Byte) => "byte",
+            Some(&FieldType::Char) => "char",
+            Some(&FieldType::Double) => "double",
+            Some(&FieldType::Float) => "float",
+            Some(&FieldType::Int) => "int",
+            Some(&FieldType::Long) => "long",
+            Some(&FieldType::Short) => "short",
+            Some(&FieldType::Boolean) => "boolean",
+            None => "void",
+        };
+        if let Some(&mirror) = self.class_mirrors.get(name) {
+            return Ok(mirror);
+        }
+        let mirror = self.new_mirror(name)?;
+        self.class_mirrors.insert(name.to_string(), mirror);
+        Ok(mirror)
+    }
+
+    /*
+     * The name is interned before the mirror is allocated, so it's
+     * kept alive by the table of interned strings meanwhile.
+     */
+    fn new_mirror(&mut self, name: &str) -> Result<Handle, JavaThrowable> {
+        let name = self.intern(name)?;
+        let mirror_class = self.load_class("java/lang/Class");
+        self.reserve(Heap::object_size(mirror_class))?;
+        let mirror = self.heap.allocate(mirror_class);
+        self.set_field(mirror, "name", "Ljava/lang/String;",
+                       Operand::Ref(name));
+        Ok(mirror)
+    }
+
+    /*
+     * Resolves a loadable constant of the current class to a value,
+     * as the ldc instructions do.
+     */
+    fn resolve_constant(&mut self, const_idx: u16)
+            -> Result<Operand, JavaThrowable> {
+        let definition = self.frame().definition;
+        let key = (definition.name, const_idx);
+        if let Some(&value) = self.resolved_constants.get(&key) {
+            return Ok(value);
+        }
+        let value = match definition.constant_pool.get(&const_idx) {
+            Some(&ConstantPoolEntry::Integer(value)) =>
+                return Ok(Operand::Int(value)),
+            Some(&ConstantPoolEntry::Float(value)) =>
+                return Ok(Operand::Float(value)),
+            Some(&ConstantPoolEntry::Long(value)) =>
+                return Ok(Operand::Long(value)),
+            Some(&ConstantPoolEntry::Double(value)) =>
+                return Ok(Operand::Double(value)),
+            Some(ConstantPoolEntry::String(constant)) => {
+                let value = definition.utf8(constant.string_idx);
+                return Ok(Operand::Ref(self.intern(value)?));
+            },
+            Some(&ConstantPoolEntry::Class(_)) => {
+                let class =
+                    self.resolve_class(definition.class_name(const_idx))?;
+                return Ok(Operand::Ref(self.class_mirror(class)?));
+            },
+            Some(ConstantPoolEntry::MethodType(constant)) =>
+                self.method_type(definition.utf8(constant.descriptor_idx))?,
+            Some(ConstantPoolEntry::MethodHandle(constant)) =>
+                self.method_handle(definition, constant)?,
+            Some(ConstantPoolEntry::Dynamic(constant)) =>
+                self.dynamic_constant(definition, constant)?,
+            _ => panic!("[ERROR] Expected loadable constant in constant \
+                         pool at index {}.", const_idx),
+        };
+        self.resolved_constants.insert(key, value);
+        Ok(value)
+    }
+
+    /*
+     * Creates a java.lang.invoke.MethodType for a method descriptor.
+     * The mirrors it refers to are kept alive by the cache of mirrors,
+     * and room is made for its parameter array and itself at once.
+     */
+    fn method_type(&mut self, descriptor: &str)
+            -> Result<Operand, JavaThrowable> {
+        let descriptor = parse_method_descriptor(descriptor);
+        let rtype = self.type_mirror(descriptor.ret.as_ref())?;
+        let mut ptypes = Vec::with_capacity(descriptor.params.len());
+        for param in descriptor.params.iter() {
+            ptypes.push(Operand::Ref(self.type_mirror(Some(param))?));
+        }
+        let ptypes_class = self.load_class("[Ljava/lang/Class;");
+        let method_type_class = self.load_class("java/lang/invoke/MethodType");
+        self.reserve(Heap::array_size(ptypes_class, ptypes.len())
+                     + Heap::object_size(method_type_class))?;
+        let ptypes_array = self.heap.allocate_array(ptypes_class, ptypes.len());
+        self.heap.get_mut(ptypes_array).elements_mut()
+            .copy_from_slice(&ptypes);
+
+        let method_type = self.heap.allocate(method_type_class);
+        self.set_field(method_type, "rtype", "Ljava/lang/Class;",
+                       Operand::Ref(rtype));
+        self.set_field(method_type, "ptypes", "[Ljava/lang/Class;",
+                       Operand::Ref(ptypes_array));
+        Ok(Operand::Ref(method_type))
+    }
+
+    /*
+     * Creates a java.lang.invoke.MethodHandle for the field or method
+     * that a MethodHandle constant refers to. Its type is that of the
+     * bytecode behavior the handle stands for, per section 5.4.3.5 of
+     * the JVM specification: field accessors and instance methods
+     * take the object as a leading parameter, and constructors
+     * return the object they create.
+     */
+    fn method_handle(&mut self, definition: &'static Class<'static>,
+                     constant: &MethodHandleConstant)
+            -> Result<Operand, JavaThrowable> {
+        let member_ref = definition.member_ref(constant.reference_idx);
+        let class = self.load_class(member_ref.class_name);
+        let receiver = format!("L{};", member_ref.class_name);
+        let descriptor = member_ref.descriptor;
+        let type_descriptor = match constant.reference_kind {
+            REF_GET_FIELD => format!("({}){}", receiver, descriptor),
+            REF_GET_STATIC => format!("(){}", descriptor),
+            REF_PUT_FIELD => format!("({}{})V", receiver, descriptor),
+            REF_PUT_STATIC => format!("({})V", descriptor),
+            REF_INVOKE_VIRTUAL | REF_INVOKE_SPECIAL | REF_INVOKE_INTERFACE =>
+                format!("({}{}", receiver, &descriptor[1 ..]),
+            REF_INVOKE_STATIC => descriptor.to_string(),
+            REF_NEW_INVOKE_SPECIAL =>
+                format!("{}{}", &descriptor[.. descriptor.len() - 1], receiver),
+            kind => panic!("[ERROR] Invalid method handle kind: {}", kind),
+        };
+        // The mirror and name are kept alive by their caches, but the
+        // type is rooted while room is made for the handle.
+        let declaring_class = self.class_mirror(class)?;
+        let name = self.intern(member_ref.name)?;
+        let method_type = self.method_type(&type_descriptor)?;
+        let handle_class =
+            self.load_class("java/lang/invoke/DirectMethodHandle");
+        if let Operand::Ref(method_type) = method_type {
+            self.local_refs.push(method_type);
+        }
+        let reserved = self.reserve(Heap::object_size(handle_class));
+        self.local_refs.pop();
+        reserved?;
+        let handle = self.heap.allocate(handle_class);
+        self.set_field(handle, "type", "Ljava/lang/invoke/MethodType;",
+                       method_type);
+        self.set_field(handle, "referenceKind", "I",
+                       Operand::Int(constant.reference_kind as i32));
+        self.set_field(handle, "declaringClass", "Ljava/lang/Class;",
+                       Operand::Ref(declaring_class));
+        self.set_field(handle, "name", "Ljava/lang/String;",
+                       Operand::Ref(name));
+        Ok(Operand::Ref(handle))
+    }
+
+    /*
+     * Computes the value of a dynamically-computed constant by
+     * invoking its bootstrap method with a lookup on the current
+     * class, the constant's name and type, and the bootstrap method's
+     * static arguments, per section 5.4.3.6 of the JVM specification.
+     * Bootstrap methods must be static, and their arguments are passed
+     * as they are, without the boxing and varargs collection that a
+     * method handle invocation could perform.
+     */
+    fn dynamic_constant(&mut self, definition: &'static Class<'static>,
+                        constant: &DynamicConstant)
+            -> Result<Operand, JavaThrowable> {
+        let (name, descriptor) =
+            definition.name_and_type(constant.name_and_type_idx);
+        let bootstrap =
+            definition.bootstrap_method(constant.bootstrap_method_attr_idx);
+        let method_ref = match definition.constant_pool
+                .get(&bootstrap.bootstrap_method_ref) {
+            Some(ConstantPoolEntry::MethodHandle(handle))
+                    if handle.reference_kind == REF_INVOKE_STATIC =>
+                definition.member_ref(handle.reference_idx),
+            _ => panic!("[ERROR] Bootstrap method of {}:{} is not a \
+                         static method.", name, descriptor),
+        };
+        let bootstrap_class = self.load_class(method_ref.class_name);
+        let (bootstrap_class, bootstrap_method) = match bootstrap_class
+                .find_method(method_ref.name, method_ref.descriptor) {
+            Some(found) => found,
+            None => panic!("[ERROR] Unable to resolve bootstrap method \
+                            {}.{}{}.", method_ref.class_name,
+                           method_ref.name, method_ref.descriptor),
+        };
+
+        let lookup_class =
+            self.load_class("java/lang/invoke/MethodHandles$Lookup");
+        self.reserve(Heap::object_size(lookup_class))?;
+        let lookup = self.heap.allocate(lookup_class);
+        // The bootstrap arguments aren't on any frame until the
+        // bootstrap method is called, so they're rooted here instead.
+        let local_refs = self.local_refs.len();
+        self.local_refs.push(lookup);
+        let result = self.bootstrap_dynamic_constant(
+            lookup, name, descriptor, bootstrap_class, bootstrap_method,
+            &bootstrap.bootstrap_arguments);
+        self.local_refs.truncate(local_refs);
+        match result {
+            Ok(Some(value)) => Ok(value),
+            Ok(None) => panic!("[ERROR] Bootstrap method {}{} returned \
+                                no value.", bootstrap_method.name,
+                               bootstrap_method.descriptor),
+            Err(exception) => Err(self.bootstrap_method_error(exception)),
+        }
+    }
+
+    /*
+     * Resolves the static arguments of a dynamic constant's bootstrap
+     * method, then initializes its class and runs it, with the rest of
+     * its arguments.
+     */
+    fn bootstrap_dynamic_constant(&mut self, lookup: Handle, name: &str,
+                                  descriptor: &str,
+                                  bootstrap_class: &'static RuntimeClass,
+                                  bootstrap_method: &'static Method<'static>,
+                                  bootstrap_arguments: &[u16])
+            -> Result<Option<Operand>, JavaThrowable> {
+        let current_mirror = {
+            let current_class = self.frame().class;
+            self.class_mirror(current_class)?
+        };
+        self.set_field(lookup, "lookupClass", "Ljava/lang/Class;",
+                       Operand::Ref(current_mirror));
+        let name = self.intern(name)?;
+        let field_type = parse_field_descriptor(descriptor);
+        let type_mirror = self.type_mirror(Some(&field_type))?;
+        let mut args = vec![Operand::Ref(lookup), Operand::Ref(name),
+                            Operand::Ref(type_mirror)];
+        for &arg_idx in bootstrap_arguments.iter() {
+            let arg = self.resolve_constant(arg_idx)?;
+            if let Operand::Ref(arg) = arg {
+                self.local_refs.push(arg);
+            }
+            args.push(arg);
+        }
+        let param_count =
+            parse_method_descriptor(bootstrap_method.descriptor).params.len();
+        if param_count != args.len() {
+            panic!("[ERROR] Bootstrap method {}{} takes {} arguments, \
+                    but was given {}.", bootstrap_method.name,
+                   bootstrap_method.descriptor, param_count, args.len());
+        }
+
+        let base_depth = self.frames.len();
+        if self.initialize_class(bootstrap_class)? {
+            self.execute(base_depth)?;
+        }
+        self.push_frame(bootstrap_class, bootstrap_method, args)?;
+        self.execute(base_depth)
+    }
+
+    /*
+     * Errors thrown by a bootstrap method propagate as they are, but
+     * other exceptions are wrapped in a BootstrapMethodError.
+     */
+    fn bootstrap_method_error(&mut self, exception: JavaThrowable)
+            -> JavaThrowable {
+        let error_class = self.load_class("java/lang/Error");
+        if self.heap.get(exception.0).header.class.is_subclass_of(error_class) {
+            return exception;
+        }
+        let error = self.throw_exception(
+            "java/lang/BootstrapMethodError",
+            "bootstrap method initialization exception");
+        self.set_field(error.0, "cause", "Ljava/lang/Throwable;",
+                       Operand::Ref(exception.0));
+        error
+    }
+
+    /*
+     * Loads a class the VM itself relies on, such as an exception it
+     * throws, which the class library must provide.
+     */
+    fn load_class(&mut self, class_name: &str) -> &'static RuntimeClass {
+        match self.loader.load(class_name) {
+            Some(class) => class,
+            None => panic!("[ERROR] Unable to load class {}.", class_name),
+        }
+    }
+
+    /*
+     * Loads a class named by guest code, throwing NoClassDefFoundError
+     * if it can't be found.
+     */
+    fn resolve_class(&mut self, class_name: &str)
+            -> Result<&'static RuntimeClass, JavaThrowable> {
+        match self.loader.load(class_name) {
+            Some(class) => Ok(class),
+            None => Err(self.throw_exception(
+                "java/lang/NoClassDefFoundError", class_name)),
+        }
+    }
+
+    /*
+     * Begins initialization of a class, as described in section 5.5
+     * of the JVM specification, by pushing frames for the static
+     * initializers of the class and of those of its superclasses and
+     * superinterfaces that have yet to be initialized. Supertypes are
+     * pushed last so that they are initialized first. Returns whether
+     * any initializers were pushed.
+     */
+    fn initialize_class(&mut self, class: &'static RuntimeClass)
+            -> Result<bool, JavaThrowable> {
+        let mut pending = Vec::new();
+        let thread = self.thread_id;
+        while let Some(busy) = self.collect_uninitialized(class,
+                                                          &mut pending)? {
+            /*
+             * Another thread is initializing one of the classes, which
+             * this one waits for it to finish before starting over.
+             */
+            self.block_until(None, |interpreter| {
+                match interpreter.loader.statics(busy).init_state {
+                    InitState::InProgress(owner) => owner == thread,
+                    _ => true,
+                }
+            })?;
+            pending.clear();
+        }
+        let mut pushed = false;
+        for class in pending.into_iter().rev() {
+            self.loader.statics_mut(class).init_state =
+                InitState::InProgress(thread);
+            if let Err(error) = self.initialize_string_constants(class) {
+                self.loader.statics_mut(class).init_state =
+                    InitState::Uninitialized;
+                return Err(error);
+            }
+            match class.declared_method("<clinit>", "()V") {
+                Some(method) => {
+                    if let Err(error) = self.push_frame(class, method,
+                                                        Vec::new()) {
+                        self.loader.statics_mut(class).init_state =
+                            InitState::Uninitialized;
+                        return Err(error);
+                    }
+                    pushed = true;
+                },
+                None => self.loader.statics_mut(class).init_state =
+                    InitState::Initialized,
+            }
+        }
+        Ok(pushed)
+    }
+
+    /*
+     * Assigns static fields whose ConstantValue attributes refer to
+     * String constants their values, which are set up as part of the
+     * class's initialization, rather than when the class is linked.
+     */
+    fn initialize_string_constants(&mut self, class: &'static RuntimeClass)
+            -> Result<(), JavaThrowable> {
+        let definition = match class.definition {
+            Some(definition) => definition,
+            None => return Ok(()),
+        };
+        for field in definition.fields.iter().filter(|f| f.is_static()) {
+            let string_idx = match field.constant_value_idx()
+                    .and_then(|idx| definition.constant_pool.get(&idx)) {
+                Some(ConstantPoolEntry::String(constant)) =>
+                    constant.string_idx,
+                _ => continue,
+            };
+            let idx = match class.static_fields.iter().position(
+                    |f| f.name == field.name
+                        && f.descriptor == field.descriptor) {
+                Some(idx) => idx,
+                None => panic!("[ERROR] {} lacks static field {}.",
+                               class.name, field.name),
+            };
+            let string = self.intern(definition.utf8(string_idx))?;
+            self.loader.statics_mut(class).values[idx] = Operand::Ref(string);
+            self.heap.static_write_barrier(class.name);
+        }
+        Ok(())
+    }
+
+    /*
+     * Lists the given class and its supertypes that have yet to be
+     * initialized, in the order in which they are to be initialized.
+     * Interfaces don't initialize their superinterfaces, and classes
+     * only initialize those that declare default methods. If another
+     * thread is initializing one of them, that class is returned.
+     */
+    fn collect_uninitialized(&mut self, class: &'static RuntimeClass,
+                             pending: &mut Vec<&'static RuntimeClass>)
+            -> Result<Option<&'static RuntimeClass>, JavaThrowable> {
+        match self.loader.statics(class).init_state {
+            InitState::Uninitialized
+                    if !pending.iter().any(|c| c.name == class.name) => {
+                if !class.is_interface() {
+                    let supertypes = class.super_class.into_iter().chain(
+                        class.interfaces.iter().cloned()
+                            .filter(|i| declares_default_methods(i)));
+                    for supertype in supertypes {
+                        let busy =
+                            self.collect_uninitialized(supertype, pending)?;
+                        if busy.is_some() {
+                            return Ok(busy);
+                        }
+                    }
+                }
+                pending.push(class);
+                Ok(None)
+            },
+            InitState::InProgress(owner) if owner != self.thread_id =>
+                Ok(Some(class)),
+            InitState::Erroneous => Err(self.throw_exception(
+                "java/lang/NoClassDefFoundError",
+                &format!("Could not initialize class {}",
+                         class.name.replace('/', ".")))),
+            _ => Ok(None),
+        }
+    }
+
+    /*
+     * Ensures that a class has been initialized before the
+     * instruction at the given pc makes use of it. If initializers
+     * need to run first, the instruction is rewound so that it will
+     * be executed again once they return, and false is returned.
+     */
+    fn ensure_initialized(&mut self, class: &'static RuntimeClass,
+                          opcode_pc: usize) -> Result<bool, JavaThrowable> {
+        let current = self.frames.len() - 1;
+        if self.initialize_class(class)? {
+            self.frames[current].pc = opcode_pc;
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    fn resolve_static_field(&mut self, field_const_idx: u16)
+            -> Result<(&'static RuntimeClass, usize), JavaThrowable> {
+        let field_ref = self.frame().definition.member_ref(field_const_idx);
+        let class = self.resolve_class(field_ref.class_name)?;
+        match class.find_static_field(field_ref.name, field_ref.descriptor) {
+            Some(found) => Ok(found),
+            None => Err(self.throw_exception("java/lang/NoSuchFieldError",
+                                             field_ref.name)),
+        }
+    }
+
+    fn getstatic(&mut self, opcode_pc: usize, field_const_idx: u16)
+            -> Result<(), JavaThrowable> {
+        let (class, idx) = self.resolve_static_field(field_const_idx)?;
+        if self.ensure_initialized(class, opcode_pc)? {
+            let value = self.loader.statics(class).values[idx];
+            self.frame().push(value);
+        }
+        Ok(())
+    }
+
+    fn putstatic(&mut self, opcode_pc: usize, field_const_idx: u16)
+            -> Result<(), JavaThrowable> {
+        let (class, idx) = self.resolve_static_field(field_const_idx)?;
+        if self.ensure_initialized(class, opcode_pc)? {
+            let value = coerce_to_field_type(
+                &class.static_fields[idx].field_type, self.frame().pop());
+            if let Operand::Ref(_) = value {
+                self.heap.static_write_barrier(class.name);
+            }
+            self.loader.statics_mut(class).values[idx] = value;
+        }
+        Ok(())
+    }
+
+    /*
+     * Resolves a field ref to the index of the field within the
+     * layout of instances of the class that declares it.
+     */
+    fn resolve_instance_field(&mut self, field_const_idx: u16)
+            -> Result<(&'static RuntimeClass, usize), JavaThrowable> {
+        let field_ref = self.frame().definition.member_ref(field_const_idx);
+        let class = self.resolve_class(field_ref.class_name)?;
+        match class.instance_field_idx(field_ref.name, field_ref.descriptor) {
+            Some(idx) => Ok((class, idx)),
+            None => Err(self.throw_exception("java/lang/NoSuchFieldError",
+                                             field_ref.name)),
+        }
+    }
+
+    fn getfield(&mut self, field_const_idx: u16)
+            -> Result<(), JavaThrowable> {
+        let (_, idx) = self.resolve_instance_field(field_const_idx)?;
+        let object = match self.frame().pop() {
+            Operand::Ref(object) => object,
+            Operand::Null => return Err(self.null_pointer_exception()),
+            _ => panic!("[ERROR] getfield expected objectref at top \
+                         of stack but didn't get one."),
+        };
+        let value = self.heap.get(object).fields()[idx];
+        self.frame().push(value);
+        Ok(())
+    }
+
+    fn putfield(&mut self, field_const_idx: u16)
+            -> Result<(), JavaThrowable> {
+        let (class, idx) = self.resolve_instance_field(field_const_idx)?;
+        let field = &class.instance_fields[idx];
+        let value = coerce_to_field_type(&field.field_type,
+                                         self.frame().pop());
+        let object = match self.frame().pop() {
+            Operand::Ref(object) => object,
+            Operand::Null => return Err(self.null_pointer_exception()),
+            _ => panic!("[ERROR] putfield expected objectref on the \
+                         stack but didn't get one."),
+        };
+        if let Operand::Ref(_) = value {
+            self.heap.write_barrier(object);
+        }
+        self.heap.get_mut(object).fields_mut()[idx] = value;
+        Ok(())
+    }
+
+    /*
+     * Makes room for objects of the given total size, collecting the
+     * young generation first if it's full, and the whole heap if it
+     * has grown past its capacity, throwing OutOfMemoryError if
+     * there's still no room. This is the only place allocations on
+     * behalf of guest code collect garbage, so its callers mustn't
+     * hold handles the collector can't find.
+     */
+    fn reserve(&mut self, bytes: usize) -> Result<(), JavaThrowable> {
+        if self.heap.is_young_collection_due(bytes) {
+            self.collect_young_generation();
+        }
+        if self.heap.is_full_collection_due(bytes) {
+            self.collect_garbage(GcCause::AllocationFailure, bytes, false);
+        }
+        if !self.heap.has_room_for(bytes) {
+            // Soft references are cleared only as a last resort.
+            self.collect_garbage(GcCause::AllocationFailure, bytes, true);
+        }
+        if self.heap.has_room_for(bytes) {
+            Ok(())
+        } else {
+            Err(self.throw_exception("java/lang/OutOfMemoryError",
+                                     "Java heap space"))
+        }
+    }
+
+    fn collect_garbage(&mut self, cause: GcCause, requested: usize,
+                       clear_soft_references: bool) -> Collection {
+        let started = Instant::now();
+        let roots = self.gc_roots(true);
+        let collection = self.heap.collect(&roots, requested,
+                                           clear_soft_references);
+        self.log_collection("Full", cause, &collection, started);
+        collection
+    }
+
+    /*
+     * Minor collections trace from the static fields of only those
+     * classes a reference has been stored to since the last one, as
+     * the old objects the rest refer to are found through the card
+     * table if they refer to young ones.
+     */
+    fn collect_young_generation(&mut self) -> Collection {
+        let started = Instant::now();
+        let mut roots = self.gc_roots(false);
+        let dirty_statics = self.heap.take_dirty_statics();
+        for &class_name in dirty_statics.iter() {
+            roots.extend(self.loader.static_references_of(class_name));
+        }
+        let collection = self.heap.collect_young(&roots);
+        for class_name in dirty_statics {
+            let references = self.loader.static_references_of(class_name);
+            if references.iter().any(|&object| self.heap.is_young(object)) {
+                self.heap.static_write_barrier(class_name);
+            }
+        }
+        self.log_collection("Young", GcCause::AllocationFailure, &collection,
+                            started);
+        collection
+    }
+
+    fn log_collection(&mut self, kind: &str, cause: GcCause,
+                      collection: &Collection, started: Instant) {
+        let pause = started.elapsed();
+        self.heap.record_pause(pause);
+        if self.verbose.gc {
+            println!("[{:.3}s][info][gc] GC({}) Pause {} ({}) \
+                      {}K->{}K({}K) {:.3}ms",
+                     self.started.elapsed().as_secs_f64(),
+                     self.heap.stats().collections - 1, kind, cause,
+                     collection.used_before / 1024,
+                     collection.used_after / 1024,
+                     collection.capacity / 1024,
+                     pause.as_secs_f64() * 1000.0);
+        }
+    }
+
+    /*
+     * The objects the collector starts tracing from: those held by
+     * every thread and its java.lang.Thread, by static fields (unless
+     * left out), by the interned strings, class mirrors and resolved
+     * constants, and by global references. The other threads hold
+     * those they held when they last gave up the VM lock.
+     */
+    fn gc_roots(&self, include_statics: bool) -> Vec<Handle> {
+        let mut roots = self.thread_roots();
+        for (&id, thread) in self.threads.iter() {
+            if id != self.thread_id {
+                roots.extend(thread.roots.iter().cloned());
+            }
+            roots.extend(thread.object);
+        }
+        if include_statics {
+            roots.extend(self.loader.static_references());
+        }
+        roots.extend(self.interned_strings.values().cloned());
+        roots.extend(self.class_mirrors.values().cloned());
+        for constant in self.resolved_constants.values() {
+            if let Operand::Ref(object) = *constant {
+                roots.push(object);
+            }
+        }
+        roots.extend(self.global_refs.iter().cloned());
+        roots
+    }
+
+    /*
+     * The objects this thread holds: those referred to by the locals
+     * and operands of its frames, the monitors they hold, and its
+     * local references.
+     */
+    fn thread_roots(&self) -> Vec<Handle> {
+        let mut roots = Vec::new();
+        for frame in self.frames.iter() {
+            roots.extend(frame.monitor);
+            let locals = frame.local_var_arr.iter().filter_map(|&local| local);
+            for operand in locals.chain(frame.operand_stack.iter().cloned()) {
+                if let Operand::Ref(object) = operand {
+                    roots.push(object);
+                }
+            }
+        }
+        roots.extend(self.local_refs.iter().cloned());
+        roots
+    }
+
+    fn new_array(&mut self, array_class_name: &str)
+            -> Result<(), JavaThrowable> {
+        let count = self.frame().pop_int();
+        if count < 0 {
+            return Err(self.throw_exception(
+                "java/lang/NegativeArraySizeException", &count.to_string()));
+        }
+        let class = self.resolve_class(array_class_name)?;
+        self.reserve(Heap::array_size(class, count as usize))?;
+        let array = self.heap.allocate_array(class, count as usize);
+        self.frame().push(Operand::Ref(array));
+        Ok(())
+    }
+
+    /*
+     * Pops an index and the array beneath it, checking that the
+     * array is of the kind the instruction expects and that the
+     * index is within its bounds.
+     */
+    fn pop_array_element(&mut self, kind: u8)
+            -> Result<(Handle, usize), JavaThrowable> {
+        let index = self.frame().pop_int();
+        let array = match self.frame().pop() {
+            Operand::Ref(array) => array,
+            Operand::Null => return Err(self.null_pointer_exception()),
+            _ => panic!("[ERROR] Expected arrayref on the stack \
+                         but didn't get one."),
+        };
+        let object = self.heap.get(array);
+        let is_expected_kind = match object.header.class.component_type {
+            Some(ref component_type) => is_array_kind(kind, component_type),
+            None => false,
+        };
+        if !is_expected_kind {
+            panic!("[ERROR] Expected {} array, but found {}.",
+                   ARRAY_KINDS[kind as usize], object.header.class.name);
+        }
+        let length = object.length();
+        if index < 0 || index as usize >= length {
+            return Err(self.throw_exception(
+                "java/lang/ArrayIndexOutOfBoundsException",
+                &format!("Index {} out of bounds for length {}",
+                         index, length)));
+        }
+        Ok((array, index as usize))
+    }
+
+    fn array_load(&mut self, kind: u8)
+            -> Result<(), JavaThrowable> {
+        let (array, index) = self.pop_array_element(kind)?;
+        let value = self.heap.get(array).element(index);
+        self.frame().push(value);
+        Ok(())
+    }
+
+    /*
+     * Stores a value to an array; byte, char and short values are
+     * truncated to the width of the array's components, and booleans
+     * to their lowest bit. References may only be stored to arrays
+     * whose component class they are assignable to.
+     */
+    fn array_store(&mut self, kind: u8)
+            -> Result<(), JavaThrowable> {
+        let value = self.frame().pop();
+        let (array, index) = self.pop_array_element(kind)?;
+        let array_class = self.heap.get(array).header.class;
+        let value = match (value, &array_class.component_type) {
+            (Operand::Int(value), &Some(FieldType::Boolean)) =>
+                Operand::Int(value & 1),
+            (Operand::Int(value), &Some(FieldType::Byte)) =>
+                Operand::Int(value as i8 as i32),
+            (Operand::Int(value), &Some(FieldType::Char)) =>
+                Operand::Int(value as u16 as i32),
+            (Operand::Int(value), &Some(FieldType::Short)) =>
+                Operand::Int(value as i16 as i32),
+            (Operand::Ref(object), _) => {
+                let class = self.heap.get(object).header.class;
+                match array_class.component_class {
+                    Some(component_class)
+                        if self.is_subtype(class, component_class) => value,
+                    _ => return Err(self.throw_exception(
+                        "java/lang/ArrayStoreException",
+                        &class.name.replace('/', "."))),
+                }
+            },
+            _ => value,
+        };
+        // Byte, char and short arrays, encoded after
+        // reference arrays, all hold int values.
+        let operand_type = match kind {
+            0 ..= 4 => OperandType::from_encoding(kind),
+            _ => OperandType::Int,
+        };
+        if !operand_type.matches(&value) {
+            panic!("[ERROR] Value stored to {} is of the wrong type.",
+                   array_class.name);
+        }
+        if let Operand::Ref(_) = value {
+            self.heap.write_barrier(array);
+        }
+        self.heap.get_mut(array).set_element(index, value);
+        Ok(())
+    }
+
+    fn invoke(&mut self, invocation: Invocation, opcode_pc: usize,
+              method_const_idx: u16) -> Result<(), JavaThrowable> {
+        let current_class = self.frame().class;
+        let current_definition = self.frame().definition;
+        let method_ref = current_definition.member_ref(method_const_idx);
+        if self.verbose.trace {
+            println!("invoke: Method {}.\"{}\":{}", method_ref.class_name,
+                     method_ref.name, method_ref.descriptor);
+        }
+
+        let resolved_class = self.resolve_class(method_ref.class_name)?;
+        let (resolved_in, resolved_method) = match resolved_class.find_method(
+                method_ref.name, method_ref.descriptor) {
+            Some(found) => found,
+            None => return Err(self.throw_exception(
+                "java/lang/NoSuchMethodError",
+                &format!("{}.{}{}", method_ref.class_name.replace('/', "."),
+                         method_ref.name, method_ref.descriptor))),
+        };
+        if invocation == Invocation::Static && !resolved_method.is_static() {
+            return Err(self.throw_exception(
+                "java/lang/IncompatibleClassChangeError",
+                &format!("Expected static method {}.{}{}",
+                         resolved_in.name.replace('/', "."),
+                         resolved_method.name, resolved_method.descriptor)));
+        }
+        if invocation == Invocation::Static
+                && !self.ensure_initialized(resolved_in, opcode_pc)? {
+            return Ok(());
+        }
+
+        /*
+         * Pop the arguments (and for all but static invocations,
+         * the object reference beneath them); arguments are popped in
+         * reverse, so the list is flipped to restore declaration order.
+         */
+        let descriptor = parse_method_descriptor(method_ref.descriptor);
+        let frame = self.frame();
+        let mut args: Vec<Operand> = descriptor.params.iter()
+            .map(|_| frame.pop()).collect();
+        if invocation != Invocation::Static {
+            match frame.pop() {
+                Operand::Null => return Err(self.null_pointer_exception()),
+                receiver => args.push(receiver),
+            }
+        }
+        args.reverse();
+
+        let (class, method) = match invocation {
+            Invocation::Static => (resolved_in, resolved_method),
+            Invocation::Special => {
+                /*
+                 * Calls to superclass methods (other than constructors)
+                 * are looked up starting from the direct superclass of
+                 * the current class, rather than the resolved class.
+                 */
+                let is_super_call = method_ref.name != "<init>"
+                    && !resolved_class.is_interface()
+                    && resolved_class.name != current_class.name
+                    && current_class.is_subclass_of(resolved_class)
+                    && current_definition.access_flags & ACC_SUPER != 0;
+                match current_class.super_class {
+                    Some(super_class) if is_super_call =>
+                        select_method(super_class, resolved_method),
+                    _ => (resolved_in, resolved_method),
+                }
+            },
+            Invocation::Virtual | Invocation::Interface => {
+                if resolved_method.access_flags & ACC_PRIVATE != 0 {
+                    (resolved_in, resolved_method)
+                } else {
+                    let receiver_class = match args[0] {
+                        Operand::Ref(object) =>
+                            self.heap.get(object).header.class,
+                        _ => panic!("[ERROR] Expected objectref as the \
+                                     receiver of {}.{}{}.", resolved_in.name,
+                                    resolved_method.name,
+                                    resolved_method.descriptor),
+                    };
+                    select_method(receiver_class, resolved_method)
+                }
+            },
+        };
+
+        if method.is_abstract() {
+            // As in HotSpot, the receiver's class is named, if there is one.
+            let class = match args.first() {
+                Some(&Operand::Ref(object))
+                        if invocation != Invocation::Static =>
+                    self.heap.get(object).header.class,
+                _ => class,
+            };
+            return Err(self.throw_exception(
+                "java/lang/AbstractMethodError",
+                &format!("{}.{}{}", class.name.replace('/', "."),
+                         method.name, method.descriptor)));
+        }
+        // Should the call block, the instruction is executed again.
+        let retained = if self.suspendable
+                && (method.is_native() || method.is_synchronized()) {
+            Some(args.clone())
+        } else {
+            None
+        };
+        let result = if method.is_native() {
+            self.call_native(class, method, args).map(|result| {
+                if let Some(result) = result {
+                    self.frame().push(result);
+                }
+            })
+        } else {
+            self.push_frame(class, method, args)
+        };
+        if let Some(args) = retained.filter(|_| self.suspended) {
+            let frame = self.frame();
+            for arg in args {
+                frame.push(arg);
+            }
+        }
+        result
+    }
+
+    /*
+     * Calls the Rust implementation of a native method, as linked
+     * along with its class; a native method for which none was
+     * registered throws UnsatisfiedLinkError.
+     */
+    fn call_native(&mut self, class: &'static RuntimeClass,
+                   method: &'static Method<'static>, args: Vec<Operand>)
+            -> Result<Option<Operand>, JavaThrowable> {
+        let native = match class.natives.get(&(method.name,
+                                                method.descriptor)) {
+            Some(Some(native)) => native.clone(),
+            _ => return Err(self.throw_exception(
+                "java/lang/UnsatisfiedLinkError",
+                &format!("'{}'", describe_method(class.name, method.name,
+                                                 method.descriptor)))),
+        };
+
+        let monitor = if method.is_synchronized() {
+            Some(self.method_monitor(class, method, &args)?)
+        } else {
+            None
+        };
+        let descriptor = parse_method_descriptor(method.descriptor);
+        let mut params = descriptor.params.iter();
+        let receiver_type = FieldType::Object(class.name.to_string());
+        let values: Vec<Value> = args.into_iter().enumerate()
+            .map(|(idx, arg)| {
+                let field_type = if idx == 0 && !method.is_static() {
+                    &receiver_type
+                } else {
+                    params.next().unwrap_or_else(
+                        || panic!("[ERROR] Too many arguments passed \
+                                   to {}.", method.name))
+                };
+                Value::from_operand(arg, field_type)
+            }).collect();
+        let local_refs = self.local_refs.len();
+        self.local_refs.extend(values.iter().filter_map(|value| match *value {
+            Value::Ref(object) => Some(object),
+            _ => None,
+        }));
+        if let Some(object) = monitor {
+            if let Err(exception) = self.monitor_enter(object) {
+                self.local_refs.truncate(local_refs);
+                return Err(exception);
+            }
+            self.local_refs.push(object);
+        }
+        let result = native(&mut Env { interpreter: self }, &values);
+        if let Some(object) = monitor {
+            let thread = self.thread_id;
+            self.heap.get_mut(object).header.exit_monitor(thread);
+            self.wake_blocked_threads();
+        }
+        self.local_refs.truncate(local_refs);
+        let result = result?;
+        if !result.is_of_type(descriptor.ret.as_ref()) {
+            panic!("[ERROR] Native method {}.{}{} returned {:?}.",
+                   class.name, method.name, method.descriptor, result);
+        }
+        Ok(result.to_operand())
+    }
+}
+
+/*
+ * Describes a method as it's declared in Java source,
+ * e.g. "int Example.twice(int, java.lang.String[])".
+ */
+fn describe_method(class_name: &str, name: &str, descriptor: &str)
+        -> String {
+    let descriptor = parse_method_descriptor(descriptor);
+    let ret = match descriptor.ret {
+        Some(ref ret) => ret.java_name(),
+        None => "void".to_string(),
+    };
+    let params: Vec<String> = descriptor.params.iter()
+        .map(|param| param.java_name()).collect();
+    format!("{} {}.{}({})", ret, class_name.replace('/', "."),
+            name, params.join(", "))
+}
+
+fn select_method(class: &'static RuntimeClass,
+                 resolved_method: &'static Method<'static>)
+        -> (&'static RuntimeClass, &'static Method<'static>) {
+    match class.find_method(resolved_method.name, resolved_method.descriptor) {
+        Some(found) => found,
+        None => panic!("[ERROR] No implementation of {}{} \
+                        found in {}.", resolved_method.name,
+                       resolved_method.descriptor, class.name),
+    }
+}
+
+fn stack_trace_element_field_idx(element_class: &RuntimeClass, name: &str)
+        -> usize {
+    let descriptor = if name == "lineNumber" {
+        "I"
+    } else {
+        "Ljava/lang/String;"
+    };
+    match element_class.instance_field_idx(name, descriptor) {
+        Some(idx) => idx,
+        None => panic!("[ERROR] StackTraceElement lacks field {}.", name),
+    }
+}
 
-    panic!("TODO: Push static method/field value onto stack.");
+fn declares_default_methods(interface: &RuntimeClass) -> bool {
+    interface.definition.is_some_and(|d| d.methods.iter()
+                                     .any(|m| !m.is_abstract()
+                                          && !m.is_static()))
 }
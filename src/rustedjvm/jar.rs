@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use inflate::inflate;
+
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+const CENTRAL_DIRECTORY_HEADER: u32 = 0x0201_4b50;
+const LOCAL_FILE_HEADER: u32 = 0x0403_4b50;
+
+const STORED: u16 = 0;
+const DEFLATED: u16 = 8;
+
+/*
+ * A jar (or zip) file, read into memory when it's opened; its
+ * entries are decompressed as they're read. Neither zip64 archives
+ * nor archives spanning several files are supported.
+ */
+pub struct JarFile {
+    pub path: PathBuf,
+    buffer: Vec<u8>,
+    entries: HashMap<String, Entry>,
+}
+
+struct Entry {
+    method: u16,
+    compressed_size: usize,
+    size: usize,
+    header_offset: usize,
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn read_u16(buffer: &[u8], idx: usize) -> io::Result<u16> {
+    match buffer.get(idx .. idx + 2) {
+        Some(bytes) => Ok(u16::from_le_bytes([bytes[0], bytes[1]])),
+        None => Err(invalid("Truncated zip file")),
+    }
+}
+
+fn read_u32(buffer: &[u8], idx: usize) -> io::Result<u32> {
+    match buffer.get(idx .. idx + 4) {
+        Some(bytes) => Ok(u32::from_le_bytes([bytes[0], bytes[1],
+                                              bytes[2], bytes[3]])),
+        None => Err(invalid("Truncated zip file")),
+    }
+}
+
+impl JarFile {
+    pub fn open(path: &Path) -> io::Result<JarFile> {
+        let buffer = fs::read(path)?;
+
+        /*
+         * The central directory, which lists the entries, is found
+         * through the record that ends the file, which is followed
+         * only by a comment of up to 64 KiB.
+         */
+        let search_start = buffer.len().saturating_sub(22 + 0xffff);
+        let end_idx = match (search_start .. buffer.len().saturating_sub(21))
+                .rev()
+                .find(|&idx| read_u32(&buffer, idx).ok()
+                      == Some(END_OF_CENTRAL_DIRECTORY)) {
+            Some(idx) => idx,
+            None => return Err(invalid("Not a zip file")),
+        };
+        let entry_count = read_u16(&buffer, end_idx + 10)?;
+        let mut idx = read_u32(&buffer, end_idx + 16)? as usize;
+
+        let mut entries = HashMap::new();
+        for _ in 0 .. entry_count {
+            if read_u32(&buffer, idx)? != CENTRAL_DIRECTORY_HEADER {
+                return Err(invalid("Corrupt central directory"));
+            }
+            let name_length = read_u16(&buffer, idx + 28)? as usize;
+            let extra_length = read_u16(&buffer, idx + 30)? as usize;
+            let comment_length = read_u16(&buffer, idx + 32)? as usize;
+            let name = match buffer.get(idx + 46 .. idx + 46 + name_length) {
+                Some(name) => String::from_utf8_lossy(name).into_owned(),
+                None => return Err(invalid("Truncated zip file")),
+            };
+            entries.insert(name, Entry {
+                method: read_u16(&buffer, idx + 10)?,
+                compressed_size: read_u32(&buffer, idx + 20)? as usize,
+                size: read_u32(&buffer, idx + 24)? as usize,
+                header_offset: read_u32(&buffer, idx + 42)? as usize,
+            });
+            idx += 46 + name_length + extra_length + comment_length;
+        }
+
+        Ok(JarFile { path: path.to_path_buf(), buffer, entries })
+    }
+
+    /*
+     * Reads the entry with the given name (e.g., "com/acme/Main.class"),
+     * or returns None if there's no such entry.
+     */
+    pub fn read(&self, name: &str) -> io::Result<Option<Vec<u8>>> {
+        let entry = match self.entries.get(name) {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        let header = entry.header_offset;
+        if read_u32(&self.buffer, header)? != LOCAL_FILE_HEADER {
+            return Err(invalid("Corrupt local file header"));
+        }
+        let data_idx = header + 30
+            + read_u16(&self.buffer, header + 26)? as usize
+            + read_u16(&self.buffer, header + 28)? as usize;
+        let data = match self.buffer.get(data_idx
+                                         .. data_idx + entry.compressed_size) {
+            Some(data) => data,
+            None => return Err(invalid("Truncated zip file")),
+        };
+        let contents = match entry.method {
+            STORED => data.to_vec(),
+            DEFLATED => inflate(data).map_err(|error| invalid(&error))?,
+            method => return Err(invalid(&format!(
+                "Unsupported compression method {} for {}", method, name))),
+        };
+        if contents.len() != entry.size {
+            return Err(invalid(&format!("{} has the wrong size", name)));
+        }
+        Ok(Some(contents))
+    }
+
+    /*
+     * Returns the value of an attribute in the main section of the
+     * jar's manifest, e.g. "Main-Class".
+     */
+    pub fn manifest_attribute(&self, attribute: &str)
+            -> io::Result<Option<String>> {
+        let manifest = match self.read("META-INF/MANIFEST.MF")? {
+            Some(manifest) => String::from_utf8_lossy(&manifest).into_owned(),
+            None => return Ok(None),
+        };
+
+        // Long values continue on lines that begin with a space.
+        let mut lines: Vec<String> = Vec::new();
+        for line in manifest.lines() {
+            if line.is_empty() {
+                break;
+            }
+            match (line.strip_prefix(' '), lines.last_mut()) {
+                (Some(continuation), Some(last)) => last.push_str(continuation),
+                _ => lines.push(line.to_string()),
+            }
+        }
+        Ok(lines.iter().find_map(|line| {
+            let mut parts = line.splitn(2, ':');
+            match (parts.next(), parts.next()) {
+                (Some(name), Some(value))
+                        if name.eq_ignore_ascii_case(attribute) =>
+                    Some(value.trim().to_string()),
+                _ => None,
+            }
+        }))
+    }
+}
@@ -1,6 +1,18 @@
 pub mod attributes;
 pub mod classes;
 pub mod constants;
+pub mod descriptors;
 pub mod exceptions;
+pub mod fields;
+pub mod heap;
+mod inflate;
 pub mod interpreter;
+pub mod jar;
+pub mod loader;
 pub mod methods;
+pub mod natives;
+mod npe;
+mod reader;
+pub mod strings;
+mod threads;
+pub mod value;
@@ -0,0 +1,658 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use classes::*;
+use constants::*;
+use descriptors::*;
+use heap::*;
+use interpreter::{Operand, Verbose};
+use jar::JarFile;
+use methods::*;
+use natives::{NativeMethod, NativeRegistry};
+
+/*
+ * The parts of the Java class library that the interpreter
+ * provides itself, compiled from the sources under rt/.
+ */
+const BOOTSTRAP_CLASSES: &[(&str, &[u8])] = &[
+    ("java/io/PrintStream",
+     include_bytes!("../../rt/java/io/PrintStream.class")),
+    ("java/io/Serializable",
+     include_bytes!("../../rt/java/io/Serializable.class")),
+    ("java/lang/AbstractMethodError",
+     include_bytes!("../../rt/java/lang/AbstractMethodError.class")),
+    ("java/lang/ArithmeticException",
+     include_bytes!("../../rt/java/lang/ArithmeticException.class")),
+    ("java/lang/ArrayIndexOutOfBoundsException",
+     include_bytes!("../../rt/java/lang/ArrayIndexOutOfBoundsException.class")),
+    ("java/lang/ArrayStoreException",
+     include_bytes!("../../rt/java/lang/ArrayStoreException.class")),
+    ("java/lang/AssertionError",
+     include_bytes!("../../rt/java/lang/AssertionError.class")),
+    ("java/lang/BootstrapMethodError",
+     include_bytes!("../../rt/java/lang/BootstrapMethodError.class")),
+    ("java/lang/Class",
+     include_bytes!("../../rt/java/lang/Class.class")),
+    ("java/lang/ClassCastException",
+     include_bytes!("../../rt/java/lang/ClassCastException.class")),
+    ("java/lang/Cloneable",
+     include_bytes!("../../rt/java/lang/Cloneable.class")),
+    ("java/lang/Error",
+     include_bytes!("../../rt/java/lang/Error.class")),
+    ("java/lang/Exception",
+     include_bytes!("../../rt/java/lang/Exception.class")),
+    ("java/lang/ExceptionInInitializerError",
+     include_bytes!("../../rt/java/lang/ExceptionInInitializerError.class")),
+    ("java/lang/IllegalArgumentException",
+     include_bytes!("../../rt/java/lang/IllegalArgumentException.class")),
+    ("java/lang/IllegalMonitorStateException",
+     include_bytes!("../../rt/java/lang/IllegalMonitorStateException.class")),
+    ("java/lang/IllegalThreadStateException",
+     include_bytes!("../../rt/java/lang/IllegalThreadStateException.class")),
+    ("java/lang/IncompatibleClassChangeError",
+     include_bytes!("../../rt/java/lang/IncompatibleClassChangeError.class")),
+    ("java/lang/IndexOutOfBoundsException",
+     include_bytes!("../../rt/java/lang/IndexOutOfBoundsException.class")),
+    ("java/lang/InstantiationError",
+     include_bytes!("../../rt/java/lang/InstantiationError.class")),
+    ("java/lang/InterruptedException",
+     include_bytes!("../../rt/java/lang/InterruptedException.class")),
+    ("java/lang/LinkageError",
+     include_bytes!("../../rt/java/lang/LinkageError.class")),
+    ("java/lang/NegativeArraySizeException",
+     include_bytes!("../../rt/java/lang/NegativeArraySizeException.class")),
+    ("java/lang/NoClassDefFoundError",
+     include_bytes!("../../rt/java/lang/NoClassDefFoundError.class")),
+    ("java/lang/NoSuchFieldError",
+     include_bytes!("../../rt/java/lang/NoSuchFieldError.class")),
+    ("java/lang/NoSuchMethodError",
+     include_bytes!("../../rt/java/lang/NoSuchMethodError.class")),
+    ("java/lang/NullPointerException",
+     include_bytes!("../../rt/java/lang/NullPointerException.class")),
+    ("java/lang/Object",
+     include_bytes!("../../rt/java/lang/Object.class")),
+    ("java/lang/OutOfMemoryError",
+     include_bytes!("../../rt/java/lang/OutOfMemoryError.class")),
+    ("java/lang/Runnable",
+     include_bytes!("../../rt/java/lang/Runnable.class")),
+    ("java/lang/RuntimeException",
+     include_bytes!("../../rt/java/lang/RuntimeException.class")),
+    ("java/lang/StackOverflowError",
+     include_bytes!("../../rt/java/lang/StackOverflowError.class")),
+    ("java/lang/StackTraceElement",
+     include_bytes!("../../rt/java/lang/StackTraceElement.class")),
+    ("java/lang/String",
+     include_bytes!("../../rt/java/lang/String.class")),
+    ("java/lang/System",
+     include_bytes!("../../rt/java/lang/System.class")),
+    ("java/lang/System$SystemProperties",
+     include_bytes!("../../rt/java/lang/System$SystemProperties.class")),
+    ("java/lang/Thread",
+     include_bytes!("../../rt/java/lang/Thread.class")),
+    ("java/lang/ThreadDeath",
+     include_bytes!("../../rt/java/lang/ThreadDeath.class")),
+    ("java/lang/Throwable",
+     include_bytes!("../../rt/java/lang/Throwable.class")),
+    ("java/lang/UnsatisfiedLinkError",
+     include_bytes!("../../rt/java/lang/UnsatisfiedLinkError.class")),
+    ("java/lang/VirtualMachineError",
+     include_bytes!("../../rt/java/lang/VirtualMachineError.class")),
+    ("java/lang/invoke/DirectMethodHandle",
+     include_bytes!("../../rt/java/lang/invoke/DirectMethodHandle.class")),
+    ("java/lang/invoke/MethodHandle",
+     include_bytes!("../../rt/java/lang/invoke/MethodHandle.class")),
+    ("java/lang/invoke/MethodHandles",
+     include_bytes!("../../rt/java/lang/invoke/MethodHandles.class")),
+    ("java/lang/invoke/MethodHandles$Lookup",
+     include_bytes!("../../rt/java/lang/invoke/MethodHandles$Lookup.class")),
+    ("java/lang/invoke/MethodType",
+     include_bytes!("../../rt/java/lang/invoke/MethodType.class")),
+    ("java/lang/ref/Cleaner",
+     include_bytes!("../../rt/java/lang/ref/Cleaner.class")),
+    ("java/lang/ref/Cleaner$Cleanable",
+     include_bytes!("../../rt/java/lang/ref/Cleaner$Cleanable.class")),
+    ("java/lang/ref/Cleaner$PhantomCleanable",
+     include_bytes!("../../rt/java/lang/ref/Cleaner$PhantomCleanable.class")),
+    ("java/lang/ref/PhantomReference",
+     include_bytes!("../../rt/java/lang/ref/PhantomReference.class")),
+    ("java/lang/ref/Reference",
+     include_bytes!("../../rt/java/lang/ref/Reference.class")),
+    ("java/lang/ref/ReferenceQueue",
+     include_bytes!("../../rt/java/lang/ref/ReferenceQueue.class")),
+    ("java/lang/ref/ReferenceQueue$Null",
+     include_bytes!("../../rt/java/lang/ref/ReferenceQueue$Null.class")),
+    ("java/lang/ref/SoftReference",
+     include_bytes!("../../rt/java/lang/ref/SoftReference.class")),
+    ("java/lang/ref/WeakReference",
+     include_bytes!("../../rt/java/lang/ref/WeakReference.class")),
+    ("java/util/Properties",
+     include_bytes!("../../rt/java/util/Properties.class")),
+    ("java/util/WeakHashMap",
+     include_bytes!("../../rt/java/util/WeakHashMap.class")),
+    ("java/util/WeakHashMap$Entry",
+     include_bytes!("../../rt/java/util/WeakHashMap$Entry.class")),
+    ("java/util/concurrent/locks/LockSupport",
+     include_bytes!("../../rt/java/util/concurrent/locks/LockSupport.class")),
+];
+
+/*
+ * A class that has been loaded and linked against its
+ * superclass and superinterfaces.
+ */
+pub struct RuntimeClass {
+    pub name: &'static str,
+    // Array classes are created by the VM, and have no class file.
+    pub definition: Option<&'static Class<'static>>,
+    pub super_class: Option<&'static RuntimeClass>,
+    pub interfaces: Vec<&'static RuntimeClass>,
+    // The layout of instances, starting with inherited fields.
+    pub instance_fields: Vec<FieldSlot>,
+    // The static fields declared by this class alone.
+    pub static_fields: Vec<FieldSlot>,
+    // For array classes, the type of their components, and for
+    // arrays of references, the class of their components as well.
+    pub component_type: Option<FieldType>,
+    pub component_class: Option<&'static RuntimeClass>,
+    // For subclasses of java/lang/ref/Reference, the kind of reference.
+    pub reference_kind: Option<ReferenceKind>,
+    // Whether the class or a superclass overrides Object.finalize.
+    pub has_finalizer: bool,
+    /*
+     * The implementations of the class's native methods, keyed by
+     * name and descriptor, which are looked up in the registry when
+     * the class is linked; None records that none was registered.
+     */
+    pub natives: HashMap<(&'static str, &'static str), Option<NativeMethod>>,
+}
+
+/*
+ * The kinds of reference objects the collector treats specially,
+ * from the strongest to the weakest.
+ */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ReferenceKind {
+    Soft,
+    Weak,
+    Phantom,
+}
+
+#[derive(Clone)]
+pub struct FieldSlot {
+    pub class_name: &'static str,
+    pub name: &'static str,
+    pub descriptor: &'static str,
+    pub field_type: FieldType,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum InitState {
+    Uninitialized,
+    // Being initialized by the thread with the given id.
+    InProgress(u32),
+    Initialized,
+    // The static initializer of the class threw an exception.
+    Erroneous,
+}
+
+/*
+ * The values of the static fields of a class, which are
+ * created when the class is prepared, along with the progress
+ * of the class's initialization.
+ */
+pub struct ClassStatics {
+    pub values: Vec<Operand>,
+    pub init_state: InitState,
+}
+
+impl RuntimeClass {
+    /*
+     * Returns the index of the given instance field within the
+     * layout of this class; as subclasses extend the layout of their
+     * superclass, the index is valid for instances of subclasses too.
+     * Later declarations hide earlier ones of the same name, so the
+     * layout is searched from the end.
+     */
+    pub fn instance_field_idx(&self, name: &str, descriptor: &str)
+            -> Option<usize> {
+        self.instance_fields.iter()
+            .rposition(|f| f.name == name && f.descriptor == descriptor)
+    }
+
+    /*
+     * Returns the method with the given name and descriptor
+     * declared by this class itself; array classes declare none.
+     */
+    pub fn declared_method(&self, name: &str, descriptor: &str)
+            -> Option<&'static Method<'static>> {
+        self.definition.and_then(|d| d.method(name, descriptor))
+    }
+
+    /*
+     * Resolves a static field by searching this class, then its
+     * superinterfaces and then its superclasses, as described in
+     * section 5.4.3.2 of the JVM specification; yields the class that
+     * declares the field and its index among that class's statics.
+     */
+    pub fn find_static_field(&'static self, name: &str, descriptor: &str)
+            -> Option<(&'static RuntimeClass, usize)> {
+        if let Some(idx) = self.static_fields.iter()
+                .position(|f| f.name == name && f.descriptor == descriptor) {
+            return Some((self, idx));
+        }
+        self.interfaces.iter()
+            .find_map(|i| i.find_static_field(name, descriptor))
+            .or_else(|| self.super_class
+                     .and_then(|c| c.find_static_field(name, descriptor)))
+    }
+
+    pub fn is_interface(&self) -> bool {
+        self.definition.is_some_and(|d| d.is_interface())
+    }
+
+    pub fn is_abstract(&self) -> bool {
+        self.definition.is_some_and(|d| d.access_flags & ACC_ABSTRACT != 0)
+    }
+
+    pub fn is_array(&self) -> bool {
+        self.component_type.is_some()
+    }
+
+    /*
+     * Searches this class, its superclasses and then its
+     * superinterfaces for a method with the given name and descriptor,
+     * as described by the method resolution and selection procedures
+     * in sections 5.4.3.3 and 5.4.6 of the JVM specification.
+     */
+    pub fn find_method(&'static self, name: &str, descriptor: &str)
+            -> Option<(&'static RuntimeClass, &'static Method<'static>)> {
+        let mut current = Some(self);
+        while let Some(class) = current {
+            if let Some(method) = class.declared_method(name, descriptor) {
+                return Some((class, method));
+            }
+            current = class.super_class;
+        }
+        self.find_interface_method(name, descriptor)
+    }
+
+    /*
+     * Searches the superinterfaces of this class (and those of its
+     * superclasses) for a non-abstract method, which is how default
+     * methods are selected.
+     */
+    fn find_interface_method(&'static self, name: &str, descriptor: &str)
+            -> Option<(&'static RuntimeClass, &'static Method<'static>)> {
+        let mut candidates: Vec<&'static RuntimeClass> = vec![self];
+        let mut abstract_match = None;
+        while let Some(class) = candidates.pop() {
+            if class.is_interface() {
+                if let Some(method) = class.declared_method(name,
+                                                            descriptor) {
+                    if !method.is_abstract() {
+                        return Some((class, method));
+                    }
+                    abstract_match = abstract_match.or(Some((class, method)));
+                }
+            }
+            candidates.extend(class.interfaces.iter());
+            candidates.extend(class.super_class);
+        }
+        abstract_match
+    }
+
+    pub fn is_subclass_of(&self, other: &RuntimeClass) -> bool {
+        let mut current = Some(self);
+        while let Some(class) = current {
+            if class.name == other.name {
+                return true;
+            }
+            current = class.super_class;
+        }
+        false
+    }
+
+    /*
+     * Determines whether a reference to an instance of this class
+     * may be stored in a variable of the given class, per the rules
+     * for checkcast in section 6.5 of the JVM specification. Array
+     * classes extend Object and implement Cloneable and Serializable,
+     * so only assignment between two array types needs special care.
+     */
+    pub fn is_assignable_to(&self, other: &RuntimeClass) -> bool {
+        if self.name == other.name {
+            return true;
+        }
+        if let (Some(component), Some(other_component)) =
+                (&self.component_type, &other.component_type) {
+            return match (self.component_class, other.component_class) {
+                (Some(class), Some(other_class)) =>
+                    class.is_assignable_to(other_class),
+                _ => component == other_component,
+            };
+        }
+        let mut candidates: Vec<&RuntimeClass> = vec![self];
+        while let Some(class) = candidates.pop() {
+            if class.name == other.name {
+                return true;
+            }
+            candidates.extend(class.interfaces.iter());
+            candidates.extend(class.super_class);
+        }
+        false
+    }
+}
+
+/*
+ * Locates and parses class files on demand, caching
+ * each class the first time it is loaded.
+ *
+ * Classes are never unloaded, so the buffer backing each
+ * class file, and the classes parsed and linked from it, are
+ * deliberately leaked; this gives loaded classes a 'static lifetime,
+ * which lets interpreter frames and heap objects refer to them freely.
+ */
+pub struct ClassLoader {
+    // The directories and jar files searched, in order, for classes.
+    class_path: Vec<ClassPathEntry>,
+    classes: HashMap<String, &'static RuntimeClass>,
+    statics: HashMap<&'static str, ClassStatics>,
+    natives: NativeRegistry,
+    verbose: Verbose,
+    // When the VM started, which -verbose:class logs relative to.
+    started: Instant,
+}
+
+/*
+ * Jar files are opened (and read) when the loader is created; as
+ * in the JDK, class path entries that can't be read are ignored.
+ */
+enum ClassPathEntry {
+    Directory(PathBuf),
+    Jar(JarFile),
+}
+
+impl ClassLoader {
+    pub fn new(class_path: Vec<PathBuf>, natives: NativeRegistry,
+               verbose: Verbose, started: Instant) -> ClassLoader {
+        let class_path = class_path.into_iter()
+            .filter_map(|path| if path.is_file() {
+                JarFile::open(&path).ok().map(ClassPathEntry::Jar)
+            } else {
+                Some(ClassPathEntry::Directory(path))
+            })
+            .collect();
+        ClassLoader {
+            class_path,
+            classes: HashMap::new(),
+            statics: HashMap::new(),
+            natives,
+            verbose,
+            started,
+        }
+    }
+
+    pub fn statics(&self, class: &RuntimeClass) -> &ClassStatics {
+        match self.statics.get(class.name) {
+            Some(statics) => statics,
+            None => panic!("[ERROR] {} has not been prepared.", class.name),
+        }
+    }
+
+    /*
+     * The objects referred to by the static fields of the classes
+     * loaded so far, which are found from the types of the fields.
+     */
+    pub fn static_references(&self) -> Vec<Handle> {
+        let mut references = Vec::new();
+        for class_name in self.classes.keys() {
+            references.extend(self.static_references_of(class_name));
+        }
+        references
+    }
+
+    // The objects referred to by the static fields of the given class.
+    pub fn static_references_of(&self, class_name: &str) -> Vec<Handle> {
+        let mut references = Vec::new();
+        let (class, values) = match (self.classes.get(class_name),
+                                     self.statics.get(class_name)) {
+            (Some(class), Some(statics)) => (class, &statics.values),
+            _ => return references,
+        };
+        for (field, value) in class.static_fields.iter().zip(values.iter()) {
+            if let (true, &Operand::Ref(object)) =
+                    (field.field_type.is_reference(), value) {
+                references.push(object);
+            }
+        }
+        references
+    }
+
+    pub fn statics_mut(&mut self, class: &RuntimeClass)
+            -> &mut ClassStatics {
+        match self.statics.get_mut(class.name) {
+            Some(statics) => statics,
+            None => panic!("[ERROR] {} has not been prepared.", class.name),
+        }
+    }
+
+    /*
+     * Returns the class with the given binary name
+     * (e.g., "com/acme/Main", or "[I" for an array class), or None
+     * if no class file for it (or, for array classes, for its
+     * element class) exists in the bootstrap classes or on
+     * the class path.
+     */
+    pub fn load(&mut self, class_name: &str)
+            -> Option<&'static RuntimeClass> {
+        if let Some(class) = self.classes.get(class_name) {
+            return Some(*class);
+        }
+        if class_name.starts_with('[') {
+            return self.create_array_class(class_name);
+        }
+
+        let (class_file, source) = self.find_class_file(class_name)?;
+        let class_file: &'static ClassFile = Box::leak(Box::new(class_file));
+        let definition: &'static Class<'static> =
+            Box::leak(Box::new(class_file.parse(self.verbose.trace)));
+        if definition.name != class_name {
+            panic!("[ERROR] Expected {} to define class {}, \
+                    but it defines {}.",
+                   class_file.class_name, class_name, definition.name);
+        }
+
+        let class = self.link(definition);
+        self.classes.insert(class_name.to_string(), class);
+        if self.verbose.class {
+            println!("[{:.3}s][info][class,load] {} source: {}",
+                     self.started.elapsed().as_secs_f64(),
+                     class_name.replace('/', "."), source);
+        }
+        Some(class)
+    }
+
+    /*
+     * Finds the class file for a class, along with a description
+     * of where it was found, as -verbose:class reports it.
+     */
+    fn find_class_file(&self, class_name: &str)
+            -> Option<(ClassFile, String)> {
+        if let Some(&(_, bytecodes)) = BOOTSTRAP_CLASSES.iter()
+                .find(|&&(name, _)| name == class_name) {
+            return Some((ClassFile::from_bytes(class_name, bytecodes),
+                         "jrt:/java.base".to_string()));
+        }
+
+        let file_name = format!("{}.class", class_name);
+        self.class_path.iter().find_map(|entry| match *entry {
+            ClassPathEntry::Directory(ref directory) => {
+                let path = directory.join(class_name);
+                if !path.with_extension("class").is_file() {
+                    return None;
+                }
+                Some((ClassFile::new(&path.to_string_lossy()),
+                      format!("{}/", file_url(directory))))
+            },
+            ClassPathEntry::Jar(ref jar) => match jar.read(&file_name) {
+                Ok(Some(bytecodes)) =>
+                    Some((ClassFile::from_bytes(class_name, &bytecodes),
+                          file_url(&jar.path))),
+                Ok(None) => None,
+                Err(error) => panic!("[ERROR] Unable to read {} from {}: {}",
+                                     file_name, jar.path.display(), error),
+            },
+        })
+    }
+
+    /*
+     * Loads the superclass and superinterfaces of a class,
+     * lays out the fields of its instances, prepares its
+     * static fields, and links its native methods.
+     */
+    fn link(&mut self, definition: &'static Class<'static>)
+            -> &'static RuntimeClass {
+        let super_class = definition.super_class
+            .map(|name| self.load_required(name));
+        let interfaces = definition.interfaces.iter()
+            .map(|name| self.load_required(name)).collect();
+
+        let mut instance_fields = match super_class {
+            Some(super_class) => super_class.instance_fields.clone(),
+            None => Vec::new(),
+        };
+        let mut static_fields = Vec::new();
+        let mut static_values = Vec::new();
+        for field in definition.fields.iter() {
+            let field_type = match FieldType::parse(field.descriptor) {
+                Ok(field_type) => field_type,
+                Err(error) => panic!("[ERROR] Malformed field \
+                                      descriptor: {}", error),
+            };
+            let slot = FieldSlot {
+                class_name: definition.name,
+                name: field.name,
+                descriptor: field.descriptor,
+                field_type,
+            };
+            if !field.is_static() {
+                instance_fields.push(slot);
+                continue;
+            }
+            static_values.push(match field.constant_value_idx() {
+                Some(idx) => constant_value(definition, idx),
+                None => default_value(&slot.field_type),
+            });
+            static_fields.push(slot);
+        }
+        self.statics.insert(definition.name, ClassStatics {
+            values: static_values,
+            init_state: InitState::Uninitialized,
+        });
+
+        let reference_kind = match definition.name {
+            "java/lang/ref/SoftReference" => Some(ReferenceKind::Soft),
+            "java/lang/ref/WeakReference" => Some(ReferenceKind::Weak),
+            "java/lang/ref/PhantomReference" => Some(ReferenceKind::Phantom),
+            _ => super_class.and_then(|class| class.reference_kind),
+        };
+        let has_finalizer = super_class.is_some_and(|class| {
+            class.has_finalizer
+                || definition.method("finalize", "()V").is_some()
+        });
+        let natives = definition.methods.iter()
+            .filter(|method| method.is_native())
+            .map(|method| ((method.name, method.descriptor),
+                           self.natives.lookup(definition.name, method.name,
+                                               method.descriptor)))
+            .collect();
+
+        Box::leak(Box::new(RuntimeClass {
+            name: definition.name,
+            definition: Some(definition),
+            super_class,
+            interfaces,
+            instance_fields,
+            static_fields,
+            component_type: None,
+            component_class: None,
+            reference_kind,
+            has_finalizer,
+            natives,
+        }))
+    }
+
+    /*
+     * Creates the array class with the given name, loading the
+     * class of its components first, as described in section 5.3.3
+     * of the JVM specification.
+     */
+    fn create_array_class(&mut self, class_name: &str)
+            -> Option<&'static RuntimeClass> {
+        let component_type = match FieldType::parse(&class_name[1..]) {
+            Ok(component_type) => component_type,
+            Err(error) => panic!("[ERROR] Malformed array class name: {}",
+                                 error),
+        };
+        let component_class = match component_type {
+            FieldType::Object(ref name) => Some(self.load(name)?),
+            FieldType::Array(_) => Some(self.load(&class_name[1..])?),
+            _ => None,
+        };
+
+        let name: &'static str =
+            Box::leak(class_name.to_string().into_boxed_str());
+        let class: &'static RuntimeClass = Box::leak(Box::new(RuntimeClass {
+            name,
+            definition: None,
+            super_class: Some(self.load_required("java/lang/Object")),
+            interfaces: vec![self.load_required("java/lang/Cloneable"),
+                             self.load_required("java/io/Serializable")],
+            instance_fields: Vec::new(),
+            static_fields: Vec::new(),
+            component_type: Some(component_type),
+            component_class,
+            reference_kind: None,
+            has_finalizer: false,
+            natives: HashMap::new(),
+        }));
+        self.classes.insert(class_name.to_string(), class);
+        self.statics.insert(name, ClassStatics {
+            values: Vec::new(),
+            init_state: InitState::Initialized,
+        });
+        Some(class)
+    }
+
+    fn load_required(&mut self, class_name: &str) -> &'static RuntimeClass {
+        match self.load(class_name) {
+            Some(class) => class,
+            None => panic!("[ERROR] Unable to load class {}.", class_name),
+        }
+    }
+}
+
+/*
+ * Reads the initial value of a static field from the constant pool.
+ */
+fn constant_value(definition: &Class, idx: u16) -> Operand {
+    match definition.constant_pool.get(&idx) {
+        Some(&ConstantPoolEntry::Integer(value)) => Operand::Int(value),
+        Some(&ConstantPoolEntry::Float(value)) => Operand::Float(value),
+        Some(&ConstantPoolEntry::Long(value)) => Operand::Long(value),
+        Some(&ConstantPoolEntry::Double(value)) => Operand::Double(value),
+        /*
+         * Creating a String takes the heap, so string constants are
+         * assigned by the interpreter once the class is initialized.
+         */
+        Some(&ConstantPoolEntry::String(_)) => Operand::Null,
+        _ => panic!("[ERROR] Expected constant value in constant pool \
+                     at index {}.", idx),
+    }
+}
+
+fn file_url(path: &Path) -> String {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    format!("file:{}", path.display())
+}
@@ -1,61 +1,99 @@
 use std::collections::HashMap;
+use std::fmt;
 
 use attributes::*;
 use constants::*;
+use reader::*;
+
+pub const ACC_PUBLIC: u16 = 0x0001;
+pub const ACC_PRIVATE: u16 = 0x0002;
+pub const ACC_PROTECTED: u16 = 0x0004;
+pub const ACC_STATIC: u16 = 0x0008;
+pub const ACC_FINAL: u16 = 0x0010;
+pub const ACC_SYNCHRONIZED: u16 = 0x0020;
+pub const ACC_NATIVE: u16 = 0x0100;
+pub const ACC_ABSTRACT: u16 = 0x0400;
 
 pub struct Method<'a> {
     pub access_flags: u16,
     pub name_idx: u16,
     pub name: &'a str,
     pub descriptor_idx: u16,
+    pub descriptor: &'a str,
     pub attrs_count: u16,
     pub attributes: HashMap<&'a str, Attribute<'a>>,
 }
 
 impl<'a> Method<'a> {
-    pub fn from_bytecodes(bytecodes: &'a Vec<u8>, byte_idx: &mut usize,
+    pub fn from_bytecodes(bytecodes: &'a [u8], byte_idx: &mut usize,
                           constant_pool: &HashMap<u16,
                           ConstantPoolEntry<'a>>) -> Method<'a> {
 
-        let access_flags = (bytecodes[*byte_idx]
-                            + bytecodes[*byte_idx + 1]) as u16;
-        let name_idx = (bytecodes[*byte_idx + 2]
-                        + bytecodes[*byte_idx + 3]) as u16;
-        let descriptor_idx = (bytecodes[*byte_idx + 4]
-                              + bytecodes[*byte_idx + 5]) as u16;
-        let attrs_count = (bytecodes[*byte_idx + 6]
-                           + bytecodes[*byte_idx + 7]) as u16;
-        *byte_idx = *byte_idx + 8;
+        let access_flags = read_u16(bytecodes, byte_idx);
+        let name_idx = read_u16(bytecodes, byte_idx);
+        let descriptor_idx = read_u16(bytecodes, byte_idx);
+        let attrs_count = read_u16(bytecodes, byte_idx);
 
         let name = match constant_pool.get(&name_idx) {
-            Some(&ConstantPoolEntry::Utf8(ref e)) => e.utf8_str,
+            Some(ConstantPoolEntry::Utf8(e)) => e.utf8_str,
             Some(_) | None => panic!("[ERROR] Can't find name at index {} \
                             in constant pool.", name_idx),
         };
 
+        let descriptor = match constant_pool.get(&descriptor_idx) {
+            Some(ConstantPoolEntry::Utf8(e)) => e.utf8_str,
+            Some(_) | None => panic!("[ERROR] Can't find descriptor at \
+                            index {} in constant pool.", descriptor_idx),
+        };
+
         let mut attributes = HashMap::new();
         for _ in 0 .. attrs_count {
             let attr = Attribute::from_bytecodes(
                 bytecodes, byte_idx, constant_pool);
-            let attr_name = match attr {
-                Attribute::Code(ref s) => s.attr_name,
-                Attribute::LineNumberTable(ref s) => s.attr_name,
-                Attribute::SourceFile(ref s) => s.attr_name,
-            };
-            attributes.insert(attr_name, attr);
+            attributes.insert(attr.name(), attr);
         };
 
         Method {
-            access_flags: access_flags,
-            name_idx: name_idx,
-            name: name,
-            descriptor_idx: descriptor_idx,
-            attrs_count: attrs_count,
-            attributes: attributes,
+            access_flags,
+            name_idx,
+            name,
+            descriptor_idx,
+            descriptor,
+            attrs_count,
+            attributes,
         }
     }
 
-    pub fn to_string(&self) -> String {
+    pub fn is_public(&self) -> bool {
+        self.access_flags & ACC_PUBLIC != 0
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.access_flags & ACC_STATIC != 0
+    }
+
+    pub fn is_synchronized(&self) -> bool {
+        self.access_flags & ACC_SYNCHRONIZED != 0
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.access_flags & ACC_NATIVE != 0
+    }
+
+    pub fn is_abstract(&self) -> bool {
+        self.access_flags & ACC_ABSTRACT != 0
+    }
+
+    pub fn code(&self) -> Option<&CodeAttribute<'a>> {
+        match self.attributes.get("Code") {
+            Some(Attribute::Code(s)) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> fmt::Display for Method<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut string_rep = format!("Method:\n\
                 \t- access_flags=0x{:x}\n\
                 \t- name_idx={}\n\
@@ -64,11 +102,10 @@ impl<'a> Method<'a> {
                 self.access_flags, self.name_idx, self.descriptor_idx,
                 self.attrs_count);
 
-        for (_, attr) in self.attributes.iter() {
-            string_rep = string_rep + &format!("\tAttribute:{}",
-                                               attr.to_string());
+        for attr in self.attributes.values() {
+            string_rep += &format!("\tAttribute:{}", attr);
         }
 
-        string_rep
+        write!(f, "{}", string_rep)
     }
 }
@@ -0,0 +1,364 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use heap::Handle;
+use interpreter::{Env, JavaThrowable};
+use strings;
+use value::Value;
+
+/*
+ * The Rust implementation of a Java native method. It's given the
+ * method's arguments, preceded by the receiver for instance methods,
+ * and returns a value of the method's return type (Value::Void for
+ * void methods), or throws an exception. As any of the VM's threads
+ * may call it, it must be safe to share between them.
+ */
+pub type NativeMethod = Arc<dyn Fn(&mut Env, &[Value])
+                                -> Result<Value, JavaThrowable>
+                                + Send + Sync>;
+
+/*
+ * The native methods available to guest code, keyed by the class,
+ * name and descriptor of the method they implement.
+ */
+pub struct NativeRegistry {
+    methods: HashMap<(String, String, String), NativeMethod>,
+}
+
+impl Default for NativeRegistry {
+    fn default() -> Self {
+        NativeRegistry::new()
+    }
+}
+
+impl NativeRegistry {
+    // Creates a registry holding the natives of the class library.
+    pub fn new() -> NativeRegistry {
+        let mut registry = NativeRegistry { methods: HashMap::new() };
+        registry.register("java/io/PrintStream", "write",
+                          "(Ljava/lang/String;)V", print_stream_write);
+        registry.register("java/lang/Class", "desiredAssertionStatus", "()Z",
+                          class_desired_assertion_status);
+        registry.register("java/lang/Object", "getClass",
+                          "()Ljava/lang/Class;", object_get_class);
+        registry.register("java/lang/Object", "hashCode", "()I",
+                          object_hash_code);
+        registry.register("java/lang/Object", "notify", "()V", object_notify);
+        registry.register("java/lang/Object", "notifyAll", "()V",
+                          object_notify_all);
+        registry.register("java/lang/Object", "wait", "(J)V", object_wait);
+        registry.register("java/lang/String", "intern",
+                          "()Ljava/lang/String;", string_intern);
+        for descriptor in ["(I)Ljava/lang/String;", "(J)Ljava/lang/String;",
+                           "(F)Ljava/lang/String;", "(D)Ljava/lang/String;"]
+                .iter() {
+            registry.register("java/lang/String", "valueOf", descriptor,
+                              string_value_of);
+        }
+        registry.register("java/lang/System", "clearProperty",
+                          "(Ljava/lang/String;)Ljava/lang/String;",
+                          system_clear_property);
+        registry.register("java/lang/System", "exit", "(I)V", system_exit);
+        registry.register("java/lang/System", "gc", "()V", system_gc);
+        registry.register("java/lang/System", "getProperty",
+                          "(Ljava/lang/String;)Ljava/lang/String;",
+                          system_get_property);
+        registry.register("java/lang/System", "getenv",
+                          "(Ljava/lang/String;)Ljava/lang/String;",
+                          system_getenv);
+        registry.register("java/lang/System", "propertyNames",
+                          "()[Ljava/lang/String;", system_property_names);
+        registry.register("java/lang/System", "setProperty",
+                          "(Ljava/lang/String;Ljava/lang/String;)\
+                           Ljava/lang/String;",
+                          system_set_property);
+        registry.register("java/lang/Thread", "currentThread",
+                          "()Ljava/lang/Thread;", thread_current_thread);
+        registry.register("java/lang/Thread", "holdsLock",
+                          "(Ljava/lang/Object;)Z", thread_holds_lock);
+        registry.register("java/lang/Thread", "interrupt0", "()V",
+                          thread_interrupt);
+        registry.register("java/lang/Thread", "isAlive", "()Z",
+                          thread_is_alive);
+        registry.register("java/lang/Thread", "isInterrupted", "(Z)Z",
+                          thread_is_interrupted);
+        registry.register("java/lang/Thread", "join", "(J)V", thread_join);
+        registry.register("java/lang/Thread", "sleep", "(J)V", thread_sleep);
+        registry.register("java/lang/Thread", "start0", "()V", thread_start);
+        registry.register("java/lang/Thread", "yield", "()V", thread_yield);
+        registry.register("java/util/concurrent/locks/LockSupport", "park0",
+                          "(J)V", lock_support_park);
+        registry.register("java/util/concurrent/locks/LockSupport", "unpark",
+                          "(Ljava/lang/Thread;)V", lock_support_unpark);
+        registry
+    }
+
+    /*
+     * Registers the implementation of a native method, replacing
+     * any that was registered for it before.
+     */
+    pub fn register<F>(&mut self, class_name: &str, name: &str,
+                       descriptor: &str, method: F)
+            where F: Fn(&mut Env, &[Value]) -> Result<Value, JavaThrowable>
+                     + Send + Sync + 'static {
+        self.methods.insert((class_name.to_string(), name.to_string(),
+                             descriptor.to_string()), Arc::new(method));
+    }
+
+    pub fn lookup(&self, class_name: &str, name: &str, descriptor: &str)
+            -> Option<NativeMethod> {
+        self.methods.get(&(class_name.to_string(), name.to_string(),
+                           descriptor.to_string())).cloned()
+    }
+}
+
+fn receiver(args: &[Value]) -> Handle {
+    match args.first() {
+        Some(&Value::Ref(receiver)) => receiver,
+        _ => panic!("[ERROR] Expected a receiver as the first argument \
+                     of a native instance method."),
+    }
+}
+
+fn print_stream_write(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    let string = match args[1] {
+        Value::Ref(string) => env.string_value(string),
+        _ => panic!("[ERROR] PrintStream.write expected a String."),
+    };
+    // As in PrintStream, failures to write are not reported.
+    let _ = match env.get_field(receiver(args), "fd", "I") {
+        Value::Int(2) => env.stderr().write_all(string.as_bytes()),
+        _ => env.stdout().write_all(string.as_bytes()),
+    };
+    Ok(Value::Void)
+}
+
+fn class_desired_assertion_status(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    let name = match env.get_field(receiver(args), "name",
+                                   "Ljava/lang/String;") {
+        Value::Ref(name) => env.string_value(name),
+        _ => panic!("[ERROR] Class mirror has no name."),
+    };
+    Ok(Value::Boolean(env.desired_assertion_status(&name)))
+}
+
+fn object_get_class(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    Ok(Value::Ref(env.class_of(receiver(args))?))
+}
+
+fn object_hash_code(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    Ok(Value::Int(env.heap().get(receiver(args)).header.identity_hash))
+}
+
+fn object_notify(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    env.notify(receiver(args), false)?;
+    Ok(Value::Void)
+}
+
+fn object_notify_all(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    env.notify(receiver(args), true)?;
+    Ok(Value::Void)
+}
+
+fn object_wait(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    let timeout = match args[1] {
+        Value::Long(timeout) => timeout,
+        _ => panic!("[ERROR] Object.wait expected a long timeout."),
+    };
+    env.wait(receiver(args), timeout)?;
+    Ok(Value::Void)
+}
+
+fn string_intern(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    Ok(Value::Ref(env.intern_string(receiver(args))))
+}
+
+fn string_value_of(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    let value = match args[0] {
+        Value::Int(value) => value.to_string(),
+        Value::Long(value) => value.to_string(),
+        Value::Float(value) => strings::float_to_string(value),
+        Value::Double(value) => strings::double_to_string(value),
+        _ => panic!("[ERROR] String.valueOf expected a primitive."),
+    };
+    Ok(Value::Ref(env.new_string(&value)?))
+}
+
+/*
+ * Converts a String argument, which may be null, to a Rust string.
+ */
+fn string_arg(env: &Env, arg: Value) -> Option<String> {
+    match arg {
+        Value::Ref(string) => Some(env.string_value(string)),
+        Value::Null => None,
+        _ => panic!("[ERROR] Expected a String argument, not {:?}.", arg),
+    }
+}
+
+fn string_or_null(env: &mut Env, value: Option<String>)
+        -> Result<Value, JavaThrowable> {
+    match value {
+        Some(value) => Ok(Value::Ref(env.new_string(&value)?)),
+        None => Ok(Value::Null),
+    }
+}
+
+// As in System.checkKey.
+fn property_key(env: &mut Env, arg: Value) -> Result<String, JavaThrowable> {
+    match string_arg(env, arg) {
+        Some(ref key) if key.is_empty() =>
+            Err(env.throw("java/lang/IllegalArgumentException",
+                          "key can't be empty")),
+        Some(key) => Ok(key),
+        None => Err(env.throw("java/lang/NullPointerException",
+                              "key can't be null")),
+    }
+}
+
+fn system_clear_property(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    let key = property_key(env, args[0])?;
+    let previous = env.clear_system_property(&key);
+    string_or_null(env, previous)
+}
+
+fn system_exit(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    match args[0] {
+        Value::Int(status) => Err(env.exit(status)),
+        _ => panic!("[ERROR] System.exit expected an int."),
+    }
+}
+
+fn system_gc(env: &mut Env, _args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    env.collect_garbage();
+    Ok(Value::Void)
+}
+
+fn system_get_property(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    let key = property_key(env, args[0])?;
+    let value = env.system_property(&key).map(|value| value.to_string());
+    string_or_null(env, value)
+}
+
+fn system_getenv(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    let name = match string_arg(env, args[0]) {
+        Some(name) => name,
+        None => return Err(env.throw("java/lang/NullPointerException",
+                                     "name can't be null")),
+    };
+    let value = env.environment_variable(&name).map(|value| value.to_string());
+    string_or_null(env, value)
+}
+
+fn system_property_names(env: &mut Env, _args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    let names = env.system_property_names();
+    Ok(Value::Ref(env.new_string_array(&names)?))
+}
+
+fn system_set_property(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    let key = property_key(env, args[0])?;
+    let value = match string_arg(env, args[1]) {
+        Some(value) => value,
+        None => return Err(env.throw("java/lang/NullPointerException",
+                                     "value can't be null")),
+    };
+    let previous = env.set_system_property(&key, &value);
+    string_or_null(env, previous)
+}
+
+fn thread_current_thread(env: &mut Env, _args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    Ok(Value::Ref(env.current_thread()?))
+}
+
+fn thread_holds_lock(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    match args[0] {
+        Value::Ref(object) => Ok(Value::Boolean(env.holds_lock(object))),
+        _ => Err(env.throw("java/lang/NullPointerException", "obj")),
+    }
+}
+
+fn thread_interrupt(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    env.interrupt(receiver(args));
+    Ok(Value::Void)
+}
+
+fn thread_is_alive(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    Ok(Value::Boolean(env.is_alive(receiver(args))))
+}
+
+fn thread_is_interrupted(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    let clear = match args[1] {
+        Value::Boolean(clear) => clear,
+        _ => panic!("[ERROR] Thread.isInterrupted expected a boolean."),
+    };
+    Ok(Value::Boolean(env.is_interrupted(receiver(args), clear)))
+}
+
+fn thread_join(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    let millis = match args[1] {
+        Value::Long(millis) => millis,
+        _ => panic!("[ERROR] Thread.join expected a long timeout."),
+    };
+    env.join(receiver(args), millis)?;
+    Ok(Value::Void)
+}
+
+fn thread_sleep(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    match args[0] {
+        Value::Long(millis) => env.sleep(millis)?,
+        _ => panic!("[ERROR] Thread.sleep expected a long."),
+    }
+    Ok(Value::Void)
+}
+
+fn thread_start(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    env.start_thread(receiver(args))?;
+    Ok(Value::Void)
+}
+
+fn thread_yield(env: &mut Env, _args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    env.yield_now();
+    Ok(Value::Void)
+}
+
+fn lock_support_park(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    match args[0] {
+        Value::Long(nanos) => env.park(nanos)?,
+        _ => panic!("[ERROR] LockSupport.park expected a long."),
+    }
+    Ok(Value::Void)
+}
+
+// Unparking null does nothing.
+fn lock_support_unpark(env: &mut Env, args: &[Value])
+        -> Result<Value, JavaThrowable> {
+    if let Value::Ref(thread) = args[0] {
+        env.unpark(thread);
+    }
+    Ok(Value::Void)
+}
@@ -0,0 +1,560 @@
+use std::collections::HashSet;
+
+use attributes::*;
+use classes::*;
+use descriptors::*;
+use methods::*;
+use reader::*;
+
+/*
+ * Builds the detail message of a NullPointerException thrown
+ * by the instruction at the given pc, in the style of the helpful
+ * messages described by JEP 358: the action that failed, followed,
+ * where possible, by a description of where the null came from,
+ * e.g. 'Cannot read field "next" because "<local1>.head" is null'.
+ */
+pub fn describe(definition: &Class, method: &Method, code: &CodeAttribute,
+                pc: usize) -> String {
+    let analysis = Analysis::new(definition, method, code);
+    let bytecodes = code.code_slice;
+    let (action, null_slot) = match bytecodes[pc] {
+        opcode @ 0x2e ..= 0x35 => (format!(
+            "Cannot load from {} array", array_kind(opcode - 0x2e)), 1),
+        opcode @ 0x4f ..= 0x56 => {
+            let kind = opcode - 0x4f;
+            let value_words = if kind == 1 || kind == 3 { 2 } else { 1 };
+            (format!("Cannot store to {} array", array_kind(kind)),
+             value_words + 1)
+        },
+        0xbe => ("Cannot read the array length".to_string(), 0),
+        0xbf => ("Cannot throw exception".to_string(), 0),
+        0xb4 => (format!("Cannot read field \"{}\"",
+                         analysis.member_ref(pc).name), 0),
+        0xb5 => {
+            let field_ref = analysis.member_ref(pc);
+            (format!("Cannot assign field \"{}\"", field_ref.name),
+             field_words(field_ref.descriptor))
+        },
+        0xb6 | 0xb7 | 0xb9 => {
+            let method_ref = analysis.member_ref(pc);
+            (format!("Cannot invoke \"{}\"", analysis.method_name(pc)),
+             param_words(method_ref.descriptor))
+        },
+        0xc2 => ("Cannot enter synchronized block".to_string(), 0),
+        0xc3 => ("Cannot exit synchronized block".to_string(), 0),
+        opcode => panic!("[ERROR] Instruction 0x{:x} does not throw \
+                          NullPointerException.", opcode),
+    };
+
+    let source_pc = match analysis.stack_before(pc) {
+        Some(stack) if stack.len() > null_slot =>
+            stack[stack.len() - 1 - null_slot],
+        _ => None,
+    };
+    let source_pc = match source_pc {
+        Some(source_pc) => source_pc,
+        None => return action,
+    };
+    if is_invoke(bytecodes[source_pc]) {
+        return format!("{} because the return value of \"{}\" is null",
+                       action, analysis.method_name(source_pc));
+    }
+    let mut cause = String::new();
+    if analysis.print_source(&mut cause, source_pc, MAX_DETAIL) {
+        format!("{} because \"{}\" is null", action, cause)
+    } else {
+        action
+    }
+}
+
+// How deeply nested an expression may be described.
+const MAX_DETAIL: usize = 5;
+
+/*
+ * For each word on the operand stack, the pc of the instruction
+ * that pushed it, or None when different paths through the method
+ * disagree (or the word is the exception passed to a handler).
+ */
+type Stack = Vec<Option<usize>>;
+
+struct Analysis<'c, 'a: 'c> {
+    definition: &'c Class<'a>,
+    method: &'c Method<'a>,
+    bytecodes: &'a [u8],
+    // The stack before each instruction, for those reachable.
+    stacks: Vec<Option<Stack>>,
+    // The local variable slots that are stored to anywhere.
+    written_slots: HashSet<usize>,
+}
+
+impl<'c, 'a> Analysis<'c, 'a> {
+    /*
+     * Simulates the effect of each instruction on the operand stack,
+     * following every branch until the stacks at each instruction no
+     * longer change, much as the verifier's type inference does.
+     */
+    fn new(definition: &'c Class<'a>, method: &'c Method<'a>,
+           code: &'c CodeAttribute<'a>) -> Analysis<'c, 'a> {
+        let bytecodes = code.code_slice;
+        let mut analysis = Analysis {
+            definition,
+            method,
+            bytecodes,
+            stacks: vec![None; bytecodes.len()],
+            written_slots: HashSet::new(),
+        };
+        let mut worklist = vec![0];
+        analysis.stacks[0] = Some(Vec::new());
+        for handler in code.exception_table.iter() {
+            analysis.merge(handler.handle_pc as usize, vec![None],
+                           &mut worklist);
+        }
+        while let Some(pc) = worklist.pop() {
+            let stack = match analysis.stacks[pc] {
+                Some(ref stack) => stack.clone(),
+                None => continue,
+            };
+            match analysis.simulate(pc, stack) {
+                Some(successors) => for (successor, stack) in successors {
+                    analysis.merge(successor, stack, &mut worklist);
+                },
+                // An instruction the analysis doesn't understand.
+                None => return analysis,
+            }
+        }
+        analysis
+    }
+
+    fn merge(&mut self, pc: usize, stack: Stack, worklist: &mut Vec<usize>) {
+        if pc >= self.stacks.len() {
+            return;
+        }
+        let merged = match self.stacks[pc] {
+            None => stack,
+            Some(ref existing) if existing.len() != stack.len() => return,
+            Some(ref existing) => {
+                let merged: Stack = existing.iter().zip(stack.iter())
+                    .map(|(a, b)| if a == b { *a } else { None }).collect();
+                if merged == *existing {
+                    return;
+                }
+                merged
+            },
+        };
+        self.stacks[pc] = Some(merged);
+        worklist.push(pc);
+    }
+
+    fn stack_before(&self, pc: usize) -> Option<&Stack> {
+        self.stacks[pc].as_ref()
+    }
+
+    fn member_ref(&self, pc: usize) -> MemberRef<'a> {
+        self.definition.member_ref(self.u16_at(pc + 1))
+    }
+
+    fn u16_at(&self, pc: usize) -> u16 {
+        let mut idx = pc;
+        read_u16(self.bytecodes, &mut idx)
+    }
+
+    fn i32_at(&self, pc: usize) -> i32 {
+        let mut idx = pc;
+        read_u32(self.bytecodes, &mut idx) as i32
+    }
+
+    /*
+     * Applies the instruction at the given pc to the stack before it,
+     * yielding the instructions that may follow it, together with the
+     * stack each of them will see.
+     */
+    fn simulate(&mut self, pc: usize, mut stack: Stack)
+            -> Option<Vec<(usize, Stack)>> {
+        let opcode = self.bytecodes[pc];
+        let mut length = 1;
+        let mut branches = Vec::new();
+        let mut subroutine = None;
+        let mut falls_through = true;
+        let (pops, pushes) = match opcode {
+            0x00 => (0, 0),
+            0x01 ..= 0x08 | 0x0b ..= 0x0d => (0, 1),
+            0x09 | 0x0a | 0x0e | 0x0f => (0, 2),
+            0x10 | 0x12 => { length = 2; (0, 1) },
+            0x11 | 0x13 => { length = 3; (0, 1) },
+            0x14 => { length = 3; (0, 2) },
+            0x15 | 0x17 | 0x19 => { length = 2; (0, 1) },
+            0x16 | 0x18 => { length = 2; (0, 2) },
+            0x1a ..= 0x1d | 0x22 ..= 0x25 | 0x2a ..= 0x2d => (0, 1),
+            0x1e ..= 0x21 | 0x26 ..= 0x29 => (0, 2),
+            0x2f | 0x31 => (2, 2),
+            0x2e | 0x30 | 0x32 ..= 0x35 => (2, 1),
+            0x36 ..= 0x3a => {
+                length = 2;
+                self.written_slots.insert(self.bytecodes[pc + 1] as usize);
+                (if opcode == 0x37 || opcode == 0x39 { 2 } else { 1 }, 0)
+            },
+            0x3b ..= 0x4e => {
+                let offset = opcode - 0x3b;
+                self.written_slots.insert((offset % 4) as usize);
+                (if offset / 4 == 1 || offset / 4 == 3 { 2 } else { 1 }, 0)
+            },
+            0x50 | 0x52 => (4, 0),
+            0x4f | 0x51 | 0x53 ..= 0x56 => (3, 0),
+            0x57 => (1, 0),
+            0x58 => (2, 0),
+            0x59 ..= 0x5e => {
+                // dup, dup_x1, dup_x2, dup2, dup2_x1 and dup2_x2.
+                let offset = (opcode - 0x59) as usize;
+                let (words, beneath) = (offset / 3 + 1, offset % 3);
+                let top = stack.split_off(stack.len().checked_sub(words)?);
+                let under = stack.split_off(stack.len().checked_sub(beneath)?);
+                stack.extend(top.iter().cloned());
+                stack.extend(under);
+                stack.extend(top);
+                (0, 0)
+            },
+            0x5f => {
+                let top = stack.pop()?;
+                let under = stack.pop()?;
+                stack.push(top);
+                stack.push(under);
+                (0, 0)
+            },
+            0x60 ..= 0x73 => match (opcode - 0x60) % 2 {
+                0 => (2, 1),
+                _ => (4, 2),
+            },
+            0x74 | 0x76 => (1, 1),
+            0x75 | 0x77 => (2, 2),
+            0x78 | 0x7a | 0x7c | 0x7e | 0x80 | 0x82 => (2, 1),
+            0x79 | 0x7b | 0x7d => (3, 2),
+            0x7f | 0x81 | 0x83 => (4, 2),
+            0x84 => {
+                length = 3;
+                self.written_slots.insert(self.bytecodes[pc + 1] as usize);
+                (0, 0)
+            },
+            0x85 | 0x87 | 0x8c | 0x8d => (1, 2),
+            0x86 | 0x8b | 0x91 ..= 0x93 => (1, 1),
+            0x88 | 0x89 | 0x8e | 0x90 => (2, 1),
+            0x8a | 0x8f => (2, 2),
+            0x94 | 0x97 | 0x98 => (4, 1),
+            0x95 | 0x96 => (2, 1),
+            0x99 ..= 0x9e | 0xc6 | 0xc7 => {
+                length = 3;
+                branches.push(self.branch_target(pc));
+                (1, 0)
+            },
+            0x9f ..= 0xa6 => {
+                length = 3;
+                branches.push(self.branch_target(pc));
+                (2, 0)
+            },
+            0xa7 | 0xc8 => {
+                length = if opcode == 0xa7 { 3 } else { 5 };
+                branches.push(self.branch_target(pc));
+                falls_through = false;
+                (0, 0)
+            },
+            0xa8 | 0xc9 => {
+                /*
+                 * The subroutine eventually returns to the instruction
+                 * after the jsr, which is approximated by falling
+                 * through with the return address popped again.
+                 */
+                length = if opcode == 0xa8 { 3 } else { 5 };
+                let mut subroutine_stack = stack.clone();
+                subroutine_stack.push(Some(pc));
+                subroutine = Some((self.branch_target(pc), subroutine_stack));
+                (0, 0)
+            },
+            0xa9 | 0xac ..= 0xb1 | 0xbf => {
+                falls_through = false;
+                (0, 0)
+            },
+            0xaa | 0xab => {
+                let mut idx = (pc + 4) & !3;
+                branches.push(self.offset_target(pc, idx));
+                let targets = if opcode == 0xaa {
+                    let low = self.i32_at(idx + 4);
+                    let high = self.i32_at(idx + 8);
+                    idx += 12;
+                    (0 .. high.checked_sub(low)? + 1)
+                        .map(|i| idx + 4 * i as usize).collect::<Vec<_>>()
+                } else {
+                    let pairs = self.i32_at(idx + 4);
+                    idx += 8;
+                    (0 .. pairs).map(|i| idx + 8 * i as usize + 4)
+                        .collect::<Vec<_>>()
+                };
+                for target in targets {
+                    branches.push(self.offset_target(pc, target));
+                }
+                falls_through = false;
+                (1, 0)
+            },
+            0xb2 => {
+                length = 3;
+                (0, field_words(self.member_ref(pc).descriptor))
+            },
+            0xb3 => {
+                length = 3;
+                (field_words(self.member_ref(pc).descriptor), 0)
+            },
+            0xb4 => {
+                length = 3;
+                (1, field_words(self.member_ref(pc).descriptor))
+            },
+            0xb5 => {
+                length = 3;
+                (1 + field_words(self.member_ref(pc).descriptor), 0)
+            },
+            0xb6 ..= 0xb9 => {
+                length = if opcode == 0xb9 { 5 } else { 3 };
+                let descriptor = self.member_ref(pc).descriptor;
+                let receiver = if opcode == 0xb8 { 0 } else { 1 };
+                (param_words(descriptor) + receiver,
+                 return_words(descriptor))
+            },
+            0xbb => { length = 3; (0, 1) },
+            0xbc => { length = 2; (1, 1) },
+            0xbd | 0xc1 => { length = 3; (1, 1) },
+            0xbe => (1, 1),
+            0xc0 => {
+                // checkcast leaves its operand in place.
+                length = 3;
+                (0, 0)
+            },
+            0xc2 | 0xc3 => (1, 0),
+            0xc4 => {
+                let modified_opcode = self.bytecodes[pc + 1];
+                let idx = self.u16_at(pc + 2) as usize;
+                length = 4;
+                match modified_opcode {
+                    0x15 | 0x17 | 0x19 => (0, 1),
+                    0x16 | 0x18 => (0, 2),
+                    0x36 ..= 0x3a => {
+                        self.written_slots.insert(idx);
+                        (if modified_opcode == 0x37
+                            || modified_opcode == 0x39 { 2 } else { 1 }, 0)
+                    },
+                    0x84 => {
+                        length = 6;
+                        self.written_slots.insert(idx);
+                        (0, 0)
+                    },
+                    0xa9 => {
+                        falls_through = false;
+                        (0, 0)
+                    },
+                    _ => return None,
+                }
+            },
+            0xc5 => {
+                length = 4;
+                (self.bytecodes[pc + 3] as usize, 1)
+            },
+            _ => return None,
+        };
+
+        let remaining = stack.len().checked_sub(pops)?;
+        stack.truncate(remaining);
+        stack.extend((0 .. pushes).map(|_| Some(pc)));
+        let mut successors: Vec<(usize, Stack)> = branches.into_iter()
+            .map(|target| (target, stack.clone())).collect();
+        successors.extend(subroutine);
+        if falls_through {
+            successors.push((pc + length, stack));
+        }
+        Some(successors)
+    }
+
+    /*
+     * The target of a branch instruction, whose offset follows its
+     * opcode; goto_w and jsr_w have four byte offsets, the rest two.
+     */
+    fn branch_target(&self, pc: usize) -> usize {
+        let offset = match self.bytecodes[pc] {
+            0xc8 | 0xc9 => self.i32_at(pc + 1),
+            _ => self.u16_at(pc + 1) as i16 as i32,
+        };
+        (pc as i64 + offset as i64) as usize
+    }
+
+    // The target of a switch, whose offset is at the given index.
+    fn offset_target(&self, pc: usize, offset_idx: usize) -> usize {
+        (pc as i64 + self.i32_at(offset_idx) as i64) as usize
+    }
+
+    /*
+     * Describes the value pushed by the instruction at the given pc
+     * as a Java expression, returning false if it can't be described.
+     */
+    fn print_source(&self, out: &mut String, pc: usize, max_detail: usize)
+            -> bool {
+        if max_detail == 0 {
+            return false;
+        }
+        let opcode = self.bytecodes[pc];
+        match opcode {
+            0x01 => out.push_str("null"),
+            0x02 ..= 0x08 => out.push_str(&(opcode as i32 - 0x03).to_string()),
+            0x10 => out.push_str(&(self.bytecodes[pc + 1] as i8).to_string()),
+            0x11 => out.push_str(&(self.u16_at(pc + 1) as i16).to_string()),
+            0x15 | 0x19 => self.print_local(out, self.bytecodes[pc + 1] as usize),
+            0x1a ..= 0x1d => self.print_local(out, (opcode - 0x1a) as usize),
+            0x2a ..= 0x2d => self.print_local(out, (opcode - 0x2a) as usize),
+            0xc4 if self.bytecodes[pc + 1] == 0x15
+                    || self.bytecodes[pc + 1] == 0x19 =>
+                self.print_local(out, self.u16_at(pc + 2) as usize),
+            0x2e ..= 0x35 => {
+                let stack = match self.stack_before(pc) {
+                    Some(stack) => stack,
+                    None => return false,
+                };
+                let index_source = stack[stack.len() - 1];
+                let array_source = stack[stack.len() - 2];
+                if !array_source.is_some_and(
+                        |s| self.print_source(out, s, max_detail - 1)) {
+                    out.push_str("<array>");
+                }
+                out.push('[');
+                if !index_source.is_some_and(
+                        |s| self.print_source(out, s, max_detail - 1)) {
+                    out.push_str("...");
+                }
+                out.push(']');
+            },
+            0xb2 => {
+                let field_ref = self.member_ref(pc);
+                out.push_str(&format!("{}.{}",
+                                      class_display_name(field_ref.class_name),
+                                      field_ref.name));
+            },
+            0xb4 => {
+                let object_source = self.stack_before(pc)
+                    .and_then(|stack| stack.last().cloned()).and_then(|s| s);
+                if object_source.is_some_and(
+                        |s| self.print_source(out, s, max_detail - 1)) {
+                    out.push('.');
+                }
+                out.push_str(self.member_ref(pc).name);
+            },
+            0xb6 ..= 0xb9 => out.push_str(&self.method_name(pc)),
+            0xc0 => {
+                let source = self.stack_before(pc)
+                    .and_then(|stack| stack.last().cloned()).and_then(|s| s);
+                return source.is_some_and(
+                    |s| self.print_source(out, s, max_detail));
+            },
+            _ => return false,
+        }
+        true
+    }
+
+    /*
+     * Names a local variable; without a LocalVariableTable, only
+     * "this" and parameters that are never reassigned can be told
+     * apart from other locals.
+     */
+    fn print_local(&self, out: &mut String, slot: usize) {
+        let is_parameter = !self.written_slots.contains(&slot);
+        if !self.method.is_static() && slot == 0 && is_parameter {
+            out.push_str("this");
+            return;
+        }
+        let descriptor = match MethodDescriptor::parse(self.method.descriptor) {
+            Ok(descriptor) => descriptor,
+            Err(error) => panic!("[ERROR] Malformed method descriptor: {}",
+                                 error),
+        };
+        let mut current = if self.method.is_static() { 0 } else { 1 };
+        for (idx, param) in descriptor.params.iter().enumerate() {
+            if slot >= current && slot < current + param.slot_size() {
+                if is_parameter {
+                    out.push_str(&format!("<parameter{}>", idx + 1));
+                    return;
+                }
+                break;
+            }
+            current += param.slot_size();
+        }
+        out.push_str(&format!("<local{}>", slot));
+    }
+
+    /*
+     * Names the method invoked by the instruction at the given pc,
+     * e.g. "java.util.List.add(int, Object)".
+     */
+    fn method_name(&self, pc: usize) -> String {
+        let method_ref = self.member_ref(pc);
+        let params = match MethodDescriptor::parse(method_ref.descriptor) {
+            Ok(descriptor) => descriptor.params.iter()
+                .map(type_display_name).collect::<Vec<_>>().join(", "),
+            Err(error) => panic!("[ERROR] Malformed method descriptor: {}",
+                                 error),
+        };
+        format!("{}.{}({})", class_display_name(method_ref.class_name),
+                method_ref.name, params)
+    }
+}
+
+fn is_invoke(opcode: u8) -> bool {
+    (0xb6 ..= 0xb9).contains(&opcode)
+}
+
+fn array_kind(kind: u8) -> &'static str {
+    ["int", "long", "float", "double", "object", "byte/boolean", "char",
+     "short"][kind as usize]
+}
+
+fn field_words(descriptor: &str) -> usize {
+    match FieldType::parse(descriptor) {
+        Ok(field_type) => field_type.slot_size(),
+        Err(error) => panic!("[ERROR] Malformed field descriptor: {}", error),
+    }
+}
+
+fn param_words(descriptor: &str) -> usize {
+    match MethodDescriptor::parse(descriptor) {
+        Ok(descriptor) => descriptor.param_slots(),
+        Err(error) => panic!("[ERROR] Malformed method descriptor: {}", error),
+    }
+}
+
+fn return_words(descriptor: &str) -> usize {
+    match MethodDescriptor::parse(descriptor) {
+        Ok(descriptor) => descriptor.ret.map_or(0, |ret| ret.slot_size()),
+        Err(error) => panic!("[ERROR] Malformed method descriptor: {}", error),
+    }
+}
+
+/*
+ * Object and String are common enough to be named
+ * without their package.
+ */
+fn class_display_name(class_name: &str) -> String {
+    match class_name {
+        "java/lang/Object" => "Object".to_string(),
+        "java/lang/String" => "String".to_string(),
+        _ => class_name.replace('/', "."),
+    }
+}
+
+fn type_display_name(field_type: &FieldType) -> String {
+    match *field_type {
+        FieldType::Object(ref class_name) => class_display_name(class_name),
+        FieldType::Array(ref component) =>
+            format!("{}[]", type_display_name(component)),
+        ref primitive => match *primitive {
+            FieldType::Byte => "byte",
+            FieldType::Char => "char",
+            FieldType::Double => "double",
+            FieldType::Float => "float",
+            FieldType::Int => "int",
+            FieldType::Long => "long",
+            FieldType::Short => "short",
+            _ => "boolean",
+        }.to_string(),
+    }
+}
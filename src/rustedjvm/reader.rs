@@ -0,0 +1,19 @@
+/*
+ * Class files store all multi-byte quantities in big-endian
+ * order; these helpers read a value at the current index
+ * and advance the index past it.
+ */
+
+pub fn read_u16(bytecodes: &[u8], byte_idx: &mut usize) -> u16 {
+    let value = ((bytecodes[*byte_idx] as u16) << 8)
+        | bytecodes[*byte_idx + 1] as u16;
+    *byte_idx += 2;
+    value
+}
+
+pub fn read_u32(bytecodes: &[u8], byte_idx: &mut usize) -> u32 {
+    let value = bytecodes[*byte_idx..*byte_idx + 4]
+        .iter().fold(0, |s, &x| (s << 8) | x as u32);
+    *byte_idx += 4;
+    value
+}
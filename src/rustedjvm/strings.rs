@@ -0,0 +1,148 @@
+use heap::*;
+use interpreter::Operand;
+
+/*
+ * java.lang.String stores its chars in a byte[] value, along with
+ * a coder describing how they are encoded. When every char of a
+ * string fits in a single byte, as most do, the value holds one
+ * Latin-1 byte per char; otherwise it holds two bytes per UTF-16
+ * char, high byte first. Byte arrays are packed on the heap, so
+ * Latin-1 strings take a single byte per char.
+ */
+pub const LATIN1: u8 = 0;
+pub const UTF16: u8 = 1;
+
+pub fn encode(chars: &[u16]) -> (Vec<i8>, u8) {
+    if chars.iter().all(|&c| c <= 0xff) {
+        (chars.iter().map(|&c| c as u8 as i8).collect(), LATIN1)
+    } else {
+        let bytes = chars.iter()
+            .flat_map(|&c| vec![(c >> 8) as u8 as i8, c as u8 as i8])
+            .collect();
+        (bytes, UTF16)
+    }
+}
+
+pub fn decode(value: &[i8], coder: u8) -> Vec<u16> {
+    match coder {
+        LATIN1 => value.iter().map(|&b| b as u8 as u16).collect(),
+        UTF16 => value.chunks(2)
+            .map(|pair| ((pair[0] as u8 as u16) << 8) | pair[1] as u8 as u16)
+            .collect(),
+        _ => panic!("[ERROR] Unrecognized String coder: {}", coder),
+    }
+}
+
+/*
+ * Returns the UTF-16 chars of a java.lang.String on the heap.
+ */
+pub fn chars(heap: &Heap, string: Handle) -> Vec<u16> {
+    let object = heap.get(string);
+    let class = object.header.class;
+    let (value_idx, coder_idx) = match (
+            class.instance_field_idx("value", "[B"),
+            class.instance_field_idx("coder", "B")) {
+        (Some(value_idx), Some(coder_idx)) => (value_idx, coder_idx),
+        _ => panic!("[ERROR] Expected a String, but found an \
+                     instance of {}.", class.name),
+    };
+    let coder = match object.fields()[coder_idx] {
+        Operand::Int(coder) => coder as u8,
+        _ => panic!("[ERROR] Expected byte as String coder."),
+    };
+    match object.fields()[value_idx] {
+        Operand::Ref(value) => decode(heap.get(value).bytes(), coder),
+        _ => panic!("[ERROR] String value is null."),
+    }
+}
+
+/*
+ * Converts a java.lang.String to a Rust string; unpaired
+ * surrogates, which Rust strings cannot hold, are replaced.
+ */
+pub fn to_rust_string(heap: &Heap, string: Handle) -> String {
+    String::from_utf16_lossy(&chars(heap, string))
+}
+
+/*
+ * Formats a double as Double.toString does (as of JDK 19, which
+ * fixed its occasional surplus digits): with the fewest digits
+ * that uniquely distinguish it, in plain notation for magnitudes
+ * from 10^-3 up to 10^7, and in scientific notation otherwise.
+ * Rust's scientific notation likewise yields the fewest digits, but
+ * where a single digit would do, Java picks the closest decimal of
+ * up to two digits instead (e.g. 1.4E-45 rather than 1.0E-45).
+ */
+pub fn double_to_string(value: f64) -> String {
+    let magnitude = value.abs();
+    let two_digits = format!("{:.1e}", magnitude);
+    let scientific = if two_digits.parse() == Ok(magnitude) {
+        two_digits
+    } else {
+        format!("{:e}", magnitude)
+    };
+    to_java_string(value, &scientific)
+}
+
+pub fn float_to_string(value: f32) -> String {
+    let magnitude = value.abs();
+    let two_digits = format!("{:.1e}", magnitude);
+    let scientific = if two_digits.parse() == Ok(magnitude) {
+        two_digits
+    } else {
+        format!("{:e}", magnitude)
+    };
+    // Widening is exact, and the digits are already those of the float.
+    to_java_string(value as f64, &scientific)
+}
+
+fn to_java_string(value: f64, scientific: &str) -> String {
+    let sign = if value.is_sign_negative() { "-" } else { "" };
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        format!("{}Infinity", sign)
+    } else if value == 0.0 {
+        format!("{}0.0", sign)
+    } else {
+        let plain = (1e-3 .. 1e7).contains(&value.abs());
+        format!("{}{}", sign, java_notation(scientific, plain))
+    }
+}
+
+/*
+ * Rewrites a positive number in Rust's scientific notation, such as
+ * "1.25e3", as Java writes it: "1250.0" in plain notation, or
+ * "1.25E3" in scientific notation. Either way, at least one digit
+ * follows the decimal point.
+ */
+fn java_notation(scientific: &str, plain: bool) -> String {
+    let (mantissa, exponent) = match scientific.find('e') {
+        Some(idx) => (&scientific[.. idx], &scientific[idx + 1 ..]),
+        None => panic!("[ERROR] Expected scientific notation: {}",
+                       scientific),
+    };
+    let exponent: i32 = match exponent.parse() {
+        Ok(exponent) => exponent,
+        Err(_) => panic!("[ERROR] Invalid exponent: {}", scientific),
+    };
+    let digits: String = mantissa.chars().filter(|&c| c != '.').collect();
+    let digits = match digits.trim_end_matches('0') {
+        "" => "0",
+        trimmed => trimmed,
+    };
+    if !plain {
+        let fraction = if digits.len() > 1 { &digits[1 ..] } else { "0" };
+        return format!("{}.{}E{}", &digits[.. 1], fraction, exponent);
+    }
+    if exponent < 0 {
+        return format!("0.{}{}", "0".repeat((-exponent - 1) as usize),
+                       digits);
+    }
+    let int_len = exponent as usize + 1;
+    if digits.len() > int_len {
+        format!("{}.{}", &digits[.. int_len], &digits[int_len ..])
+    } else {
+        format!("{}{}.0", digits, "0".repeat(int_len - digits.len()))
+    }
+}
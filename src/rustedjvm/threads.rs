@@ -0,0 +1,310 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use heap::Handle;
+
+/*
+ * The lock a thread holds while it runs guest code or otherwise
+ * touches the state of the VM, so that only one does at a time. It's
+ * handed to the threads that ask for it in the order they ask, so that
+ * a thread that gives it up at a safepoint, only to ask for it again,
+ * doesn't keep the others from running.
+ */
+pub struct VmLock {
+    state: Mutex<LockState>,
+    // Signalled when the lock is handed to the next thread in line.
+    turn: Condvar,
+    /*
+     * Signalled when something a blocked thread may be waiting for
+     * happens, such as a monitor being exited or a thread dying.
+     */
+    events: Condvar,
+    blocked: AtomicUsize,
+}
+
+/*
+ * Threads take a ticket when they ask for the lock, and hold it while
+ * their ticket is the one being served.
+ */
+struct LockState {
+    next_ticket: u64,
+    now_serving: u64,
+}
+
+impl Default for VmLock {
+    fn default() -> Self {
+        VmLock::new()
+    }
+}
+
+impl VmLock {
+    pub fn new() -> VmLock {
+        VmLock {
+            state: Mutex::new(LockState { next_ticket: 0, now_serving: 0 }),
+            turn: Condvar::new(),
+            events: Condvar::new(),
+            blocked: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn acquire(&self) {
+        let state = self.state();
+        self.wait_for_turn(state);
+    }
+
+    pub fn release(&self) {
+        let mut state = self.state();
+        state.now_serving += 1;
+        self.turn.notify_all();
+    }
+
+    // Whether other threads are waiting for the lock.
+    pub fn is_contended(&self) -> bool {
+        let state = self.state();
+        state.next_ticket > state.now_serving + 1
+    }
+
+    // Lets the threads waiting for the lock have it before taking it back.
+    pub fn yield_now(&self) {
+        let mut state = self.state();
+        state.now_serving += 1;
+        self.turn.notify_all();
+        self.wait_for_turn(state);
+    }
+
+    /*
+     * Gives up the lock until an event is signalled or the deadline
+     * (if any) passes, or spuriously, and then takes it back. As the
+     * lock is given up only once the thread is waiting for events,
+     * those signalled by the threads that then take the lock aren't
+     * missed.
+     */
+    pub fn wait_for_event(&self, deadline: Option<Instant>) {
+        let mut state = self.state();
+        self.blocked.fetch_add(1, Ordering::SeqCst);
+        state.now_serving += 1;
+        self.turn.notify_all();
+        let state = match deadline {
+            Some(deadline) => {
+                let timeout = deadline.saturating_duration_since(
+                    Instant::now());
+                match self.events.wait_timeout(state, timeout) {
+                    Ok((state, _)) => state,
+                    Err(poisoned) => poisoned.into_inner().0,
+                }
+            },
+            None => match self.events.wait(state) {
+                Ok(state) => state,
+                Err(poisoned) => poisoned.into_inner(),
+            },
+        };
+        self.blocked.fetch_sub(1, Ordering::SeqCst);
+        self.wait_for_turn(state);
+    }
+
+    // Wakes the threads waiting for events, if there are any.
+    pub fn signal_event(&self) {
+        if self.blocked.load(Ordering::SeqCst) > 0 {
+            let _state = self.state();
+            self.events.notify_all();
+        }
+    }
+
+    fn wait_for_turn(&self, mut state: MutexGuard<'_, LockState>) {
+        let ticket = state.next_ticket;
+        state.next_ticket += 1;
+        while state.now_serving != ticket {
+            state = match self.turn.wait(state) {
+                Ok(state) => state,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+        }
+    }
+
+    /*
+     * No code that can panic runs while the mutex is held, so it's
+     * never left in an inconsistent state.
+     */
+    fn state(&self) -> MutexGuard<'_, LockState> {
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+/*
+ * The turns green threads take when they're scheduled
+ * deterministically, all on the thread that created the VM. Each
+ * turn is given to a thread picked at random from those ready to
+ * run, for a random number of instructions, with the random numbers
+ * drawn from a generator seeded by the seed the VM was created with,
+ * so the same program run with the same seed takes the same turns.
+ *
+ * Time is virtual, too: it passes as instructions are executed and,
+ * when every thread is blocked, skips ahead to the earliest deadline
+ * any of them is waiting for.
+ */
+pub struct Schedule {
+    random: u64,
+    // Whether each thread that's alive is ready to run, by id.
+    threads: BTreeMap<u32, Waiting>,
+    clock: Instant,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Waiting {
+    Ready,
+    // Blocked until an event is signalled or the deadline (if any) passes.
+    Blocked(Option<Instant>),
+}
+
+// The virtual time each instruction takes when threads are scheduled.
+const INSTRUCTION_TIME: Duration = Duration::from_nanos(10);
+
+impl Schedule {
+    pub fn new(seed: u64) -> Schedule {
+        Schedule {
+            random: seed,
+            threads: BTreeMap::new(),
+            clock: Instant::now(),
+        }
+    }
+
+    // Makes a thread ready to take turns, as it's started.
+    pub fn admit(&mut self, thread: u32) {
+        self.threads.insert(thread, Waiting::Ready);
+    }
+
+    // Forgets a thread once it has died.
+    pub fn forget(&mut self, thread: u32) {
+        self.threads.remove(&thread);
+    }
+
+    /*
+     * Passes a thread over until an event is signalled or the
+     * deadline (if any) passes.
+     */
+    pub fn block(&mut self, thread: u32, deadline: Option<Instant>) {
+        self.threads.insert(thread, Waiting::Blocked(deadline));
+    }
+
+    // Makes the blocked threads ready to check whether they can go on.
+    pub fn signal_event(&mut self) {
+        for waiting in self.threads.values_mut() {
+            *waiting = Waiting::Ready;
+        }
+    }
+
+    /*
+     * The number of instructions a thread executes before its next
+     * safepoint: a random number up to the given interval, which the
+     * virtual time is advanced by.
+     */
+    pub fn quantum(&mut self, interval: u32) -> u32 {
+        let quantum = 1 + (self.next_random() % interval as u64) as u32;
+        self.clock += INSTRUCTION_TIME * quantum;
+        quantum
+    }
+
+    pub fn now(&self) -> Instant {
+        self.clock
+    }
+
+    /*
+     * Picks the thread to take the next turn from those ready to run
+     * that the given function accepts. If none are, but some are
+     * blocked until a deadline, time skips ahead to the earliest, and
+     * if the rest are blocked indefinitely, there's none to pick.
+     */
+    pub fn next_turn<F>(&mut self, eligible: F) -> Option<u32>
+            where F: Fn(u32) -> bool {
+        let is_ready = |(&thread, &waiting): (&u32, &Waiting)| {
+            waiting == Waiting::Ready && eligible(thread)
+        };
+        if !self.threads.iter().any(is_ready) {
+            let earliest = self.threads.iter()
+                .filter(|&(&thread, _)| eligible(thread))
+                .filter_map(|(_, &waiting)| match waiting {
+                    Waiting::Blocked(deadline) => deadline,
+                    Waiting::Ready => None,
+                })
+                .min()?;
+            self.clock = self.clock.max(earliest);
+            let clock = self.clock;
+            for waiting in self.threads.values_mut() {
+                if let Waiting::Blocked(Some(deadline)) = *waiting {
+                    if deadline <= clock {
+                        *waiting = Waiting::Ready;
+                    }
+                }
+            }
+        }
+        let ready: Vec<u32> = self.threads.iter()
+            .filter(|&entry| is_ready(entry))
+            .map(|(&thread, _)| thread)
+            .collect();
+        Some(ready[(self.next_random() % ready.len() as u64) as usize])
+    }
+
+    // The next number from a SplitMix64 generator.
+    fn next_random(&mut self) -> u64 {
+        self.random = self.random.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.random;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/*
+ * What the VM keeps about a thread from when it's started until
+ * it dies.
+ */
+pub struct ThreadState {
+    /*
+     * The java.lang.Thread standing for the thread, which for the
+     * main thread is created only once it's asked for.
+     */
+    pub object: Option<Handle>,
+    pub daemon: bool,
+    pub interrupted: bool,
+    // Whether LockSupport.unpark has made the thread's permit available.
+    pub permit: bool,
+    // The object the thread is in Object.wait on, if any.
+    pub waiting_on: Option<Handle>,
+    // When the thread started waiting, which orders notifications.
+    pub wait_order: u64,
+    pub notified: bool,
+    /*
+     * The times the thread had entered the monitor it gave up in
+     * Object.wait, which it enters as many times again once it wakes;
+     * set until it has, so that a green thread suspended in the call
+     * can pick up where it left off.
+     */
+    pub wait_count: Option<u32>,
+    /*
+     * The objects the thread's frames and local references held when
+     * it last gave up the VM lock or its turn, which the collector
+     * treats as roots until it takes it back.
+     */
+    pub roots: Vec<Handle>,
+}
+
+impl ThreadState {
+    pub fn new(object: Option<Handle>, daemon: bool) -> ThreadState {
+        ThreadState {
+            object,
+            daemon,
+            interrupted: false,
+            permit: false,
+            waiting_on: None,
+            wait_order: 0,
+            notified: false,
+            wait_count: None,
+            roots: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use descriptors::*;
+use heap::Handle;
+use interpreter::Operand;
+
+/*
+ * A Java value as seen from Rust, typed as precisely as the
+ * descriptor it is passed or returned through. Unlike operands,
+ * which widen booleans, bytes, chars and shorts to ints, values
+ * keep the type they were declared with.
+ */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Value {
+    // The result of a method that returns void.
+    Void,
+    Boolean(bool),
+    Byte(i8),
+    Char(u16),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    Ref(Handle),
+    Null,
+}
+
+impl Value {
+    pub fn from_operand(operand: Operand, field_type: &FieldType) -> Value {
+        match (field_type, operand) {
+            (&FieldType::Boolean, Operand::Int(value)) =>
+                Value::Boolean(value != 0),
+            (&FieldType::Byte, Operand::Int(value)) => Value::Byte(value as i8),
+            (&FieldType::Char, Operand::Int(value)) =>
+                Value::Char(value as u16),
+            (&FieldType::Short, Operand::Int(value)) =>
+                Value::Short(value as i16),
+            (&FieldType::Int, Operand::Int(value)) => Value::Int(value),
+            (&FieldType::Long, Operand::Long(value)) => Value::Long(value),
+            (&FieldType::Float, Operand::Float(value)) => Value::Float(value),
+            (&FieldType::Double, Operand::Double(value)) =>
+                Value::Double(value),
+            (_, Operand::Ref(handle)) if field_type.is_reference() =>
+                Value::Ref(handle),
+            (_, Operand::Null) if field_type.is_reference() => Value::Null,
+            _ => panic!("[ERROR] Operand does not hold a value of type {}.",
+                        field_type),
+        }
+    }
+
+    // Void has no operand; every other value has exactly one.
+    pub fn to_operand(self) -> Option<Operand> {
+        match self {
+            Value::Void => None,
+            Value::Boolean(value) => Some(Operand::Int(value as i32)),
+            Value::Byte(value) => Some(Operand::Int(value as i32)),
+            Value::Char(value) => Some(Operand::Int(value as i32)),
+            Value::Short(value) => Some(Operand::Int(value as i32)),
+            Value::Int(value) => Some(Operand::Int(value)),
+            Value::Long(value) => Some(Operand::Long(value)),
+            Value::Float(value) => Some(Operand::Float(value)),
+            Value::Double(value) => Some(Operand::Double(value)),
+            Value::Ref(handle) => Some(Operand::Ref(handle)),
+            Value::Null => Some(Operand::Null),
+        }
+    }
+
+    /*
+     * Whether the value can be passed or returned as the given type,
+     * where None stands for void.
+     */
+    pub fn is_of_type(&self, field_type: Option<&FieldType>) -> bool {
+        match (field_type, *self) {
+            (None, Value::Void)
+                | (Some(&FieldType::Boolean), Value::Boolean(_))
+                | (Some(&FieldType::Byte), Value::Byte(_))
+                | (Some(&FieldType::Char), Value::Char(_))
+                | (Some(&FieldType::Short), Value::Short(_))
+                | (Some(&FieldType::Int), Value::Int(_))
+                | (Some(&FieldType::Long), Value::Long(_))
+                | (Some(&FieldType::Float), Value::Float(_))
+                | (Some(&FieldType::Double), Value::Double(_)) => true,
+            (Some(field_type), Value::Ref(_))
+                | (Some(field_type), Value::Null) =>
+                field_type.is_reference(),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,9 @@
+public class ArrayIndexOutOfBounds {
+
+  public static void main(String[] args) {
+    int[] values = new int[3];
+    for (int i = 0; i <= values.length; i++) {
+      values[i] = i;
+    }
+  }
+}
@@ -0,0 +1,16 @@
+public class ArrayStore {
+
+  static class Fruit {
+  }
+
+  static class Apple extends Fruit {
+  }
+
+  static class Orange extends Fruit {
+  }
+
+  public static void main(String[] args) {
+    Fruit[] fruits = new Apple[1];
+    fruits[0] = new Orange();
+  }
+}
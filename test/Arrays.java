@@ -0,0 +1,124 @@
+public class Arrays {
+
+  static class Animal {
+    int legs;
+  }
+
+  static class Dog extends Animal {
+  }
+
+  static int ints(int length) {
+    int[] values = new int[length];
+    for (int i = 0; i < values.length; i++) {
+      values[i] = i * i;
+    }
+    int sum = 0;
+    for (int value : values) {
+      sum += value;
+    }
+    return sum;
+  }
+
+  static long longs(int length) {
+    long[] values = new long[length];
+    values[0] = 1;
+    for (int i = 1; i < values.length; i++) {
+      values[i] = values[i - 1] * 3;
+    }
+    return values[length - 1];
+  }
+
+  static double floatingPoint(int length) {
+    float[] floats = new float[length];
+    double[] doubles = new double[length];
+    for (int i = 0; i < length; i++) {
+      floats[i] = i / 2.0f / 2.0f;
+      doubles[i] = floats[i] * 2.0;
+    }
+    return doubles[length - 1] + floats[1];
+  }
+
+  static int narrow(int value) {
+    byte[] bytes = new byte[1];
+    char[] chars = new char[1];
+    short[] shorts = new short[1];
+    boolean[] booleans = new boolean[2];
+    bytes[0] = (byte) value;
+    chars[0] = (char) value;
+    shorts[0] = (short) value;
+    booleans[1] = true;
+    return bytes[0] + chars[0] + shorts[0] + (booleans[0] ? 100 : 0)
+        + (booleans[1] ? 1 : 0);
+  }
+
+  static int references() {
+    Animal[] animals = new Animal[3];
+    animals[0] = new Animal();
+    animals[1] = new Dog();
+    Object[] objects = new Dog[2];
+    objects[0] = new Dog();
+    objects[1] = null;
+    int count = 0;
+    for (Animal animal : animals) {
+      if (animal != null) {
+        animal.legs = 4;
+        count += animal.legs;
+      }
+    }
+    if (objects[0] != null && objects[1] == null) {
+      count += 100;
+    }
+    return count;
+  }
+
+  static int multiDimensional(int rows, int columns) {
+    int[][] grid = new int[rows][columns];
+    for (int row = 0; row < rows; row++) {
+      for (int column = 0; column < columns; column++) {
+        grid[row][column] = row * columns + column;
+      }
+    }
+    int[][][] partial = new int[2][3][];
+    int trace = 0;
+    for (int i = 0; i < rows && i < columns; i++) {
+      trace += grid[i][i];
+    }
+    return trace * 1000 + grid.length * 100 + grid[0].length * 10
+        + partial[1].length + (partial[1][2] == null ? -1 : 0);
+  }
+
+  static int jagged() {
+    long[][] triangle = new long[5][];
+    for (int i = 0; i < triangle.length; i++) {
+      triangle[i] = new long[i + 1];
+      triangle[i][0] = 1;
+      triangle[i][i] = 1;
+      for (int j = 1; j < i; j++) {
+        triangle[i][j] = triangle[i - 1][j - 1] + triangle[i - 1][j];
+      }
+    }
+    return (int) triangle[4][2];
+  }
+
+  public static void main(String[] args) {
+    System.out.println(args.length);
+    System.out.println(ints(10));
+    System.out.println(longs(30));
+    System.out.println(floatingPoint(7));
+    System.out.println(narrow(-129));
+    System.out.println(references());
+    System.out.println(multiDimensional(3, 4));
+    System.out.println(jagged());
+
+    long[][][] cube = new long[2][3][4];
+    System.out.println(cube.length);
+    System.out.println(cube[1].length);
+    System.out.println(cube[1][2].length);
+    System.out.println(cube[1][2][3]);
+    Object[][] empty = new Object[0][5];
+    System.out.println(empty.length);
+    char[] chars = new char[3];
+    chars[2] = 'z';
+    System.out.println(chars);
+  }
+}
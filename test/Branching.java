@@ -0,0 +1,107 @@
+public class Branching {
+
+  static int collatzSteps(int n) {
+    int steps = 0;
+    while (n != 1) {
+      if (n % 2 == 0) {
+        n = n / 2;
+      } else {
+        n = 3 * n + 1;
+      }
+      steps++;
+    }
+    return steps;
+  }
+
+  static int comparisons(int a, int b) {
+    int flags = 0;
+    if (a == b) flags |= 1;
+    if (a != b) flags |= 2;
+    if (a < b) flags |= 4;
+    if (a >= b) flags |= 8;
+    if (a > b) flags |= 16;
+    if (a <= b) flags |= 32;
+    if (a < 0) flags |= 64;
+    if (a > 0) flags |= 128;
+    if (a <= 0) flags |= 256;
+    if (a >= 0) flags |= 512;
+    return flags;
+  }
+
+  static int wideComparisons(long l, int halves, double d) {
+    float f = halves / 2.0f;
+    int flags = 0;
+    float nanF = f / 0 - f / 0;
+    double nanD = d / 0 - d / 0;
+    if (l > 5) flags |= 1;
+    if (f < 2.0f) flags |= 2;
+    if (d >= 3.0) flags |= 4;
+    if (nanF < f) flags |= 8;
+    if (nanF > f) flags |= 16;
+    if (nanD < d) flags |= 32;
+    if (nanD > d) flags |= 64;
+    if (nanD != nanD) flags |= 128;
+    return flags;
+  }
+
+  static int denseSwitch(int n) {
+    switch (n) {
+      case 1: return 10;
+      case 2: return 20;
+      case 3: return 30;
+      case 4: return 40;
+      default: return -1;
+    }
+  }
+
+  static int sparseSwitch(int n) {
+    switch (n) {
+      case -1000: return 1;
+      case 7: return 2;
+      case 100000: return 3;
+      default: return 4;
+    }
+  }
+
+  static int references(Branching a, Branching b) {
+    int flags = 0;
+    if (a == b) flags |= 1;
+    if (a != b) flags |= 2;
+    if (a == null) flags |= 4;
+    if (b != null) flags |= 8;
+    return flags;
+  }
+
+  static int longLoop() {
+    int total = 0;
+    for (int i = 0; i < 300; i++) {
+      total += i;
+      if (total > 20000) {
+        break;
+      }
+    }
+    return total;
+  }
+
+  public static void main(String[] args) {
+    int thousand = 1000;
+    System.out.println(collatzSteps(27));
+    System.out.println(comparisons(3, 3));
+    System.out.println(comparisons(-2, 5));
+    System.out.println(comparisons(9, 1));
+    System.out.println(wideComparisons(6, 3, 3.0));
+    for (int i = 0; i < 6; i++) {
+      System.out.println(denseSwitch(i));
+      System.out.println(sparseSwitch(i * 7 - 7));
+    }
+    System.out.println(sparseSwitch(-1000));
+    System.out.println(sparseSwitch(thousand * 100));
+    System.out.println(sparseSwitch(Integer.MIN_VALUE));
+    System.out.println(references(null, null));
+    Branching branching = new Branching();
+    System.out.println(references(branching, branching));
+    System.out.println(references(branching, new Branching()));
+    System.out.println(longLoop());
+    System.out.println(Subroutines.quadruple(5));
+  }
+}
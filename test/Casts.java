@@ -0,0 +1,98 @@
+import java.io.Serializable;
+
+public class Casts {
+
+  interface Shape {
+    int sides();
+  }
+
+  interface Polygon extends Shape {
+  }
+
+  static class Square implements Polygon {
+    public int sides() {
+      return 4;
+    }
+  }
+
+  static class Tile extends Square implements Cloneable {
+  }
+
+  static class Box<T> {
+    T value;
+
+    Box(T value) {
+      this.value = value;
+    }
+
+    T get() {
+      return value;
+    }
+  }
+
+  static int bits;
+  static int count;
+
+  static void record(boolean result) {
+    bits |= (result ? 1 : 0) << count;
+    count++;
+  }
+
+  public static void main(String[] args) {
+    Object tile = new Tile();
+    Object square = new Square();
+    Object ints = new int[2];
+    Object grid = new int[2][3];
+    Object tiles = new Tile[1];
+    Object shapes = new Shape[1][1];
+    Object nothing = null;
+
+    record(tile instanceof Square);
+    record(tile instanceof Shape);
+    record(tile instanceof Cloneable);
+    record(square instanceof Tile);
+    record(square instanceof Cloneable);
+    record(nothing instanceof Object);
+    record(ints instanceof Object);
+    record(ints instanceof Cloneable);
+    record(ints instanceof Serializable);
+    record(ints instanceof long[]);
+    record(ints instanceof Object[]);
+    record(grid instanceof Object[]);
+    record(grid instanceof Cloneable[]);
+    record(grid instanceof Serializable[]);
+    record(grid instanceof int[][]);
+    record(tiles instanceof Square[]);
+    record(tiles instanceof Polygon[]);
+    record(tiles instanceof Cloneable[]);
+    record(tiles instanceof Object[]);
+    record(shapes instanceof Object[][]);
+    record(shapes instanceof Shape[][]);
+    record(shapes instanceof Polygon[][]);
+    record(shapes instanceof Object[]);
+
+    Box<Shape> box = new Box<Shape>(new Tile());
+    int sides = 0;
+    for (int i = 0; i < 10; i++) {
+      sides += box.get().sides();
+    }
+    Shape[] covariant = (Shape[]) tiles;
+    Object[][] nested = (Object[][]) shapes;
+    Polygon polygon = (Polygon) nothing;
+    int caught = 0;
+    try {
+      Tile wrong = (Tile) square;
+    } catch (ClassCastException e) {
+      caught++;
+    }
+    try {
+      Object[] wrong = (Object[]) ints;
+    } catch (ClassCastException e) {
+      caught++;
+    }
+    System.out.println(bits);
+    System.out.println(count);
+    System.out.println(sides + caught * 100 + covariant.length + nested.length);
+    System.out.println(polygon);
+  }
+}
@@ -0,0 +1,24 @@
+public class ChainedTrace {
+
+  static class Failure extends RuntimeException {
+    Failure(Throwable cause) {
+      super(null, cause);
+    }
+  }
+
+  static int divide(int value, int divisor) {
+    return value / divisor;
+  }
+
+  static int average(int total, int count) {
+    try {
+      return divide(total, count);
+    } catch (ArithmeticException e) {
+      throw new Failure(e);
+    }
+  }
+
+  public static void main(String[] args) {
+    average(10, 0);
+  }
+}
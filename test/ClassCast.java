@@ -0,0 +1,19 @@
+public class ClassCast {
+
+  static class Animal {
+  }
+
+  static class Dog extends Animal {
+  }
+
+  static class Cat extends Animal {
+  }
+
+  static Animal adopt() {
+    return new Cat();
+  }
+
+  public static void main(String[] args) {
+    Dog dog = (Dog) adopt();
+  }
+}
@@ -0,0 +1,29 @@
+import java.lang.invoke.MethodHandles;
+
+public class Condy {
+
+  static class Bootstrap {
+
+    static Object[] garbage;
+
+    // Allocates well past a small heap, so the collector runs while
+    // the dynamic constant's bootstrap arguments are waiting.
+    static {
+      for (int i = 0; i < 20000; i++) {
+        garbage = new Object[32];
+        garbage[0] = new int[16];
+      }
+    }
+
+    static Object bootstrap(MethodHandles.Lookup lookup, String name,
+                            Class<?> type, String argument) {
+      return lookup.lookupClass().getName().concat(" ").concat(name)
+          .concat(" ").concat(type.getName()).concat(" ").concat(argument);
+    }
+  }
+
+  public static void main(String[] args) {
+    System.out.println(LoadableConstants.value());
+    System.out.println(LoadableConstants.value() == LoadableConstants.value());
+  }
+}
@@ -0,0 +1,67 @@
+import java.lang.invoke.MethodHandle;
+import java.lang.invoke.MethodType;
+
+public class Constants {
+
+  static class Other {
+    static String greeting() {
+      return "hello";
+    }
+  }
+
+  static int checksum(String value) {
+    int sum = 0;
+    for (int i = 0; i < value.length(); i++) {
+      sum = sum * 31 + value.charAt(i);
+    }
+    return sum;
+  }
+
+  public static void main(String[] args) {
+    int big = 1234567;
+    float ratio = 0.75f;
+    long wide = 9876543210L;
+    double precise = 2.718281828;
+    System.out.println(big + (int) (ratio * 100) + (int) (wide % 1000)
+        + (int) (precise * 1000));
+    System.out.println(-1000000);
+    System.out.println(ratio);
+    System.out.println(wide);
+    System.out.println(precise);
+
+    // Equal string constants are the same object, even across classes.
+    String hello = "hello";
+    System.out.println(hello == Other.greeting());
+    System.out.println(hello == "hel" + "lo");
+    System.out.println(checksum(hello) + checksum("été ☃"));
+
+    Class<?> self = Constants.class;
+    Class<?> grid = int[][].class;
+    Class<?> names = String[].class;
+    System.out.println(self.getName());
+    System.out.println(grid.getName());
+    System.out.println(grid.getComponentType().getComponentType().getName());
+    System.out.println(names.getComponentType().getName());
+    System.out.println(names.isArray());
+    System.out.println(self.isArray());
+    System.out.println(self == Constants.class);
+    System.out.println(grid.getComponentType() == int[].class);
+
+    MethodType type = LoadableConstants.methodType();
+    System.out.println(type.returnType().getName());
+    System.out.println(type.parameterCount());
+    System.out.println(type.parameterType(0).getName());
+    System.out.println(type.parameterType(1).getName());
+    System.out.println(type == LoadableConstants.methodType());
+
+    // Handles to instance methods take the receiver first.
+    MethodHandle length = LoadableConstants.lengthHandle();
+    System.out.println(length.type().returnType().getName());
+    System.out.println(length.type().parameterCount());
+    System.out.println(length.type().parameterType(0).getName());
+    System.out.println(length == LoadableConstants.lengthHandle());
+
+    System.out.println(LoadableConstants.value());
+    System.out.println(LoadableConstants.value() == LoadableConstants.value());
+  }
+}
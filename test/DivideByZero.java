@@ -0,0 +1,10 @@
+public class DivideByZero {
+
+  static int divide(int a, int b) {
+    return a / b;
+  }
+
+  public static void main(String[] args) {
+    int result = divide(1, 0);
+  }
+}
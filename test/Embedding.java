@@ -0,0 +1,50 @@
+/*
+ * Exercised from Rust through the embedding API rather than run.
+ */
+public class Embedding {
+
+  static int instances;
+  static String greeting;
+
+  static {
+    greeting = "Hello";
+  }
+
+  private final String name;
+  private long total;
+
+  public Embedding(String name) {
+    this.name = name;
+    instances++;
+  }
+
+  public static int add(int a, int b) {
+    return a + b;
+  }
+
+  public static String greet(String name) {
+    return greeting.concat(", ").concat(name).concat("!");
+  }
+
+  public static int[] allocate(int length) {
+    return new int[length];
+  }
+
+  public static void check(boolean valid) {
+    if (!valid) {
+      throw new IllegalArgumentException("invalid");
+    }
+  }
+
+  public long add(long amount) {
+    total += amount;
+    return total;
+  }
+
+  public String describe() {
+    return name.concat(" has ").concat(String.valueOf(total));
+  }
+
+  static abstract class Shape {
+  }
+}
@@ -0,0 +1,152 @@
+public class Exceptions {
+
+  static class Failure extends Exception {
+    final int code;
+
+    Failure(int code) {
+      this.code = code;
+    }
+  }
+
+  static class SpecificFailure extends Failure {
+    SpecificFailure(int code) {
+      super(code);
+    }
+  }
+
+  static class BrokenInitializer {
+    static int value = divide(1, 0);
+  }
+
+  static int cleanups;
+
+  static int divide(int a, int b) {
+    return a / b;
+  }
+
+  static void fail(int code) throws Failure {
+    if (code % 2 == 0) {
+      throw new SpecificFailure(code);
+    }
+    throw new Failure(code);
+  }
+
+  static int deepFail(int depth, int code) throws Failure {
+    if (depth == 0) {
+      fail(code);
+    }
+    try {
+      return deepFail(depth - 1, code);
+    } finally {
+      cleanups++;
+    }
+  }
+
+  static int catchByType(int code) {
+    try {
+      fail(code);
+      return -1;
+    } catch (SpecificFailure e) {
+      return e.code * 10 + 1;
+    } catch (Failure e) {
+      return e.code * 10 + 2;
+    }
+  }
+
+  static int acrossFrames() {
+    try {
+      return deepFail(5, 7);
+    } catch (Failure e) {
+      return e.code + cleanups * 100;
+    }
+  }
+
+  static int finallyOverrides() {
+    int result = 0;
+    for (int i = 0; i < 3; i++) {
+      try {
+        if (i == 1) {
+          continue;
+        }
+        result += 10;
+      } finally {
+        result += 1;
+      }
+    }
+    return result;
+  }
+
+  static int vmExceptions(int[] values, Failure nothing) {
+    int caught = 0;
+    try {
+      divide(4, 0);
+    } catch (ArithmeticException e) {
+      caught += e.getMessage().length();
+    }
+    try {
+      values[values.length] = 1;
+    } catch (IndexOutOfBoundsException e) {
+      caught += 100;
+    }
+    try {
+      caught += nothing.code;
+    } catch (NullPointerException e) {
+      caught += 1000;
+    }
+    try {
+      int[] negative = new int[values.length - 10];
+    } catch (RuntimeException e) {
+      caught += 10000;
+    }
+    return caught;
+  }
+
+  static int rethrow(int code) {
+    try {
+      try {
+        fail(code);
+      } catch (Failure e) {
+        throw new IllegalStateExceptionLike(e);
+      }
+    } catch (IllegalStateExceptionLike e) {
+      return e.failure.code + (e.getCause() == e.failure ? 100 : 0);
+    }
+    return -1;
+  }
+
+  static class IllegalStateExceptionLike extends RuntimeException {
+    final Failure failure;
+
+    IllegalStateExceptionLike(Failure cause) {
+      super(cause);
+      this.failure = cause;
+    }
+  }
+
+  static int initializerFailure() {
+    int result = 0;
+    try {
+      result = BrokenInitializer.value;
+    } catch (ExceptionInInitializerError e) {
+      if (e.getCause() != null) {
+        result += 1;
+      }
+    }
+    try {
+      result = BrokenInitializer.value;
+    } catch (NoClassDefFoundError e) {
+      result += 10;
+    }
+    return result;
+  }
+
+  public static void main(String[] args) {
+    int specific = catchByType(4);
+    int general = catchByType(3);
+    int across = acrossFrames();
+    int finallyResult = finallyOverrides();
+    int vm = vmExceptions(new int[3], null);
+    int rethrown = rethrow(9);
+    int initializer = initializerFailure();
+  }
+}
@@ -0,0 +1,21 @@
+public class Exit {
+
+  public static void main(String[] args) {
+    System.out.println(args.length);
+    for (int i = 0; i < args.length; i++) {
+      System.out.println(args[i]);
+    }
+    try {
+      exit(args.length == 0 ? 0 : 40 + args.length);
+    } catch (Throwable t) {
+      System.out.println("caught");
+    } finally {
+      System.out.println("finally");
+    }
+    System.out.println("unreachable");
+  }
+
+  static void exit(int status) {
+    System.exit(status);
+  }
+}
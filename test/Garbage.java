@@ -0,0 +1,65 @@
+public class Garbage {
+
+  static class Node {
+    Node next;
+    int value;
+    Object payload;
+
+    Node(Node next, int value) {
+      this.next = next;
+      this.value = value;
+    }
+  }
+
+  // Reachable only from a static field.
+  static Node kept;
+
+  public static void main(String[] args) {
+    for (int i = 0; i < 100; i++) {
+      kept = new Node(kept, i);
+    }
+    // Reachable only from a local variable.
+    Object[] table = new Object[10];
+    for (int i = 0; i < table.length; i++) {
+      table[i] = String.valueOf(i);
+    }
+
+    // Allocate far more than the heap can hold at once.
+    long sum = 0;
+    for (int i = 0; i < 50000; i++) {
+      int[] garbage = new int[16];
+      garbage[0] = i;
+      Node temp = new Node(null, i);
+      temp.payload = garbage;
+      sum += temp.value + ((int[]) temp.payload)[0];
+    }
+    System.out.println(sum);
+
+    int total = 0;
+    for (Node node = kept; node != null; node = node.next) {
+      total += node.value;
+    }
+    System.out.println(total);
+    String joined = "";
+    for (int i = 0; i < table.length; i++) {
+      joined = joined.concat((String) table[i]);
+    }
+    System.out.println(joined);
+    System.gc();
+
+    // Hold on to everything allocated until the heap runs out.
+    Node hoard = null;
+    int count = 0;
+    try {
+      while (true) {
+        hoard = new Node(hoard, count);
+        hoard.payload = new long[64];
+        count++;
+      }
+    } catch (OutOfMemoryError e) {
+      hoard = null;
+      System.out.println(e.getMessage());
+    }
+    System.out.println(count > 1000);
+  }
+}
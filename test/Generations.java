@@ -0,0 +1,39 @@
+public class Generations {
+
+  static class Box {
+    Object value;
+    int number;
+
+    Box(int number) {
+      this.number = number;
+    }
+  }
+
+  static Object latest;
+
+  public static void main(String[] args) {
+    Box holder = new Box(0);
+    Object[] slots = new Object[8];
+    long sum = 0;
+    for (int round = 0; round < 20; round++) {
+      /*
+       * By now, the holder, the slots and this class's statics have
+       * been promoted, along with everything they refer to, so these
+       * are the only references from them to young objects.
+       */
+      holder.value = new Box(round);
+      slots[round % slots.length] = new Box(round);
+      latest = String.valueOf(round);
+
+      // Survive enough collections to be promoted in turn.
+      for (int i = 0; i < 5000; i++) {
+        int[] garbage = new int[64];
+        garbage[0] = i;
+      }
+      sum += ((Box) holder.value).number;
+      sum += ((Box) slots[round % slots.length]).number;
+    }
+    System.out.println(sum);
+    System.out.println(latest);
+  }
+}
@@ -0,0 +1,37 @@
+public class InitializerFailure {
+
+  static class Parent {
+    static int value = fail();
+
+    static int fail() {
+      throw new IllegalArgumentException("parent failed");
+    }
+  }
+
+  // The handler covers the start of the initializer, but mustn't see
+  // the failure of Parent's, which happens before this one starts.
+  static class Child extends Parent {
+    static int derived;
+
+    static {
+      try {
+        derived = value + 1;
+      } catch (Throwable t) {
+        derived = -1;
+      }
+    }
+  }
+
+  public static void main(String[] args) {
+    try {
+      System.out.println(Child.derived);
+    } catch (ExceptionInInitializerError e) {
+      System.out.println(e.getCause().getMessage());
+    }
+    try {
+      System.out.println(Child.derived);
+    } catch (NoClassDefFoundError e) {
+      System.out.println(e.getMessage());
+    }
+  }
+}
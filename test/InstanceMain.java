@@ -0,0 +1,5 @@
+public class InstanceMain {
+
+  public void main(String[] args) {
+  }
+}
@@ -0,0 +1,15 @@
+/*
+ * Shape is compiled as a concrete class here, but the Shape.class
+ * it runs with is abstract, as if it changed after this was compiled.
+ */
+public class Instantiation {
+
+  public static void main(String[] args) {
+    try {
+      new Shape();
+      System.out.println("instantiated");
+    } catch (InstantiationError e) {
+      System.out.println(e.getMessage());
+    }
+  }
+}
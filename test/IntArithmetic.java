@@ -0,0 +1,63 @@
+public class IntArithmetic {
+
+  static int arithmetic(int a, int b) {
+    int sum = a + b;
+    int difference = a - b;
+    int product = a * b;
+    int quotient = a / b;
+    int remainder = a % b;
+    int negated = -a;
+    int minValue = 1 << (b + 28);
+    int overflowed = minValue - a;
+    int minOverMinusOne = minValue / (b - 4);
+    int minRemMinusOne = minValue % -1;
+    System.out.println(overflowed);
+    System.out.println(minOverMinusOne);
+    System.out.println(minRemMinusOne);
+    return sum + difference + product + quotient + remainder + negated;
+  }
+
+  static int bitwise(int a, int b) {
+    int shiftedLeft = a << b;
+    int shiftedRight = -a >> b;
+    int unsignedShift = -a >>> b;
+    int masked = a << (b + 32);
+    System.out.println(shiftedRight);
+    System.out.println(unsignedShift);
+    System.out.println(masked);
+    return (shiftedLeft & shiftedRight) | (unsignedShift ^ masked);
+  }
+
+  static int conversions(int a) {
+    byte b = (byte) a;
+    char c = (char) a;
+    short s = (short) a;
+    System.out.println(b);
+    System.out.println((int) c);
+    System.out.println(s);
+    return b + c + s;
+  }
+
+  static int locals(int a) {
+    int i = a;
+    i++;
+    i += 100;
+    i -= 1000;
+    i += 20000;
+    int big = 30000;
+    int bigger = -129;
+    return i + big + bigger;
+  }
+
+  static int b(int value) {
+    return value;
+  }
+
+  public static void main(String[] args) {
+    System.out.println(arithmetic(7, 3));
+    System.out.println(bitwise(5, 2));
+    int result = conversions(300 * (b(233)));
+    System.out.println(result);
+    System.out.println(locals(result));
+  }
+}
@@ -0,0 +1,60 @@
+/*
+ * Links against classes that have changed since it was compiled
+ * (see LinkageTarget and LinkageShape), and one, LinkageGone,
+ * that has since been removed.
+ */
+public class Linkage {
+
+  interface Action {
+    void run();
+  }
+
+  static void attempt(Action action) {
+    try {
+      action.run();
+      System.out.println("linked");
+    } catch (LinkageError e) {
+      System.out.println(e.getClass().getName().concat(": ")
+          .concat(e.getMessage()));
+    }
+  }
+
+  public static void main(String[] args) {
+    attempt(new Action() {
+      public void run() {
+        LinkageTarget.count++;
+      }
+    });
+    attempt(new Action() {
+      public void run() {
+        System.out.println(new LinkageTarget().name);
+      }
+    });
+    attempt(new Action() {
+      public void run() {
+        new LinkageTarget().missing();
+      }
+    });
+    attempt(new Action() {
+      public void run() {
+        LinkageTarget.helper();
+      }
+    });
+    attempt(new Action() {
+      public void run() {
+        new LinkageGone();
+      }
+    });
+    attempt(new Action() {
+      public void run() {
+        LinkageShape shape = new LinkageSquare();
+        shape.area();
+      }
+    });
+    try {
+      new LinkageGone();
+    } catch (NoClassDefFoundError e) {
+      System.out.println("caught ".concat(e.getMessage()));
+    }
+  }
+}
@@ -0,0 +1,7 @@
+/*
+ * Since LinkageSquare was compiled against this interface, it has
+ * gained the method area.
+ */
+public interface LinkageShape {
+  int area();
+}
@@ -0,0 +1,2 @@
+public class LinkageSquare implements LinkageShape {
+}
@@ -0,0 +1,10 @@
+/*
+ * The class Linkage links against, as changed since Linkage was
+ * compiled against a version with a static field count, a field
+ * name, a static method helper and a method missing.
+ */
+public class LinkageTarget {
+
+  void helper() {
+  }
+}
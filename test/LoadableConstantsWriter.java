@@ -0,0 +1,135 @@
+import java.io.DataOutputStream;
+import java.io.FileOutputStream;
+import java.io.IOException;
+
+/*
+ * Writes LoadableConstants.class, whose static methods each load a
+ * constant that javac never emits an ldc for: a dynamically computed
+ * constant bootstrapped by Condy.Bootstrap.bootstrap, a MethodType
+ * and a MethodHandle. The class file is put together by hand; run
+ * this from the test directory to regenerate it.
+ */
+public class LoadableConstantsWriter {
+
+  public static void main(String[] args) throws IOException {
+    DataOutputStream out = new DataOutputStream(
+        new FileOutputStream("LoadableConstants.class"));
+    out.writeInt(0xcafebabe);
+    out.writeShort(0);
+    out.writeShort(55);
+
+    out.writeShort(34);
+    utf8(out, "LoadableConstants");                              // 1
+    classRef(out, 1);                                            // 2
+    utf8(out, "java/lang/Object");                               // 3
+    classRef(out, 3);                                            // 4
+    utf8(out, "value");                                          // 5
+    utf8(out, "()Ljava/lang/Object;");                           // 6
+    utf8(out, "Code");                                           // 7
+    utf8(out, "Condy$Bootstrap");                                // 8
+    classRef(out, 8);                                            // 9
+    utf8(out, "bootstrap");                                      // 10
+    utf8(out, "(Ljava/lang/invoke/MethodHandles$Lookup;"
+        + "Ljava/lang/String;Ljava/lang/Class;Ljava/lang/String;)"
+        + "Ljava/lang/Object;");                                 // 11
+    nameAndType(out, 10, 11);                                    // 12
+    memberRef(out, 10, 9, 12);                                   // 13
+    methodHandle(out, 6, 13); // REF_invokeStatic                // 14
+    utf8(out, "Ljava/lang/Object;");                             // 15
+    nameAndType(out, 5, 15);                                     // 16
+    out.writeByte(17);                                           // 17
+    out.writeShort(0);
+    out.writeShort(16);
+    utf8(out, "BootstrapMethods");                               // 18
+    utf8(out, "argument");                                       // 19
+    out.writeByte(8);                                            // 20
+    out.writeShort(19);
+    utf8(out, "methodType");                                     // 21
+    utf8(out, "()Ljava/lang/invoke/MethodType;");                // 22
+    utf8(out, "(I[Ljava/lang/String;)J");                        // 23
+    out.writeByte(16);                                           // 24
+    out.writeShort(23);
+    utf8(out, "lengthHandle");                                   // 25
+    utf8(out, "()Ljava/lang/invoke/MethodHandle;");              // 26
+    utf8(out, "java/lang/String");                               // 27
+    classRef(out, 27);                                           // 28
+    utf8(out, "length");                                         // 29
+    utf8(out, "()I");                                            // 30
+    nameAndType(out, 29, 30);                                    // 31
+    memberRef(out, 10, 28, 31);                                  // 32
+    methodHandle(out, 5, 32); // REF_invokeVirtual               // 33
+
+    out.writeShort(0x0021); // ACC_PUBLIC | ACC_SUPER
+    out.writeShort(2);
+    out.writeShort(4);
+    out.writeShort(0);
+    out.writeShort(0);
+
+    out.writeShort(3);
+    loadConstant(out, 5, 6, 17);
+    loadConstant(out, 21, 22, 24);
+    loadConstant(out, 25, 26, 33);
+
+    out.writeShort(1);
+    out.writeShort(18);
+    out.writeInt(8);
+    out.writeShort(1);
+    out.writeShort(14);
+    out.writeShort(1);
+    out.writeShort(20);
+    out.close();
+  }
+
+  // A public static method that returns the constant at an index.
+  private static void loadConstant(DataOutputStream out, int nameIdx,
+                                   int descriptorIdx, int constantIdx)
+      throws IOException {
+    out.writeShort(0x0009); // ACC_PUBLIC | ACC_STATIC
+    out.writeShort(nameIdx);
+    out.writeShort(descriptorIdx);
+    out.writeShort(1);
+    out.writeShort(7);
+    out.writeInt(15);
+    out.writeShort(1); // max_stack
+    out.writeShort(0); // max_locals
+    out.writeInt(3);
+    out.writeByte(0x12); // ldc
+    out.writeByte(constantIdx);
+    out.writeByte(0xb0); // areturn
+    out.writeShort(0);
+    out.writeShort(0);
+  }
+
+  private static void utf8(DataOutputStream out, String value)
+      throws IOException {
+    out.writeByte(1);
+    out.writeUTF(value);
+  }
+
+  private static void classRef(DataOutputStream out, int nameIdx)
+      throws IOException {
+    out.writeByte(7);
+    out.writeShort(nameIdx);
+  }
+
+  private static void nameAndType(DataOutputStream out, int nameIdx,
+                                  int typeIdx) throws IOException {
+    out.writeByte(12);
+    out.writeShort(nameIdx);
+    out.writeShort(typeIdx);
+  }
+
+  private static void memberRef(DataOutputStream out, int tag, int classIdx,
+                                int nameAndTypeIdx) throws IOException {
+    out.writeByte(tag);
+    out.writeShort(classIdx);
+    out.writeShort(nameAndTypeIdx);
+  }
+
+  private static void methodHandle(DataOutputStream out, int kind,
+                                   int referenceIdx) throws IOException {
+    out.writeByte(15);
+    out.writeByte(kind);
+    out.writeShort(referenceIdx);
+  }
+}
@@ -0,0 +1,31 @@
+interface ThreeSupplier {
+  int get();
+}
+
+public class MethodInvocation implements ThreeSupplier {
+
+  public MethodInvocation() {
+    int result = viaVirtual();
+    result = viaInterface(this);
+  }
+
+  public int get() {
+    return three();
+  }
+
+  int viaVirtual() {
+    return get();
+  }
+
+  static int three() {
+    return 3;
+  }
+
+  static int viaInterface(ThreeSupplier supplier) {
+    return supplier.get();
+  }
+
+  public static void main(String[] args) {
+    int result = three();
+  }
+}
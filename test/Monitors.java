@@ -0,0 +1,90 @@
+public class Monitors {
+
+  private int count;
+
+  synchronized void increment() {
+    count++;
+    // Only the owner of the monitor may notify.
+    notify();
+  }
+
+  synchronized void fail() {
+    throw new RuntimeException("failed");
+  }
+
+  static synchronized void checkStatic() {
+    Monitors.class.notifyAll();
+  }
+
+  static void notifyUnowned(Object lock) {
+    try {
+      lock.notify();
+      System.out.println("owned");
+    } catch (IllegalMonitorStateException e) {
+      System.out.println(e.getMessage());
+    }
+  }
+
+  public static void main(String[] args) throws InterruptedException {
+    Monitors monitors = new Monitors();
+    notifyUnowned(monitors);
+
+    // Monitors are reentrant.
+    synchronized (monitors) {
+      synchronized (monitors) {
+        monitors.increment();
+      }
+      notifyUnowned(monitors);
+    }
+    notifyUnowned(monitors);
+    checkStatic();
+
+    // Synchronized methods and blocks release the monitor on throwing.
+    try {
+      monitors.fail();
+    } catch (RuntimeException e) {
+      System.out.println(e.getMessage());
+    }
+    notifyUnowned(monitors);
+    try {
+      synchronized (monitors) {
+        monitors.fail();
+      }
+    } catch (RuntimeException e) {
+      notifyUnowned(monitors);
+    }
+
+    // Waiting releases the monitor and then takes it back as it was.
+    Object lock = new Object();
+    synchronized (lock) {
+      synchronized (lock) {
+        lock.wait(5);
+        lock.wait(1, 500);
+      }
+      notifyUnowned(lock);
+    }
+    try {
+      lock.wait();
+    } catch (IllegalMonitorStateException e) {
+      System.out.println(e.getMessage());
+    }
+    try {
+      synchronized (lock) {
+        lock.wait(-1);
+      }
+    } catch (IllegalArgumentException e) {
+      System.out.println(e.getMessage());
+    }
+    notifyUnowned(lock);
+
+    Object missing = null;
+    try {
+      synchronized (missing) {
+        System.out.println("entered");
+      }
+    } catch (NullPointerException e) {
+      System.out.println("NullPointerException");
+    }
+    System.out.println(monitors.count);
+  }
+}
@@ -0,0 +1,42 @@
+public class Natives {
+
+  private final String name;
+
+  Natives() {
+    this("unnamed");
+  }
+
+  Natives(String name) {
+    this.name = name;
+  }
+
+  static native long add(int a, long b);
+
+  static native String greet(String name);
+
+  static native char initial(String name, boolean upper);
+
+  native String describe(double weight);
+
+  static native void fail(int code);
+
+  static native void missing(int[] values, String label);
+
+  public static void main(String[] args) {
+    System.out.println(add(40, 2000000000000L));
+    System.out.println(greet("native"));
+    System.out.println(initial("rust", true));
+    System.out.println(new Natives("crate").describe(2.5));
+    try {
+      fail(7);
+    } catch (ArithmeticException e) {
+      System.out.println(e.getMessage());
+    }
+    try {
+      missing(null, "x");
+    } catch (UnsatisfiedLinkError e) {
+      System.out.println(e);
+    }
+    missing(new int[0], "y");
+  }
+}
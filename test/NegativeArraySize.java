@@ -0,0 +1,10 @@
+public class NegativeArraySize {
+
+  static int size(int count) {
+    return count - 3;
+  }
+
+  public static void main(String[] args) {
+    char[][] grid = new char[2][size(1)];
+  }
+}
@@ -0,0 +1,16 @@
+public class NullArrayReturn {
+
+  static int[][] grid;
+
+  static int[] row(int idx) {
+    return grid == null ? null : grid[idx];
+  }
+
+  public static void main(String[] args) {
+    grid = new int[2][];
+    grid[1] = new int[3];
+    int i = 1;
+    int total = grid[i].length;
+    total += row(0)[1];
+  }
+}
@@ -0,0 +1,17 @@
+public class NullFieldChain {
+
+  static class Node {
+    Node next;
+    int value;
+  }
+
+  static int third(Node node) {
+    return node.next.next.value;
+  }
+
+  public static void main(String[] args) {
+    Node head = new Node();
+    head.next = new Node();
+    third(head);
+  }
+}
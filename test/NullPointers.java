@@ -0,0 +1,63 @@
+public class NullPointers {
+
+  static class Node {
+    int value;
+    Node next;
+    int[] values;
+
+    Node(int value) {
+      this.value = value;
+    }
+
+    int doubled() {
+      return value * 2;
+    }
+  }
+
+  static Node shared;
+
+  static Node find(Node node, int value) {
+    while (node != null && node.value != value) {
+      node = node.next;
+    }
+    return node;
+  }
+
+  public static void main(String[] args) {
+    Node head = new Node(1);
+    head.next = new Node(2);
+    int caught = 0;
+
+    try {
+      caught += find(head, 3).value;
+    } catch (NullPointerException e) {
+      caught += 1;
+    }
+    try {
+      head.next.next.doubled();
+    } catch (NullPointerException e) {
+      caught += 10;
+    }
+    try {
+      shared.value = 4;
+    } catch (NullPointerException e) {
+      caught += 100;
+    }
+    try {
+      caught += head.values.length;
+    } catch (NullPointerException e) {
+      caught += 1000;
+    }
+    try {
+      head.values[0] = 7;
+    } catch (NullPointerException e) {
+      caught += 10000;
+    }
+    try {
+      throw null;
+    } catch (NullPointerException e) {
+      caught += 20000;
+    }
+    int result = caught + find(head, 2).doubled();
+  }
+}
@@ -0,0 +1,20 @@
+public class NullReceiver {
+
+  static class Greeter {
+    int greet(int times, long[] counts) {
+      return times;
+    }
+  }
+
+  static Greeter lookup(int key) {
+    if (key > 0) {
+      return new Greeter();
+    }
+    return null;
+  }
+
+  public static void main(String[] args) {
+    Greeter greeter = lookup(0);
+    greeter.greet(3, null);
+  }
+}
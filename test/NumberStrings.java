@@ -0,0 +1,11 @@
+public class NumberStrings {
+
+  // String.valueOf is native, so each string is created by the VM.
+  public static void main(String[] args) {
+    String last = null;
+    for (int i = 0; i < 100000; i++) {
+      last = String.valueOf(i);
+    }
+    System.out.println(last);
+  }
+}
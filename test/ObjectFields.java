@@ -0,0 +1,141 @@
+public class ObjectFields {
+
+  static class Point {
+    int x;
+    int y;
+
+    Point(int x, int y) {
+      this.x = x;
+      this.y = y;
+    }
+
+    int manhattan() {
+      return Math2.abs(x) + Math2.abs(y);
+    }
+  }
+
+  static class Point3 extends Point {
+    int z;
+    // Hides Point.x; both are present in each instance.
+    long x;
+
+    Point3(int x, int y, int z) {
+      super(x, y);
+      this.z = z;
+      this.x = (long) x * 1000000000L;
+    }
+
+    int manhattan() {
+      return super.manhattan() + Math2.abs(z);
+    }
+
+    int inheritedX() {
+      return ((Point) this).x;
+    }
+  }
+
+  static class Math2 {
+    static int abs(int value) {
+      return value < 0 ? -value : value;
+    }
+  }
+
+  static class Node {
+    int value;
+    Node next;
+  }
+
+  static class Defaults {
+    boolean flag;
+    byte b;
+    char c;
+    short s;
+    int i;
+    long l;
+    float f;
+    double d;
+    Object o;
+  }
+
+  int counter;
+
+  static Node buildList(int length) {
+    Node head = null;
+    for (int i = length; i > 0; i--) {
+      Node node = new Node();
+      node.value = i;
+      node.next = head;
+      head = node;
+    }
+    return head;
+  }
+
+  static int sum(Node node) {
+    int total = 0;
+    while (node != null) {
+      total += node.value;
+      node = node.next;
+    }
+    return total;
+  }
+
+  static int defaults() {
+    Defaults defaults = new Defaults();
+    int result = 0;
+    if (!defaults.flag) result |= 1;
+    if (defaults.b == 0 && defaults.c == 0 && defaults.s == 0) result |= 2;
+    if (defaults.i == 0 && defaults.l == 0L) result |= 4;
+    if (defaults.f == 0.0f && defaults.d == 0.0) result |= 8;
+    if (defaults.o == null) result |= 16;
+    return result;
+  }
+
+  static int narrowing(int b, int c, int s) {
+    Defaults defaults = new Defaults();
+    defaults.b = (byte) b;
+    defaults.c = (char) c;
+    defaults.s = (short) (s * 3);
+    defaults.flag = true;
+    return defaults.b + defaults.c + defaults.s + (defaults.flag ? 1 : 0);
+  }
+
+  static long hiding() {
+    Point3 point = new Point3(-3, 4, -5);
+    return point.x + point.inheritedX() + point.manhattan();
+  }
+
+  int increment() {
+    return ++counter;
+  }
+
+  public static void main(String[] args) {
+    System.out.println(sum(buildList(10)));
+    System.out.println(defaults());
+    System.out.println(narrowing(200, -1, 0x7fff));
+    System.out.println(hiding());
+
+    Defaults defaults = new Defaults();
+    System.out.println(defaults.flag);
+    System.out.println(defaults.b);
+    System.out.println((int) defaults.c);
+    System.out.println(defaults.s);
+    System.out.println(defaults.i);
+    System.out.println(defaults.l);
+    System.out.println(defaults.f);
+    System.out.println(defaults.d);
+    System.out.println(defaults.o);
+
+    // Each field of the subclass and its superclass keeps its value.
+    Point3 point = new Point3(7, -8, 9);
+    System.out.println(point.x);
+    System.out.println(point.inheritedX());
+    System.out.println(point.y);
+    System.out.println(point.z);
+    System.out.println(((Point) point).manhattan());
+
+    ObjectFields fields = new ObjectFields();
+    fields.increment();
+    System.out.println(fields.increment());
+    System.out.println(new ObjectFields().counter);
+  }
+}
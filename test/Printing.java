@@ -0,0 +1,53 @@
+public class Printing {
+
+  static class Point {
+    private final int x;
+    private final int y;
+
+    Point(int x, int y) {
+      this.x = x;
+      this.y = y;
+    }
+
+    public String toString() {
+      return "Point".concat(String.valueOf(x)).concat(",")
+          .concat(String.valueOf(y));
+    }
+  }
+
+  static class Failure extends RuntimeException {
+    Failure(String message) {
+      super(message);
+    }
+  }
+
+  public static void main(String[] args) {
+    System.out.println(true);
+    System.out.println('λ');
+    System.out.println(-42);
+    System.out.println(Long.MIN_VALUE);
+    System.out.println(1.5f);
+    System.out.println(0.1f + 0.2f);
+    System.out.println(0.1 + 0.2);
+    System.out.println(100.0);
+    System.out.println(1e7);
+    System.out.println(12345678.9);
+    System.out.println(0.001);
+    System.out.println(0.0001);
+    System.out.println(-0.0);
+    System.out.println(1.0 / 0);
+    System.out.println(0.0f / 0);
+    System.out.println(Double.MAX_VALUE);
+    System.out.println(Float.MIN_VALUE);
+    System.out.println(new char[] {'o', 'k'});
+    System.out.println("日本語");
+    System.out.println((String) null);
+    System.out.println((Object) null);
+    System.out.println(new Point(3, -4));
+    System.out.println(new Failure("bad input"));
+    System.out.print("no newline, ");
+    System.out.print(7);
+    System.out.println();
+    System.err.println("to stderr");
+  }
+}
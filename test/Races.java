@@ -0,0 +1,56 @@
+public class Races {
+
+  static int count;
+  static final Object first = new Object();
+  static final Object second = new Object();
+
+  // Increments the count without synchronizing, so updates can be lost.
+  static class Incrementer extends Thread {
+    public void run() {
+      for (int i = 0; i < 100; i++) {
+        count++;
+      }
+    }
+  }
+
+  // Takes the two locks in the given order, which can deadlock.
+  static class Locker extends Thread {
+    final Object outer;
+    final Object inner;
+
+    Locker(Object outer, Object inner) {
+      this.outer = outer;
+      this.inner = inner;
+    }
+
+    public void run() {
+      for (int i = 0; i < 10; i++) {
+        synchronized (outer) {
+          synchronized (inner) {
+            count++;
+          }
+        }
+      }
+    }
+  }
+
+  public static void main(String[] args) throws InterruptedException {
+    Thread a;
+    Thread b;
+    if (args.length > 0 && args[0].equals("deadlock")) {
+      a = new Locker(first, second);
+      b = new Locker(second, first);
+    } else {
+      a = new Incrementer();
+      b = new Incrementer();
+    }
+    a.start();
+    b.start();
+    a.join();
+    b.join();
+    System.out.println(count);
+    if (count != 200 && count != 20) {
+      throw new RuntimeException("lost updates");
+    }
+  }
+}
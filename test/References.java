@@ -0,0 +1,100 @@
+import java.lang.ref.Cleaner;
+import java.lang.ref.PhantomReference;
+import java.lang.ref.Reference;
+import java.lang.ref.ReferenceQueue;
+import java.lang.ref.SoftReference;
+import java.lang.ref.WeakReference;
+import java.util.WeakHashMap;
+
+public class References {
+
+  static class Resurrected {
+    final String name;
+
+    Resurrected(String name) {
+      this.name = name;
+    }
+
+    protected void finalize() {
+      System.out.println("finalized ".concat(name));
+      saved = this;
+    }
+  }
+
+  static Resurrected saved;
+
+  static class Action implements Runnable {
+    public void run() {
+      System.out.println("cleaned");
+    }
+  }
+
+  public static void main(String[] args) throws InterruptedException {
+    // Weak references are cleared and enqueued once the referent is gone.
+    ReferenceQueue<Object> queue = new ReferenceQueue<Object>();
+    Object kept = new Object();
+    WeakReference<Object> strong = new WeakReference<Object>(kept, queue);
+    WeakReference<Object> weak =
+        new WeakReference<Object>(new Object(), queue);
+    System.gc();
+    System.out.println(strong.get() == kept);
+    System.out.println(weak.get() == null);
+    System.out.println(weak.isEnqueued());
+    System.out.println(queue.poll() == weak);
+    System.out.println(queue.poll() == null);
+
+    // Soft references survive until memory runs short.
+    SoftReference<long[]> soft = new SoftReference<long[]>(new long[20000]);
+    System.gc();
+    System.out.println(soft.get() != null);
+    Object[] hoard = null;
+    try {
+      while (true) {
+        Object[] next = new Object[1000];
+        next[0] = hoard;
+        hoard = next;
+      }
+    } catch (OutOfMemoryError e) {
+      hoard = null;
+      System.out.println(e.getMessage());
+    }
+    System.out.println(soft.get() == null);
+
+    // Phantom references are enqueued, with nothing to get.
+    ReferenceQueue<Object> phantoms = new ReferenceQueue<Object>();
+    PhantomReference<Object> phantom =
+        new PhantomReference<Object>(new Object(), phantoms);
+    System.out.println(phantom.get() == null);
+    Reference<?> removed = phantoms.remove(1000);
+    System.out.println(removed == phantom);
+
+    // A cleaner runs its action once the object is unreachable, once.
+    Cleaner cleaner = Cleaner.create();
+    cleaner.register(new Object(), new Action());
+    System.gc();
+    Cleaner.Cleanable cleanable = cleaner.register(kept, new Action());
+    cleanable.clean();
+    cleanable.clean();
+
+    // An object is finalized once, even if its finalizer resurrects it.
+    new Resurrected("once");
+    System.gc();
+    System.out.println(saved.name);
+    saved = null;
+    System.gc();
+    System.out.println(saved == null);
+
+    // Weak hash maps drop the entries whose keys are gone.
+    WeakHashMap<Object, String> cache = new WeakHashMap<Object, String>();
+    cache.put(kept, "kept");
+    for (int i = 0; i < 100; i++) {
+      cache.put(new Object(), String.valueOf(i));
+    }
+    cache.put(null, "null");
+    System.out.println(cache.size());
+    System.gc();
+    System.out.println(cache.size());
+    System.out.println(cache.get(kept));
+    System.out.println(cache.get(null));
+  }
+}
@@ -0,0 +1,29 @@
+public class StackOverflow {
+
+  static int depth;
+
+  public static void main(String[] args) {
+    try {
+      recurse();
+    } catch (StackOverflowError e) {
+      System.out.println(depth > 100);
+    }
+    // The stack unwound, so there's room again.
+    depth = 0;
+    recurse(50);
+    System.out.println(depth);
+    recurse();
+  }
+
+  static void recurse() {
+    depth++;
+    recurse();
+  }
+
+  static void recurse(int count) {
+    depth++;
+    if (count > 1) {
+      recurse(count - 1);
+    }
+  }
+}
@@ -0,0 +1,75 @@
+public class StackTraces {
+
+  static class Failure extends RuntimeException {
+  }
+
+  static class Broken {
+    static int value = 1 / zero();
+  }
+
+  static int zero() {
+    return 0;
+  }
+
+  static void fail(int depth) {
+    if (depth == 0) {
+      throw new Failure();
+    }
+    fail(depth - 1);
+  }
+
+  static int element(int[] values, int idx) {
+    return values[idx];
+  }
+
+  static void check(boolean condition) {
+    if (!condition) {
+      throw new Error();
+    }
+  }
+
+  static void checkName(String name, char first, int length) {
+    check(name.length() == length);
+    check(name.charAt(0) == first);
+  }
+
+  public static void main(String[] args) {
+    try {
+      fail(2);
+    } catch (Failure e) {
+      StackTraceElement[] trace = e.getStackTrace();
+      check(trace.length == 4);
+      check(trace[0].getLineNumber() == 16);
+      check(trace[1].getLineNumber() == 18);
+      check(trace[2].getLineNumber() == 18);
+      check(trace[3].getLineNumber() == 38);
+      checkName(trace[0].getMethodName(), 'f', 4);
+      checkName(trace[3].getMethodName(), 'm', 4);
+      checkName(trace[0].getClassName(), 'S', 11);
+      checkName(trace[0].getFileName(), 'S', 16);
+    }
+
+    try {
+      element(new int[2], 2);
+    } catch (ArrayIndexOutOfBoundsException e) {
+      StackTraceElement[] trace = e.getStackTrace();
+      check(trace.length == 2);
+      check(trace[0].getLineNumber() == 22);
+      checkName(trace[0].getMethodName(), 'e', 7);
+      check(trace[1].getLineNumber() == 53);
+    }
+
+    try {
+      check(Broken.value == 0);
+    } catch (ExceptionInInitializerError e) {
+      StackTraceElement[] trace = e.getStackTrace();
+      check(trace.length == 1);
+      check(trace[0].getLineNumber() == 63);
+      trace = e.getCause().getStackTrace();
+      check(trace.length == 2);
+      checkName(trace[0].getMethodName(), '<', 8);
+      check(trace[0].getLineNumber() == 7);
+      check(trace[1].getLineNumber() == 63);
+    }
+  }
+}
@@ -0,0 +1,87 @@
+public class StaticFields {
+
+  static class Log {
+    static long order;
+
+    static void record(int step) {
+      System.out.println(step);
+      order = order * 10 + step;
+    }
+  }
+
+  interface Table {
+    int[] SQUARES = squares(5);
+    int LIMIT = 5;
+
+    default int lookup(int i) {
+      return SQUARES[i];
+    }
+  }
+
+  static int[] squares(int count) {
+    Log.record(1);
+    int[] squares = new int[count];
+    for (int i = 0; i < count; i++) {
+      squares[i] = i * i;
+    }
+    return squares;
+  }
+
+  static class Parent {
+    static int base;
+    static double scale;
+
+    static {
+      Log.record(2);
+      base = 40;
+      scale = 0.5;
+    }
+  }
+
+  static class Child extends Parent implements Table {
+    // Set from ConstantValue attributes, which javac inlines at uses.
+    static final String NAME = "child";
+    static final long BIG = 1L << 40;
+    static final double HALF = 0.5;
+    static int derived;
+    static Child instance;
+
+    static {
+      Log.record(3);
+      derived = base + 2;
+    }
+
+    int tableValue(int i) {
+      return lookup(i);
+    }
+  }
+
+  static int counter;
+  static long total;
+  static boolean flag;
+  static byte small;
+
+  static int next() {
+    return ++counter;
+  }
+
+  public static void main(String[] args) {
+    for (int i = 0; i < 5; i++) {
+      total += next();
+    }
+    flag = counter > 3;
+    small = (byte) (counter * 50);
+
+    Child.instance = new Child();
+    System.out.println(Log.order);
+    System.out.println(Child.derived);
+    System.out.println(Child.base);
+    System.out.println(Child.SQUARES[3] + Child.instance.tableValue(4));
+    System.out.println(Child.scale);
+    System.out.println(Table.LIMIT);
+    System.out.println(counter);
+    System.out.println(total);
+    System.out.println(flag);
+    System.out.println(small);
+  }
+}
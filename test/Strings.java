@@ -0,0 +1,49 @@
+public class Strings {
+
+  static int checksum(String value) {
+    int sum = 0;
+    for (int i = 0; i < value.length(); i++) {
+      sum = sum * 31 + value.charAt(i);
+    }
+    return sum;
+  }
+
+  static String latin() {
+    return new String(new char[] {'c', 'a', 'f', 'é'});
+  }
+
+  static String wide() {
+    return new String(new char[] {'λ', 'x', '☃'});
+  }
+
+  public static void main(String[] args) {
+    String latin = latin();
+    String wide = wide();
+    String empty = new String();
+
+    int contents = checksum(latin) + checksum(wide) + checksum("über")
+        + checksum("日本") + latin.length() * 10 + wide.length()
+        + (empty.isEmpty() ? 100 : 0) + ("".isEmpty() ? 1000 : 0);
+
+    int equality = (latin.equals("café") ? 1 : 0)
+        + (wide.equals("λx☃") ? 2 : 0)
+        + (latin.equals(wide) ? 4 : 0)
+        + (latin.equals("cafe") ? 8 : 0)
+        + (latin == "café" ? 16 : 0)
+        + (latin.hashCode() == "café".hashCode() ? 32 : 0)
+        + (wide.hashCode() == checksum(wide) ? 64 : 0);
+
+    String unique = new String(new char[] {'q', 'u', 'i', 'r', 'k'});
+    int interning = (latin.intern() == "café" ? 1 : 0)
+        + (wide.intern() == "λx☃" ? 2 : 0)
+        + (unique.intern() == unique ? 4 : 0)
+        + (new String(new char[] {'q', 'u', 'i', 'r', 'k'}).intern() == unique
+            ? 8 : 0)
+        + ("quirk" == unique ? 16 : 0)
+        + (latin.intern() == latin ? 32 : 0);
+
+    System.out.println(contents);
+    System.out.println(equality);
+    System.out.println(interning);
+  }
+}
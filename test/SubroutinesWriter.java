@@ -0,0 +1,77 @@
+import java.io.DataOutputStream;
+import java.io.FileOutputStream;
+import java.io.IOException;
+
+/*
+ * Writes Subroutines.class, whose quadruple(int) method doubles its
+ * argument twice by calling a subroutine with jsr and then jsr_w.
+ * javac stopped emitting jsr and ret long ago, so the class file is
+ * put together by hand; run this from the test directory to
+ * regenerate it.
+ */
+public class SubroutinesWriter {
+
+  public static void main(String[] args) throws IOException {
+    DataOutputStream out =
+        new DataOutputStream(new FileOutputStream("Subroutines.class"));
+    out.writeInt(0xcafebabe);
+    out.writeShort(0);
+    out.writeShort(49);
+
+    out.writeShort(8);
+    utf8(out, "Subroutines");                                    // 1
+    classRef(out, 1);                                            // 2
+    utf8(out, "java/lang/Object");                               // 3
+    classRef(out, 3);                                            // 4
+    utf8(out, "quadruple");                                      // 5
+    utf8(out, "(I)I");                                           // 6
+    utf8(out, "Code");                                           // 7
+
+    out.writeShort(0x0021); // ACC_PUBLIC | ACC_SUPER
+    out.writeShort(2);
+    out.writeShort(4);
+    out.writeShort(0);
+    out.writeShort(0);
+
+    out.writeShort(1);
+    out.writeShort(0x0009); // ACC_PUBLIC | ACC_STATIC
+    out.writeShort(5);
+    out.writeShort(6);
+    out.writeShort(1);
+    out.writeShort(7);
+    out.writeInt(29);
+    out.writeShort(2); // max_stack
+    out.writeShort(2); // max_locals
+    out.writeInt(17);
+    out.writeByte(0xa8); // 0: jsr 10
+    out.writeShort(10);
+    out.writeByte(0xc9); // 3: jsr_w 10
+    out.writeInt(7);
+    out.writeByte(0x1a); // 8: iload_0
+    out.writeByte(0xac); // 9: ireturn
+    out.writeByte(0x4c); // 10: astore_1
+    out.writeByte(0x1a); // 11: iload_0
+    out.writeByte(0x1a); // 12: iload_0
+    out.writeByte(0x60); // 13: iadd
+    out.writeByte(0x3b); // 14: istore_0
+    out.writeByte(0xa9); // 15: ret 1
+    out.writeByte(1);
+    out.writeShort(0);
+    out.writeShort(0);
+
+    out.writeShort(0);
+    out.close();
+  }
+
+  private static void utf8(DataOutputStream out, String value)
+      throws IOException {
+    out.writeByte(1);
+    out.writeUTF(value);
+  }
+
+  private static void classRef(DataOutputStream out, int nameIdx)
+      throws IOException {
+    out.writeByte(7);
+    out.writeShort(nameIdx);
+  }
+}
@@ -0,0 +1,38 @@
+import java.util.Properties;
+
+public class SystemProperties {
+  public static void main(String[] args) {
+    System.out.println(System.getProperty("greeting"));
+    System.out.println(System.getProperty("missing"));
+    System.out.println(System.getProperty("missing", "default"));
+    System.out.println(System.setProperty("greeting", "goodbye"));
+    System.out.println(System.getProperty("greeting"));
+    System.out.println(System.clearProperty("set.by.host"));
+    System.out.println(System.getProperty("set.by.host", "cleared"));
+    System.out.println(System.getProperty("java.version"));
+    System.out.println(System.getProperty("java.class.path"));
+    System.out.println(System.getProperty("user.dir") != null);
+
+    Properties props = System.getProperties();
+    System.out.println(props.getProperty("greeting"));
+    props.setProperty("greeting", "changed");
+    System.out.println(props.getProperty("greeting"));
+    System.out.println(System.getProperty("greeting"));
+    System.out.println(props.containsKey("os.name"));
+
+    System.out.println(System.getenv("GREETING"));
+    System.out.println(System.getenv("MISSING"));
+
+    try {
+      System.getProperty(null);
+    } catch (NullPointerException e) {
+      System.out.println(e.getMessage());
+    }
+    try {
+      System.setProperty("", "value");
+    } catch (IllegalArgumentException e) {
+      System.out.println(e.getMessage());
+    }
+    System.setProperty("set.by.guest", "yes");
+  }
+}
@@ -0,0 +1,223 @@
+import java.util.concurrent.locks.LockSupport;
+
+public class Threads {
+
+  static int counter;
+
+  static synchronized void increment() {
+    counter++;
+  }
+
+  static class Counter implements Runnable {
+    public void run() {
+      for (int i = 0; i < 20000; i++) {
+        increment();
+      }
+    }
+  }
+
+  // A queue of one slot, handed between threads with wait and notify.
+  static class Slot {
+    private String value;
+
+    synchronized void put(String value) throws InterruptedException {
+      while (this.value != null) {
+        wait();
+      }
+      this.value = value;
+      notifyAll();
+    }
+
+    synchronized String take() throws InterruptedException {
+      while (value == null) {
+        wait();
+      }
+      String taken = value;
+      value = null;
+      notifyAll();
+      return taken;
+    }
+  }
+
+  static class Producer extends Thread {
+    final Slot slot;
+
+    Producer(Slot slot) {
+      this.slot = slot;
+    }
+
+    public void run() {
+      try {
+        for (int i = 0; i < 5; i++) {
+          slot.put("item ".concat(String.valueOf(i)));
+        }
+      } catch (InterruptedException e) {
+        System.out.println("producer interrupted");
+      }
+    }
+  }
+
+  static class Sleeper extends Thread {
+    public void run() {
+      try {
+        Thread.sleep(60000);
+        System.out.println("slept");
+      } catch (InterruptedException e) {
+        System.out.println(e.getMessage());
+        System.out.println(isInterrupted());
+      }
+    }
+  }
+
+  static class Parker extends Thread {
+    volatile boolean ready;
+
+    public void run() {
+      while (!ready) {
+        LockSupport.park();
+      }
+      System.out.println("unparked");
+    }
+  }
+
+  // Holds on to an array across the collections other threads cause.
+  static class Allocator extends Thread {
+    long total;
+
+    public void run() {
+      int[] kept = new int[100];
+      for (int i = 0; i < kept.length; i++) {
+        kept[i] = i;
+      }
+      for (int round = 0; round < 2000; round++) {
+        int[] garbage = new int[256];
+        garbage[0] = round;
+      }
+      for (int i = 0; i < kept.length; i++) {
+        total += kept[i];
+      }
+    }
+  }
+
+  static class Slow {
+    static final String VALUE;
+
+    static {
+      try {
+        Thread.sleep(50);
+      } catch (InterruptedException e) {
+      }
+      VALUE = "initialized";
+    }
+  }
+
+  static class Initializer extends Thread {
+    public void run() {
+      System.out.println(Slow.VALUE);
+    }
+  }
+
+  static class Failing extends Thread {
+    Failing() {
+      super("failing");
+    }
+
+    public void run() {
+      throw new RuntimeException("thrown in thread");
+    }
+  }
+
+  static class Spinner extends Thread {
+    public void run() {
+      while (true) {
+        counter++;
+      }
+    }
+  }
+
+  public static void main(String[] args) throws InterruptedException {
+    Thread main = Thread.currentThread();
+    System.out.println(main.getName());
+    System.out.println(main.getId());
+    System.out.println(main == Thread.currentThread());
+
+    // Synchronized methods keep increments from being lost.
+    Thread first = new Thread(new Counter());
+    Thread second = new Thread(new Counter());
+    System.out.println(first.getName());
+    System.out.println(first.isAlive());
+    first.start();
+    second.start();
+    first.join();
+    second.join();
+    System.out.println(counter);
+    System.out.println(first.isAlive());
+    try {
+      first.start();
+    } catch (IllegalThreadStateException e) {
+      System.out.println("started twice");
+    }
+
+    // Waiting and notifying hand values between threads.
+    Slot slot = new Slot();
+    Producer producer = new Producer(slot);
+    producer.start();
+    for (int i = 0; i < 5; i++) {
+      System.out.println(slot.take());
+    }
+    producer.join();
+
+    // Interrupting a sleeping thread wakes it.
+    Sleeper sleeper = new Sleeper();
+    sleeper.start();
+    Thread.sleep(20);
+    sleeper.interrupt();
+    sleeper.join();
+    Thread.currentThread().interrupt();
+    System.out.println(Thread.interrupted());
+    System.out.println(Thread.interrupted());
+
+    // Parked threads wait for their permit.
+    Parker parker = new Parker();
+    parker.start();
+    Thread.sleep(20);
+    parker.ready = true;
+    LockSupport.unpark(parker);
+    parker.join();
+
+    // Threads keep their own objects alive through collections.
+    Allocator[] allocators = new Allocator[3];
+    for (int i = 0; i < allocators.length; i++) {
+      allocators[i] = new Allocator();
+      allocators[i].start();
+    }
+    for (int i = 0; i < allocators.length; i++) {
+      allocators[i].join();
+      System.out.println(allocators[i].total);
+    }
+
+    // A class is initialized once, with other threads waiting for it.
+    Initializer initializer = new Initializer();
+    initializer.start();
+    System.out.println(Slow.VALUE);
+    initializer.join();
+
+    // Exceptions that escape a thread are reported, and it dies.
+    Failing failing = new Failing();
+    failing.start();
+    failing.join();
+    System.out.println(failing.isAlive());
+
+    System.out.println(Thread.holdsLock(slot));
+    synchronized (slot) {
+      System.out.println(Thread.holdsLock(slot));
+    }
+
+    // Daemon threads don't keep the VM from exiting.
+    Spinner spinner = new Spinner();
+    spinner.setDaemon(true);
+    spinner.start();
+    System.out.println(spinner.isDaemon());
+    System.out.println("done");
+  }
+}
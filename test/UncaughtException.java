@@ -0,0 +1,27 @@
+public class UncaughtException {
+
+  static class Failure extends RuntimeException {
+  }
+
+  static int attempts;
+
+  static void attempt() {
+    try {
+      attempts++;
+      throw new Failure();
+    } finally {
+      attempts++;
+    }
+  }
+
+  public static void main(String[] args) {
+    try {
+      attempt();
+    } catch (IllegalArgumentExceptionLike e) {
+      attempts = -1;
+    }
+  }
+
+  static class IllegalArgumentExceptionLike extends RuntimeException {
+  }
+}
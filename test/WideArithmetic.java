@@ -0,0 +1,125 @@
+public class WideArithmetic {
+
+  long field;
+
+  static long longs(long a, int shift, long b) {
+    long sum = a + b;
+    long product = a * b;
+    long quotient = a / b;
+    long remainder = a % b;
+    long shifted = (a << shift) ^ (-a >> shift) ^ (-a >>> (shift + 64));
+    long masked = (a & b) | (a ^ b);
+    return sum - product + quotient + remainder + shifted + masked - -a;
+  }
+
+  static double doubles(double a, int b, double c) {
+    double sum = a + b;
+    double quotient = a / b;
+    double remainder = -a % c;
+    double infinity = a / (b - b);
+    return sum * quotient - remainder + 1 / infinity;
+  }
+
+  static float floats(float a, float b) {
+    return (a + b) * (a - b) / b % a + -a;
+  }
+
+  static int conversions(double d, float f, long l) {
+    int saturated = (int) (d * d * d * d * d * d);
+    long saturatedLong = (long) (-d * d * d * d * d * d * d * d * d);
+    int fromNaN = (int) (f / 0 - f / 0);
+    long fromFloat = (long) f;
+    double fromLong = (double) l;
+    float narrowed = (float) d;
+    return saturated + (int) saturatedLong + fromNaN + (int) fromFloat
+        + (int) fromLong + (int) narrowed + (int) l;
+  }
+
+  static double stackShuffling(int unused, long a, double b) {
+    long x = a;
+    double y = b;
+    x = a++ + a;
+    long z = x += 2;
+    double w = y += 1.5;
+    return x + y + z + w;
+  }
+
+  // The bits of a comparison of each kind, which are all false for NaN.
+  static int compare(long a, long b, float f, float g, double d, double e) {
+    int bits = 0;
+    if (a < b) bits |= 1;
+    if (a > b) bits |= 2;
+    if (a == b) bits |= 4;
+    if (f < g) bits |= 8;
+    if (f > g) bits |= 16;
+    if (f == g) bits |= 32;
+    if (d <= e) bits |= 64;
+    if (d >= e) bits |= 128;
+    if (d != e) bits |= 256;
+    return bits;
+  }
+
+  static void saturation(float f, double d) {
+    System.out.println((int) f);
+    System.out.println((long) f);
+    System.out.println((int) d);
+    System.out.println((long) d);
+    System.out.println((int) -d);
+    System.out.println((long) -d);
+  }
+
+  // Stores of longs and doubles whose values are also used duplicate
+  // them beneath an array reference and index, or an object.
+  static double categoryTwo(long[] longs, double[] doubles,
+                            WideArithmetic holder) {
+    long previous = longs[0]++;
+    double assigned = doubles[1] = 2.5;
+    long stored = holder.field = previous + 10;
+    longs(stored, 1, 3);
+    return previous + assigned + stored + longs[0];
+  }
+
+  static float ratio(int numerator, int denominator) {
+    return (float) numerator / denominator;
+  }
+
+  public static void main(String[] args) {
+    long l = longs(1234567890123L, 3, 10);
+    System.out.println(l);
+    System.out.println(longs(l, 1, 3));
+    System.out.println(longs(Long.MIN_VALUE, 63, -1));
+    System.out.println(doubles(7.5, 2, 2.25));
+    System.out.println(doubles(-7.5, 2, 2.25));
+    System.out.println(floats(ratio(7, 2), ratio(5, 4)));
+    System.out.println(conversions(12345.678, ratio(15, 4), 9876543210L));
+    System.out.println(stackShuffling(0, 5, 0.25));
+    System.out.println(ratio(1, 0));
+    System.out.println(ratio(-1, 0));
+    System.out.println(ratio(0, 0));
+    System.out.println(-0.0 * 5);
+    System.out.println(0.0f / -3);
+    System.out.println(-0.0 == 0.0);
+
+    float nanF = ratio(0, 0);
+    double nanD = nanF;
+    System.out.println(compare(1, 2, 1, 2, 1, 2));
+    System.out.println(compare(Long.MIN_VALUE, Long.MAX_VALUE, 2, 1, 2, 1));
+    System.out.println(compare(-5, -5, 0.0f, -0.0f, -0.0, 0.0));
+    System.out.println(compare(0, 0, nanF, 1, nanD, 1));
+    System.out.println(compare(0, 0, 1, nanF, 1, nanD));
+    System.out.println(compare(0, 0, nanF, nanF, nanD, nanD));
+
+    saturation(1e20f, 1e300);
+    saturation(nanF, nanD);
+    saturation(ratio(1, 0), 1.0 / 0);
+    saturation(-2.9f, -2.9);
+
+    long[] longs = {7};
+    double[] doubles = new double[2];
+    WideArithmetic holder = new WideArithmetic();
+    System.out.println(categoryTwo(longs, doubles, holder));
+    System.out.println(longs[0]);
+    System.out.println(doubles[1]);
+    System.out.println(holder.field);
+  }
+}
@@ -0,0 +1,17 @@
+package com.acme;
+
+import com.acme.util.Helper;
+
+public class Launcher {
+
+  public static void main(String[] args) {
+    assert args.length < 3 : "too many arguments";
+    for (int i = 0; i < args.length; i++) {
+      System.out.println(args[i]);
+    }
+    boolean enabled = false;
+    assert enabled = true;
+    System.out.println(enabled);
+    System.out.println(Helper.assertionsEnabled());
+  }
+}
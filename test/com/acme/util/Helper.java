@@ -0,0 +1,10 @@
+package com.acme.util;
+
+public class Helper {
+
+  public static boolean assertionsEnabled() {
+    boolean enabled = false;
+    assert enabled = true;
+    return enabled;
+  }
+}